@@ -0,0 +1,116 @@
+use notify_rust::Notification;
+use sc_log_analyzer::Alert;
+use std::{
+    path::Path,
+    process::Command,
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+/// Shows desktop notifications and plays alert sounds on a worker thread, so a slow
+/// notification daemon never stalls the UI.
+pub struct AlertNotifier {
+    tx: Sender<Alert>,
+    errors: Receiver<String>,
+}
+
+impl AlertNotifier {
+    pub fn spawn() -> Self {
+        let (tx, rx) = mpsc::channel::<Alert>();
+        let (error_tx, errors) = mpsc::channel();
+        std::thread::spawn(move || {
+            for alert in rx {
+                if let Some(sound) = &alert.sound {
+                    play_sound(Path::new(sound), error_tx.clone());
+                }
+                if alert.notify
+                    && let Err(err) = Notification::new()
+                        .appname("SC Log Analyzer")
+                        .summary(&alert.title)
+                        .body(&alert.body)
+                        .show()
+                    && error_tx
+                        .send(format!("Failed to show notification: {}", err))
+                        .is_err()
+                {
+                    return;
+                }
+            }
+        });
+        Self { tx, errors }
+    }
+
+    pub fn send(&self, alert: Alert) {
+        let _ = self.tx.send(alert);
+    }
+
+    /// The latest failure, if any happened since the last call.
+    pub fn take_error(&self) -> Option<String> {
+        self.errors.try_iter().last()
+    }
+}
+
+/// Plays `path` with the platform's own player, waiting for it on a separate thread so
+/// notifications are not held up by a long sound.
+fn play_sound(path: &Path, errors: Sender<String>) {
+    if !path.is_file() {
+        let _ = errors.send(format!("Sound file {} does not exist", path.display()));
+        return;
+    }
+    let path = path.to_path_buf();
+    std::thread::spawn(move || {
+        let mut last_error = None;
+        for mut command in sound_commands(&path) {
+            match command.status() {
+                Ok(status) if status.success() => return,
+                Ok(status) => last_error = Some(status.to_string()),
+                Err(err) => last_error = Some(err.to_string()),
+            }
+        }
+        let _ = errors.send(format!(
+            "Failed to play {}: {}",
+            path.display(),
+            last_error.unwrap_or_else(|| "no sound player found".to_string())
+        ));
+    });
+}
+
+/// Players to try in order. The first one that exits successfully wins.
+fn sound_commands(path: &Path) -> Vec<Command> {
+    if cfg!(target_os = "windows") {
+        // SoundPlayer only understands WAV files.
+        let quoted = path.to_string_lossy().replace('\'', "''");
+        let mut command = Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &format!("(New-Object Media.SoundPlayer '{}').PlaySync()", quoted),
+        ]);
+        hide_console(&mut command);
+        vec![command]
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("afplay");
+        command.arg(path);
+        vec![command]
+    } else {
+        let mut paplay = Command::new("paplay");
+        paplay.arg(path);
+        let mut aplay = Command::new("aplay");
+        aplay.arg("-q").arg(path);
+        let mut ffplay = Command::new("ffplay");
+        ffplay
+            .args(["-nodisp", "-autoexit", "-loglevel", "quiet"])
+            .arg(path);
+        vec![paplay, aplay, ffplay]
+    }
+}
+
+#[cfg(windows)]
+fn hide_console(command: &mut Command) {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    command.creation_flags(CREATE_NO_WINDOW);
+}
+
+#[cfg(not(windows))]
+fn hide_console(_command: &mut Command) {}
@@ -0,0 +1,296 @@
+use crate::event::{EventKind, PlayerEvent, describe_destroy_levels};
+use crate::patterns::PatternSet;
+use crate::query::{Query, QueryError};
+use crate::roster::{Roster, RosterList};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What an alert rule reacts to. "The player" is the log's primary nickname.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertTrigger {
+    /// The player was killed.
+    Killed,
+    /// The player killed someone else.
+    Kill,
+    /// Someone on the hostile list shows up in any event.
+    HostilePlayer,
+    /// A vehicle driven by the player reached at least this destroy level (1 soft, 2 hard).
+    VehicleDestroyed { level: u32 },
+    /// Any event matching a search query, e.g. `type:kill weapon:~railgun`.
+    Query { query: String },
+}
+
+impl AlertTrigger {
+    pub const KINDS: [AlertTrigger; 5] = [
+        AlertTrigger::Killed,
+        AlertTrigger::Kill,
+        AlertTrigger::HostilePlayer,
+        AlertTrigger::VehicleDestroyed { level: 2 },
+        AlertTrigger::Query {
+            query: String::new(),
+        },
+    ];
+
+    /// Whether `other` is the same kind of trigger, ignoring its settings.
+    pub fn same_kind(&self, other: &AlertTrigger) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl fmt::Display for AlertTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AlertTrigger::Killed => "I am killed",
+            AlertTrigger::Kill => "I kill someone",
+            AlertTrigger::HostilePlayer => "A hostile player appears",
+            AlertTrigger::VehicleDestroyed { .. } => "My vehicle is damaged",
+            AlertTrigger::Query { .. } => "An event matches a query",
+        })
+    }
+}
+
+/// A trigger and what to do when it fires.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertRule {
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: AlertTrigger,
+    /// Show a desktop notification.
+    pub notify: bool,
+    /// Sound file to play.
+    pub sound: Option<String>,
+    /// Minimum time between two alerts of this rule, in log time.
+    pub cooldown_secs: u32,
+}
+
+impl AlertRule {
+    /// The name, or the trigger description for unnamed rules.
+    pub fn title(&self) -> String {
+        let name = self.name.trim();
+        if name.is_empty() {
+            self.trigger.to_string()
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// The sound file, if one is set.
+    pub fn sound(&self) -> Option<&str> {
+        self.sound
+            .as_deref()
+            .map(str::trim)
+            .filter(|sound| !sound.is_empty())
+    }
+}
+
+impl Default for AlertRule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            trigger: AlertTrigger::Killed,
+            notify: true,
+            sound: None,
+            cooldown_secs: 30,
+        }
+    }
+}
+
+/// Who "I" am and who is hostile, for the triggers that depend on it.
+#[derive(Clone, Copy)]
+pub struct AlertContext<'a> {
+    pub nickname: Option<&'a str>,
+    pub roster: &'a Roster,
+}
+
+/// A fired rule.
+#[derive(Clone, Debug)]
+pub struct Alert {
+    pub title: String,
+    pub body: String,
+    pub notify: bool,
+    pub sound: Option<String>,
+}
+
+/// Checks new events against the rules, keeping each rule's cooldown.
+#[derive(Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    /// User-defined event types the rule queries can name with `type:`.
+    patterns: PatternSet,
+    queries: Vec<Result<Option<Query>, QueryError>>,
+    last_fired: Vec<Option<DateTime<Utc>>>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>, patterns: PatternSet) -> Self {
+        let mut engine = Self {
+            patterns,
+            ..Self::default()
+        };
+        engine.set_rules(rules);
+        engine
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    /// Replaces the rules. Cooldowns restart for every rule.
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>) {
+        self.last_fired = vec![None; rules.len()];
+        self.rules = rules;
+        self.parse_queries();
+    }
+
+    /// Parses the rule queries again with new user-defined patterns. Cooldowns are kept.
+    pub fn set_patterns(&mut self, patterns: PatternSet) {
+        self.patterns = patterns;
+        self.parse_queries();
+    }
+
+    fn parse_queries(&mut self) {
+        self.queries = self
+            .rules
+            .iter()
+            .map(|rule| match &rule.trigger {
+                AlertTrigger::Query { query } => Query::parse(query, &self.patterns),
+                _ => Ok(None),
+            })
+            .collect();
+    }
+
+    /// The query error of the rule at `index`, if its query does not parse.
+    pub fn rule_error(&self, index: usize) -> Option<&QueryError> {
+        self.queries.get(index)?.as_ref().err()
+    }
+
+    /// Runs the rules over newly ingested events, oldest first. A rule fires at most once per
+    /// cooldown, measured with the event timestamps so a burst of log lines gives one alert.
+    pub fn check(&mut self, events: &[PlayerEvent], context: AlertContext<'_>) -> Vec<Alert> {
+        let mut ordered: Vec<&PlayerEvent> = events.iter().collect();
+        ordered.sort_by_key(|event| event.timestamp);
+
+        let mut alerts = Vec::new();
+        for event in ordered {
+            for (index, rule) in self.rules.iter().enumerate() {
+                if !rule.enabled || (!rule.notify && rule.sound().is_none()) {
+                    continue;
+                }
+                let cooldown = Duration::seconds(i64::from(rule.cooldown_secs));
+                if let Some(last) = self.last_fired[index]
+                    && event.timestamp < last + cooldown
+                {
+                    continue;
+                }
+                let query = self.queries[index].as_ref().ok().and_then(Option::as_ref);
+                let Some(body) = describe_match(&rule.trigger, query, event, context) else {
+                    continue;
+                };
+                self.last_fired[index] = Some(event.timestamp);
+                alerts.push(Alert {
+                    title: rule.title(),
+                    body,
+                    notify: rule.notify,
+                    sound: rule.sound().map(str::to_string),
+                });
+            }
+        }
+        alerts
+    }
+}
+
+/// The notification text if `event` fires `trigger`.
+fn describe_match(
+    trigger: &AlertTrigger,
+    query: Option<&Query>,
+    event: &PlayerEvent,
+    context: AlertContext<'_>,
+) -> Option<String> {
+    let is_me = |name: &str| {
+        context
+            .nickname
+            .is_some_and(|nickname| name.trim().eq_ignore_ascii_case(nickname.trim()))
+    };
+    match (trigger, &event.kind) {
+        (AlertTrigger::Killed, EventKind::Kill(kill))
+            if is_me(&kill.victim_name) && !is_me(&kill.killer_name) =>
+        {
+            let weapon = kill.weapon.trim();
+            if weapon.is_empty() || weapon.eq_ignore_ascii_case("unknown") {
+                Some(format!("Killed by {}", kill.killer_name))
+            } else {
+                Some(format!("Killed by {} with {}", kill.killer_name, weapon))
+            }
+        }
+        (AlertTrigger::Kill, EventKind::Kill(kill))
+            if is_me(&kill.killer_name) && !is_me(&kill.victim_name) =>
+        {
+            Some(format!("You killed {}", kill.victim_name))
+        }
+        (AlertTrigger::HostilePlayer, _) => event
+            .involved_players()
+            .into_iter()
+            .find(|name| context.roster.contains(RosterList::Hostile, name))
+            .map(|name| format!("{} is nearby: {}", name, event.summary_line())),
+        (AlertTrigger::VehicleDestroyed { level }, EventKind::VehicleDestruction(vehicle))
+            if is_me(&vehicle.driver_name) && vehicle.to_level >= *level =>
+        {
+            Some(format!(
+                "{} {} by {}",
+                vehicle.vehicle_name,
+                describe_destroy_levels(vehicle.from_level, vehicle.to_level),
+                vehicle.attacker_name
+            ))
+        }
+        (AlertTrigger::Query { .. }, _) => query
+            .filter(|query| query.matches(event))
+            .map(|_| event.summary_line()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_line;
+
+    fn death(killer: &str, weapon: Option<&str>) -> PlayerEvent {
+        let using = weapon
+            .map(|weapon| format!(" using '{}' [Class W]", weapon))
+            .unwrap_or_default();
+        parse_line(&format!(
+            "<2024-09-14T18:00:00.000Z> [Notice] <Actor Death> CActor::Kill: 'Me' [1] killed by '{}' [2]{} with damage type 'Crash' from direction x: 0, y: 0, z: 0 [Team_ActorTech][Actor]",
+            killer, using
+        ))
+        .expect("kill line")
+    }
+
+    fn killed_text(event: &PlayerEvent) -> Option<String> {
+        let roster = Roster::default();
+        let context = AlertContext {
+            nickname: Some("Me"),
+            roster: &roster,
+        };
+        describe_match(&AlertTrigger::Killed, None, event, context)
+    }
+
+    #[test]
+    fn deaths_name_the_weapon_only_when_known() {
+        assert_eq!(
+            killed_text(&death("Other", Some("W_1"))).as_deref(),
+            Some("Killed by Other with W_1")
+        );
+        assert_eq!(
+            killed_text(&death("Other", Some("unknown"))).as_deref(),
+            Some("Killed by Other")
+        );
+        assert_eq!(
+            killed_text(&death("unknown", None)).as_deref(),
+            Some("Killed by unknown")
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use eframe::egui::{self, Color32, RichText};
+use rfd::FileDialog;
+use sc_log_analyzer::{Alert, AlertEngine, AlertRule, AlertTrigger, QueryError};
+
+/// Editor for the alert rules.
+#[derive(Default)]
+pub struct AlertsWindow {
+    pub open: bool,
+}
+
+impl AlertsWindow {
+    /// Shows the window, applying edits to `engine`. Returns an alert when the user asked to
+    /// test a rule.
+    pub fn show(&mut self, ctx: &egui::Context, engine: &mut AlertEngine) -> Option<Alert> {
+        if !self.open {
+            return None;
+        }
+        let mut rules = engine.rules().to_vec();
+        let mut removed = None;
+        let mut test = None;
+        let mut open = self.open;
+        egui::Window::new("Alerts")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(
+                        "Rules are checked against new lines while the log is followed, not when a log is loaded.",
+                    )
+                    .color(Color32::from_rgb(160, 160, 160)),
+                );
+                ui.add_space(4.0);
+                egui::ScrollArea::vertical()
+                    .max_height(420.0)
+                    .show(ui, |ui| {
+                        for (index, rule) in rules.iter_mut().enumerate() {
+                            ui.push_id(index, |ui| {
+                                egui::Frame::group(ui.style()).show(ui, |ui| {
+                                    match rule_editor(ui, rule, engine.rule_error(index)) {
+                                        Some(RuleAction::Remove) => removed = Some(index),
+                                        Some(RuleAction::Test) => test = Some(test_alert(rule)),
+                                        None => {}
+                                    }
+                                });
+                            });
+                        }
+                        if rules.is_empty() {
+                            ui.label(
+                                RichText::new("No rules yet").color(Color32::from_rgb(140, 140, 140)),
+                            );
+                        }
+                    });
+                ui.add_space(4.0);
+                if ui.button("Add rule").clicked() {
+                    rules.push(AlertRule::default());
+                }
+            });
+        self.open = open;
+        if let Some(index) = removed {
+            rules.remove(index);
+        }
+        if rules != engine.rules() {
+            engine.set_rules(rules);
+        }
+        test
+    }
+}
+
+enum RuleAction {
+    Remove,
+    Test,
+}
+
+fn rule_editor(
+    ui: &mut egui::Ui,
+    rule: &mut AlertRule,
+    error: Option<&QueryError>,
+) -> Option<RuleAction> {
+    let mut action = None;
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut rule.enabled, "");
+        ui.add(
+            egui::TextEdit::singleline(&mut rule.name)
+                .hint_text("Name")
+                .desired_width(140.0),
+        );
+        egui::ComboBox::from_id_source("trigger")
+            .selected_text(rule.trigger.to_string())
+            .show_ui(ui, |ui| {
+                for kind in AlertTrigger::KINDS {
+                    let selected = rule.trigger.same_kind(&kind);
+                    if ui.selectable_label(selected, kind.to_string()).clicked() && !selected {
+                        rule.trigger = kind;
+                    }
+                }
+            });
+        if ui.small_button("Test").clicked() {
+            action = Some(RuleAction::Test);
+        }
+        if ui.small_button("✕").on_hover_text("Remove").clicked() {
+            action = Some(RuleAction::Remove);
+        }
+    });
+    match &mut rule.trigger {
+        AlertTrigger::VehicleDestroyed { level } => {
+            ui.horizontal(|ui| {
+                ui.label("At destroy level");
+                ui.add(egui::DragValue::new(level).clamp_range(1..=2))
+                    .on_hover_text("1 is a soft kill, 2 a hard kill");
+            });
+        }
+        AlertTrigger::Query { query } => {
+            ui.add(
+                egui::TextEdit::singleline(query)
+                    .hint_text("type:kill weapon:~railgun")
+                    .desired_width(f32::INFINITY),
+            );
+            if let Some(err) = error {
+                ui.label(RichText::new(err.to_string()).color(Color32::from_rgb(255, 120, 120)));
+            }
+        }
+        _ => {}
+    }
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut rule.notify, "Notification");
+        let sound = rule.sound.get_or_insert_with(String::new);
+        ui.add(
+            egui::TextEdit::singleline(sound)
+                .hint_text("Sound file (.wav)")
+                .desired_width(180.0),
+        );
+        if ui.small_button("Browse…").clicked()
+            && let Some(path) = FileDialog::new()
+                .add_filter("Sound", &["wav", "ogg", "mp3"])
+                .pick_file()
+        {
+            *sound = path.display().to_string();
+        }
+        if sound.trim().is_empty() {
+            rule.sound = None;
+        }
+        ui.label("Cooldown");
+        ui.add(
+            egui::DragValue::new(&mut rule.cooldown_secs)
+                .clamp_range(0..=3600)
+                .suffix(" s"),
+        );
+    });
+    action
+}
+
+fn test_alert(rule: &AlertRule) -> Alert {
+    Alert {
+        title: rule.title(),
+        body: "This is how the alert will look.".to_string(),
+        notify: rule.notify,
+        sound: rule.sound().map(str::to_string),
+    }
+}
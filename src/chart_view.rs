@@ -0,0 +1,367 @@
+use chrono::{DateTime, Utc};
+use eframe::egui::{self, Color32, PointerButton, RichText};
+use egui_plot::{
+    GridInput, GridMark, MarkerShape, Plot, PlotPoints, Points, Polygon, uniform_grid_spacer,
+};
+use sc_log_analyzer::{
+    EventCategory, EventKind, PatternSet, PlayerEvent, patterns::DEFAULT_CUSTOM_COLOR,
+};
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+/// Steps for the time axis, in seconds.
+const TIME_STEPS: [f64; 14] = [
+    1.0, 5.0, 10.0, 30.0, 60.0, 300.0, 600.0, 1800.0, 3600.0, 10800.0, 21600.0, 43200.0, 86400.0,
+    604800.0,
+];
+
+const KILL_COLOR: Color32 = Color32::from_rgb(130, 220, 150);
+const DEATH_COLOR: Color32 = Color32::from_rgb(245, 120, 100);
+
+/// Events over time, one lane per category, with a range that can be brushed with the right
+/// mouse button to narrow the event list.
+#[derive(Default)]
+pub struct ChartView {
+    /// The x coordinates of a brush that is being dragged.
+    dragging: Option<(f64, f64)>,
+}
+
+impl ChartView {
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        events: &[&PlayerEvent],
+        nickname: Option<&str>,
+        patterns: &PatternSet,
+        brush: &mut Option<RangeInclusive<DateTime<Utc>>>,
+    ) {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(
+                RichText::new(
+                    "Right-drag to select a time range · drag or scroll to pan · Ctrl+scroll to zoom · double-click to reset",
+                )
+                .color(Color32::from_rgb(160, 160, 160)),
+            );
+            if let Some(range) = brush.as_ref() {
+                ui.label(
+                    RichText::new(format!(
+                        "Selected {} – {}",
+                        range.start().format("%Y-%m-%d %H:%M:%S"),
+                        range.end().format("%H:%M:%S")
+                    ))
+                    .color(Color32::from_rgb(160, 200, 240)),
+                );
+                if ui.small_button("Clear selection").clicked() {
+                    *brush = None;
+                }
+            }
+        });
+
+        let lane_count = EventCategory::ALL.len() as f64;
+        let plot = Plot::new("event_chart")
+            .height(200.0)
+            .allow_boxed_zoom(false)
+            .allow_drag([true, false])
+            .allow_zoom([true, false])
+            .allow_scroll([true, false])
+            .include_y(-0.5)
+            .include_y(lane_count - 0.5)
+            .y_axis_width(6)
+            .x_grid_spacer(uniform_grid_spacer(time_steps))
+            .y_grid_spacer(lane_marks)
+            .x_axis_formatter(|mark, _, range| format_time(mark.value, range))
+            .y_axis_formatter(|mark, _, _| {
+                lane_category(mark.value)
+                    .map(lane_label)
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .label_formatter(|name, point| {
+                let time = timestamp(point.x)
+                    .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_default();
+                if name.is_empty() {
+                    time
+                } else {
+                    format!("{}\n{}", name, time)
+                }
+            });
+
+        let mut dragging = self.dragging;
+        let mut finished = None;
+        plot.show(ui, |plot_ui| {
+            for category in EventCategory::ALL {
+                let lane = lane_of(category);
+                let mut plain = Vec::new();
+                let mut custom: BTreeMap<&str, Vec<[f64; 2]>> = BTreeMap::new();
+                let mut kills = Vec::new();
+                let mut deaths = Vec::new();
+                for event in events
+                    .iter()
+                    .filter(|event| EventCategory::of(&event.kind) == category)
+                {
+                    let point = [seconds(event.timestamp), lane];
+                    if let EventKind::Custom(event) = &event.kind {
+                        custom.entry(&event.name).or_default().push(point);
+                        continue;
+                    }
+                    match kill_side(event, nickname) {
+                        Some(KillSide::Kill) => kills.push(point),
+                        Some(KillSide::Death) => deaths.push(point),
+                        None => plain.push(point),
+                    }
+                }
+                plot_ui.points(
+                    Points::new(plain)
+                        .shape(MarkerShape::Circle)
+                        .radius(3.0)
+                        .color(lane_color(category))
+                        .name(lane_label(category)),
+                );
+                // Each pattern gets its own color and legend entry.
+                for (name, points) in custom {
+                    plot_ui.points(
+                        Points::new(points)
+                            .shape(MarkerShape::Circle)
+                            .radius(3.0)
+                            .color(custom_color(patterns, name))
+                            .name(name),
+                    );
+                }
+                if !kills.is_empty() {
+                    plot_ui.points(
+                        Points::new(kills)
+                            .shape(MarkerShape::Up)
+                            .radius(6.0)
+                            .filled(true)
+                            .color(KILL_COLOR)
+                            .name("My kills"),
+                    );
+                }
+                if !deaths.is_empty() {
+                    plot_ui.points(
+                        Points::new(deaths)
+                            .shape(MarkerShape::Down)
+                            .radius(6.0)
+                            .filled(true)
+                            .color(DEATH_COLOR)
+                            .name("My deaths"),
+                    );
+                }
+            }
+
+            let response = plot_ui.response().clone();
+            let pointer = plot_ui.pointer_coordinate().map(|point| point.x);
+            if response.drag_started_by(PointerButton::Secondary)
+                && let Some(x) = pointer
+            {
+                dragging = Some((x, x));
+            }
+            if let Some((start, end)) = dragging.as_mut() {
+                if let Some(x) = pointer {
+                    *end = x;
+                }
+                if !response.dragged_by(PointerButton::Secondary) {
+                    finished = Some((*start, *end));
+                }
+            }
+
+            let shaded = dragging.or_else(|| {
+                brush
+                    .as_ref()
+                    .map(|range| (seconds(*range.start()), seconds(*range.end())))
+            });
+            if let Some((start, end)) = shaded {
+                plot_ui.polygon(
+                    Polygon::new(PlotPoints::new(vec![
+                        [start, -0.5],
+                        [end, -0.5],
+                        [end, lane_count - 0.5],
+                        [start, lane_count - 0.5],
+                    ]))
+                    .fill_color(Color32::from_rgba_unmultiplied(120, 170, 230, 40))
+                    .stroke(egui::Stroke::new(1.0, Color32::from_rgb(120, 170, 230))),
+                );
+            }
+        });
+
+        if let Some((start, end)) = finished {
+            dragging = None;
+            // A click without a drag clears the selection.
+            *brush = timestamp(start.min(end))
+                .zip(timestamp(start.max(end)))
+                .filter(|(start, end)| start < end)
+                .map(|(start, end)| start..=end);
+        }
+        self.dragging = dragging;
+    }
+}
+
+enum KillSide {
+    Kill,
+    Death,
+}
+
+fn kill_side(event: &PlayerEvent, nickname: Option<&str>) -> Option<KillSide> {
+    let EventKind::Kill(kill) = &event.kind else {
+        return None;
+    };
+    let nickname = nickname?.trim();
+    if kill.victim_name.eq_ignore_ascii_case(nickname) {
+        Some(KillSide::Death)
+    } else if kill.killer_name.eq_ignore_ascii_case(nickname) {
+        Some(KillSide::Kill)
+    } else {
+        None
+    }
+}
+
+/// Kills on the top lane, custom events at the bottom, in the order of the filter checkboxes.
+fn lane_of(category: EventCategory) -> f64 {
+    let index = EventCategory::ALL
+        .iter()
+        .position(|other| *other == category)
+        .unwrap_or(0);
+    (EventCategory::ALL.len() - 1 - index) as f64
+}
+
+fn lane_category(y: f64) -> Option<EventCategory> {
+    if (y - y.round()).abs() > 1e-6 {
+        return None;
+    }
+    EventCategory::ALL
+        .into_iter()
+        .find(|category| lane_of(*category) == y.round())
+}
+
+fn lane_label(category: EventCategory) -> &'static str {
+    match category {
+        EventCategory::Kill => "Kills",
+        EventCategory::Spawn => "Spawns",
+        EventCategory::Corpse => "Corpses",
+        EventCategory::Zone => "Zones",
+        EventCategory::Status => "Status",
+        EventCategory::Hit => "Hits",
+        EventCategory::Vehicle => "Vehicles",
+        EventCategory::Custom => "Custom",
+    }
+}
+
+/// The summary colors of the event list.
+fn lane_color(category: EventCategory) -> Color32 {
+    match category {
+        EventCategory::Kill => Color32::from_rgb(235, 130, 130),
+        EventCategory::Spawn => Color32::from_rgb(130, 185, 245),
+        EventCategory::Corpse => Color32::from_rgb(200, 220, 150),
+        EventCategory::Zone => Color32::from_rgb(190, 160, 235),
+        EventCategory::Status => Color32::from_rgb(245, 180, 140),
+        EventCategory::Hit => Color32::from_rgb(160, 240, 160),
+        EventCategory::Vehicle => Color32::from_rgb(245, 150, 150),
+        EventCategory::Custom => {
+            let [r, g, b] = DEFAULT_CUSTOM_COLOR;
+            Color32::from_rgb(r, g, b)
+        }
+    }
+}
+
+/// The color set for a user-defined pattern, or the default one.
+pub fn custom_color(patterns: &PatternSet, name: &str) -> Color32 {
+    let [r, g, b] = patterns.color(name);
+    Color32::from_rgb(r, g, b)
+}
+
+fn lane_marks(_input: GridInput) -> Vec<GridMark> {
+    EventCategory::ALL
+        .into_iter()
+        .map(|category| GridMark {
+            value: lane_of(category),
+            step_size: 1.0,
+        })
+        .collect()
+}
+
+/// Grid steps of whole seconds, minutes, hours and days instead of powers of ten.
+fn time_steps(input: GridInput) -> [f64; 3] {
+    let smallest = TIME_STEPS
+        .iter()
+        .position(|step| *step >= input.base_step_size * 10.0)
+        .unwrap_or(TIME_STEPS.len() - 1);
+    let step = |offset: usize| TIME_STEPS[(smallest + offset).min(TIME_STEPS.len() - 1)];
+    [step(0), step(1), step(2)]
+}
+
+fn seconds(timestamp: DateTime<Utc>) -> f64 {
+    timestamp.timestamp_millis() as f64 / 1000.0
+}
+
+fn timestamp(seconds: f64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis((seconds * 1000.0).round() as i64)
+}
+
+fn format_time(value: f64, range: &RangeInclusive<f64>) -> String {
+    let Some(time) = timestamp(value) else {
+        return String::new();
+    };
+    if range.end() - range.start() > 2.0 * 86400.0 {
+        time.format("%m-%d %H:%M").to_string()
+    } else {
+        time.format("%H:%M:%S").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sc_log_analyzer::parse_line;
+
+    #[test]
+    fn lanes_map_back_to_their_category() {
+        assert_eq!(lane_of(EventCategory::Kill), 7.0);
+        assert_eq!(lane_of(EventCategory::Custom), 0.0);
+        for category in EventCategory::ALL {
+            assert_eq!(lane_category(lane_of(category)), Some(category));
+        }
+        assert_eq!(lane_category(2.5), None);
+        assert_eq!(lane_category(-1.0), None);
+    }
+
+    #[test]
+    fn kills_are_split_by_the_side_of_the_player() {
+        let kill = parse_line("<2024-09-14T18:00:00.000Z> [Notice] <Actor Death> CActor::Kill: 'Victim' [1] in zone 'Z' killed by 'Killer' [2] using 'W_1' [Class W] with damage type 'Bullet' from direction x: 0, y: 0, z: 0 [Team_ActorTech][Actor]")
+            .expect("kill line");
+        assert!(matches!(
+            kill_side(&kill, Some(" killer ")),
+            Some(KillSide::Kill)
+        ));
+        assert!(matches!(
+            kill_side(&kill, Some("Victim")),
+            Some(KillSide::Death)
+        ));
+        assert!(kill_side(&kill, Some("Someone")).is_none());
+        assert!(kill_side(&kill, None).is_none());
+    }
+
+    #[test]
+    fn the_time_axis_uses_clock_steps() {
+        let steps = |base_step_size| {
+            time_steps(GridInput {
+                bounds: (0.0, 3600.0),
+                base_step_size,
+            })
+        };
+        assert_eq!(steps(0.05), [1.0, 5.0, 10.0]);
+        assert_eq!(steps(2.0), [30.0, 60.0, 300.0]);
+        assert_eq!(steps(1e9), [604800.0, 604800.0, 604800.0]);
+    }
+
+    #[test]
+    fn times_round_trip_and_show_dates_on_long_ranges() {
+        let time: DateTime<Utc> = "2024-09-14T18:30:05.250Z".parse().expect("timestamp");
+        assert_eq!(timestamp(seconds(time)), Some(time));
+        let value = seconds(time);
+        assert_eq!(format_time(value, &(value..=value + 3600.0)), "18:30:05");
+        assert_eq!(
+            format_time(value, &(value..=value + 3.0 * 86400.0)),
+            "09-14 18:30"
+        );
+    }
+}
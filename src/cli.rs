@@ -0,0 +1,683 @@
+use crate::settings;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use sc_log_analyzer::{
+    DEFAULT_ENGAGEMENT_GAP_SECS, DEFAULT_RECAP_WINDOW_SECS, DeathRecap, Engagement, EventCategory,
+    EventFilter, EventStore, ExportFormat, GameBuild, LogMetadata, LogSummary, ParsedLog,
+    ParserHealth, ParserProfile, PatternSet, PlayerEvent, merge_logs, parse_log, scan_library,
+    session::segment_sessions,
+};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+#[derive(Parser)]
+#[command(name = "sc_log_analyzer", version = env!("SC_LOG_ANALYZER_VERSION"))]
+#[command(about = "Viewer for Star Citizen Game.log files. Starts the GUI without a subcommand.")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Read custom event patterns from this file instead of `patterns.toml` in the settings
+    /// directory.
+    #[arg(long, global = true)]
+    pub patterns: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Parse a log and print the matching events without opening a window.
+    Analyze(AnalyzeArgs),
+    /// List the game sessions found in a log.
+    Sessions {
+        /// Path to the Game.log file. Several files are merged into one timeline.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Store the events of one or more logs in the history database.
+    Import {
+        /// Logs to import. Events that are already stored are skipped.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Use this database instead of the one in the settings directory.
+        #[arg(long)]
+        database: Option<PathBuf>,
+    },
+    /// List Game.log and the rotated logs in `logbackups` with their date range and event counts.
+    Library {
+        /// The game install folder that contains Game.log.
+        install_dir: PathBuf,
+    },
+    /// Count the lines each parser recognized and print lines that look like events but did
+    /// not parse, e.g. after a game update changed the log format.
+    Health {
+        /// Path to the Game.log file. Several files are counted together.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Print what led up to each death of the player: hits, status effects and vehicle damage.
+    Recap {
+        /// Path to the Game.log file. Several files are merged into one timeline.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Whose deaths to recap. Defaults to the nickname found in the log.
+        #[arg(long)]
+        player: Option<String>,
+
+        /// Seconds to look back before each death.
+        #[arg(long, default_value_t = DEFAULT_RECAP_WINDOW_SECS)]
+        window: u32,
+    },
+    /// Group hits, vehicle damage and kills among the same players into engagements.
+    Engagements {
+        /// Path to the Game.log file. Several files are merged into one timeline.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// The player whose side counts as ours. Defaults to the nickname found in the log.
+        #[arg(long)]
+        player: Option<String>,
+
+        /// A player on our side. Repeat for several; defaults to the friendly roster.
+        #[arg(long = "friend")]
+        friends: Vec<String>,
+
+        /// Longest quiet stretch inside one engagement, in seconds.
+        #[arg(long, default_value_t = DEFAULT_ENGAGEMENT_GAP_SECS)]
+        gap: u32,
+
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = EngagementFormat::Text)]
+        format: EngagementFormat,
+
+        /// Write to this file instead of stdout.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Print the game build, system and shards from the header of a log.
+    Info {
+        /// Path to the Game.log file.
+        file: PathBuf,
+
+        /// Print JSON instead of text.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Args)]
+pub struct AnalyzeArgs {
+    /// Path to the Game.log file. Several files are merged into one timeline.
+    #[arg(required_unless_present = "history")]
+    files: Vec<PathBuf>,
+
+    /// Read the events stored in the history database instead of log files.
+    #[arg(long, conflicts_with = "files")]
+    history: bool,
+
+    /// Use this database instead of the one in the settings directory.
+    #[arg(long, requires = "history")]
+    database: Option<PathBuf>,
+
+    /// Only show these categories (comma separated: kill, spawn, corpse, zone, status, hit, vehicle,
+    /// custom).
+    #[arg(long, value_delimiter = ',', conflicts_with = "hide")]
+    only: Vec<EventCategory>,
+
+    /// Hide these categories (comma separated).
+    #[arg(long, value_delimiter = ',')]
+    hide: Vec<EventCategory>,
+
+    /// Hide routine events triggered by this player. Defaults to the nickname found in the
+    /// log; pass an empty string to show everything.
+    #[arg(long)]
+    ignore_player: Option<String>,
+
+    /// Only show events matching this query, e.g. `killer:Foo weapon:~klwe` or plain text that
+    /// has to appear in the summary, details or raw line.
+    #[arg(long, default_value = "", allow_hyphen_values = true)]
+    search: String,
+
+    /// Treat `--search` as a case-insensitive regular expression.
+    #[arg(long)]
+    regex: bool,
+
+    /// Only show events of this session (1-based, as listed by the `sessions` command).
+    #[arg(long)]
+    session: Option<usize>,
+
+    /// Print the detail lines below each summary.
+    #[arg(long)]
+    details: bool,
+
+    /// Print the oldest events first instead of the newest.
+    #[arg(long)]
+    oldest_first: bool,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Write to this file instead of stdout. For `csv-split` this is the target directory.
+    /// The log info is written next to it, see the `info` command.
+    #[arg(long, short, required_if_eq("format", "csv-split"))]
+    output: Option<PathBuf>,
+
+    /// Write the log info of a `csv-split` export even into the folder of a log.
+    #[arg(long, requires = "output")]
+    force: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    Ndjson,
+    /// One CSV with a category column and the columns of every category.
+    Csv,
+    /// One CSV file per category, written into the `--output` directory.
+    CsvSplit,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum EngagementFormat {
+    Text,
+    Json,
+    Ndjson,
+    /// One row per engagement, without its events.
+    Csv,
+}
+
+pub fn run(command: Command, patterns: Option<PathBuf>) -> ExitCode {
+    attach_parent_console();
+    let patterns = match load_patterns(patterns) {
+        Ok(patterns) => patterns,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let patterns = &patterns;
+    let result = match command {
+        Command::Analyze(args) => analyze(args, patterns),
+        Command::Sessions { files } => sessions(&files, patterns),
+        Command::Import { files, database } => import(&files, database, patterns),
+        Command::Library { install_dir } => library(&install_dir, patterns),
+        Command::Health { files } => health(&files, patterns),
+        Command::Info { file, json } => info(&file, json, patterns),
+        Command::Recap {
+            files,
+            player,
+            window,
+        } => recap(&files, player, window, patterns),
+        Command::Engagements {
+            files,
+            player,
+            friends,
+            gap,
+            format,
+            output,
+        } => engagements(
+            &files,
+            player,
+            friends,
+            gap,
+            format,
+            output.as_deref(),
+            patterns,
+        ),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn analyze(args: AnalyzeArgs, patterns: &PatternSet) -> Result<(), String> {
+    let parsed = if args.history {
+        open_history(args.database)?.load(None)?
+    } else {
+        load(&args.files, patterns)?
+    };
+    let unmatched = parsed.health.unmatched();
+    if unmatched > 0 {
+        eprintln!(
+            "Warning: {} line(s) look like events but did not parse; run the health command for details",
+            unmatched
+        );
+    }
+
+    let time_range = match args.session {
+        Some(number) => {
+            let sessions = segment_sessions(&parsed.markers, &parsed.events);
+            let session = number
+                .checked_sub(1)
+                .and_then(|index| sessions.get(index))
+                .ok_or_else(|| {
+                    format!(
+                        "Session {} does not exist, the log has {} session(s)",
+                        number,
+                        sessions.len()
+                    )
+                })?;
+            Some(session.time_range())
+        }
+        None => None,
+    };
+
+    let mut filter = EventFilter {
+        ignored_player: args
+            .ignore_player
+            .or(parsed.primary_nickname)
+            .unwrap_or_default(),
+        search_text: args.search,
+        search_regex: args.regex,
+        patterns: patterns.clone(),
+        time_range,
+        roster: settings::Settings::read().roster,
+        ..EventFilter::default()
+    };
+    for category in EventCategory::ALL {
+        let shown = if args.only.is_empty() {
+            !args.hide.contains(&category)
+        } else {
+            args.only.contains(&category)
+        };
+        filter.set_shown(category, shown);
+    }
+
+    let search = filter.compile_search();
+    if let Some(err) = search.error() {
+        return Err(format!("Invalid search: {}", err));
+    }
+    let mut events = filter.apply_compiled(&parsed.events, &search);
+    if args.oldest_first {
+        events.reverse();
+    }
+
+    if let (OutputFormat::CsvSplit, Some(dir)) = (args.format, &args.output) {
+        let written = sc_log_analyzer::export_csv_per_category(dir, &events)?;
+        for path in written {
+            eprintln!("Wrote {}", path.display());
+        }
+        return export_metadata(dir, &parsed.metadata, args.force, &args.files);
+    }
+
+    let out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(
+            File::create(path)
+                .map_err(|err| format!("Failed to create {}: {}", path.display(), err))?,
+        ),
+        None => Box::new(io::stdout().lock()),
+    };
+    let out = BufWriter::new(out);
+    let result = match args.format {
+        OutputFormat::Text => write_text(out, &events, args.details),
+        OutputFormat::Json => sc_log_analyzer::write_events(out, &events, ExportFormat::Json),
+        OutputFormat::Ndjson => sc_log_analyzer::write_events(out, &events, ExportFormat::Ndjson),
+        OutputFormat::Csv | OutputFormat::CsvSplit => {
+            sc_log_analyzer::write_events(out, &events, ExportFormat::Csv)
+        }
+    };
+    result.map_err(|err| format!("Failed to write output: {}", err))?;
+    match (args.format, &args.output) {
+        (OutputFormat::Text, _) | (_, None) => Ok(()),
+        (_, Some(path)) => export_metadata(path, &parsed.metadata, args.force, &args.files),
+    }
+}
+
+/// Writes the log info next to an export, replacing the one of an earlier export. Without
+/// `force`, `metadata.json` is not written into the folder of one of the `logs`.
+fn export_metadata(
+    path: &Path,
+    metadata: &LogMetadata,
+    force: bool,
+    logs: &[PathBuf],
+) -> Result<(), String> {
+    if !force && path.is_dir() {
+        let log_dirs: Vec<PathBuf> = logs
+            .iter()
+            .filter_map(|log| log.canonicalize().ok()?.parent().map(Path::to_path_buf))
+            .collect();
+        if path.canonicalize().is_ok_and(|dir| log_dirs.contains(&dir)) {
+            eprintln!(
+                "Not writing {} into the folder of the log; pass --force to write it",
+                sc_log_analyzer::metadata_path(path).display()
+            );
+            return Ok(());
+        }
+    }
+    if let Some(written) = sc_log_analyzer::export_metadata(path, Some(metadata))? {
+        eprintln!("Wrote {}", written.display());
+    }
+    Ok(())
+}
+
+/// A pattern file passed on the command line has to load; problems with the default one are
+/// only reported.
+fn load_patterns(path: Option<PathBuf>) -> Result<PatternSet, String> {
+    match path {
+        Some(path) => {
+            if !path.exists() {
+                return Err(format!("Pattern file {} does not exist", path.display()));
+            }
+            PatternSet::load(&path)
+        }
+        None => match settings::patterns_path().map(|path| PatternSet::load(&path)) {
+            Some(Ok(patterns)) => Ok(patterns),
+            Some(Err(err)) => {
+                eprintln!("Ignoring custom patterns: {}", err);
+                Ok(PatternSet::default())
+            }
+            None => Ok(PatternSet::default()),
+        },
+    }
+}
+
+fn load(files: &[PathBuf], patterns: &PatternSet) -> Result<ParsedLog, String> {
+    match files {
+        [file] => parse_log(file, patterns),
+        files => merge_logs(files, patterns),
+    }
+}
+
+fn sessions(files: &[PathBuf], patterns: &PatternSet) -> Result<(), String> {
+    let parsed = load(files, patterns)?;
+    let mut out = io::stdout().lock();
+    for (index, session) in segment_sessions(&parsed.markers, &parsed.events)
+        .iter()
+        .enumerate()
+    {
+        let mut line = session.label(index);
+        match &session.disconnect_reason {
+            Some(reason) => line.push_str(&format!(" · disconnected: {}", reason)),
+            None if session.disconnected => line.push_str(" · disconnected"),
+            None => {}
+        }
+        writeln!(out, "{}", line).map_err(|err| format!("Failed to write output: {}", err))?;
+    }
+    Ok(())
+}
+
+fn health(files: &[PathBuf], patterns: &PatternSet) -> Result<(), String> {
+    let parsed = load(files, patterns)?;
+    write_health(io::stdout().lock(), &parsed.metadata.build, &parsed.health)
+        .map_err(|err| format!("Failed to write output: {}", err))
+}
+
+fn recap(
+    files: &[PathBuf],
+    player: Option<String>,
+    window: u32,
+    patterns: &PatternSet,
+) -> Result<(), String> {
+    let parsed = load(files, patterns)?;
+    let player = player
+        .or(parsed.primary_nickname)
+        .ok_or("No player nickname found in the log; pass --player")?;
+    let recaps = DeathRecap::collect(
+        &parsed.events,
+        &player,
+        chrono::Duration::seconds(i64::from(window)),
+    );
+    let mut out = io::stdout().lock();
+    write_recaps(&mut out, &recaps).map_err(|err| format!("Failed to write output: {}", err))
+}
+
+fn write_recaps<W: Write>(mut out: W, recaps: &[DeathRecap]) -> io::Result<()> {
+    for (index, recap) in recaps.iter().enumerate() {
+        if index > 0 {
+            writeln!(out)?;
+        }
+        let kill = recap.kill();
+        let mut headline = format!(
+            "{} | {}",
+            recap.death.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            recap.headline()
+        );
+        if !kill.zone.is_empty() {
+            headline.push_str(&format!(" in {}", kill.zone));
+        }
+        writeln!(out, "{}", headline)?;
+        writeln!(out, "  {}", recap.overview())?;
+        let attackers = recap.attackers();
+        if !attackers.is_empty() {
+            let list: Vec<String> = attackers
+                .iter()
+                .map(|(name, hits)| format!("{} ({})", name, hits))
+                .collect();
+            writeln!(out, "  Hit by {}", list.join(", "))?;
+        }
+        for line in recap.timeline() {
+            writeln!(out, "  {}", line)?;
+        }
+    }
+    Ok(())
+}
+
+fn engagements(
+    files: &[PathBuf],
+    player: Option<String>,
+    friends: Vec<String>,
+    gap: u32,
+    format: EngagementFormat,
+    output: Option<&Path>,
+    patterns: &PatternSet,
+) -> Result<(), String> {
+    let parsed = load(files, patterns)?;
+    let mut our_side = if friends.is_empty() {
+        settings::Settings::read().roster.friendly
+    } else {
+        friends
+    };
+    if let Some(player) = player.or(parsed.primary_nickname) {
+        our_side.insert(0, player);
+    }
+    let engagements = Engagement::detect(
+        &parsed.events,
+        chrono::Duration::seconds(i64::from(gap)),
+        &our_side,
+    );
+
+    let out: Box<dyn Write> = match output {
+        Some(path) => Box::new(
+            File::create(path)
+                .map_err(|err| format!("Failed to create {}: {}", path.display(), err))?,
+        ),
+        None => Box::new(io::stdout().lock()),
+    };
+    let out = BufWriter::new(out);
+    let result = match format {
+        EngagementFormat::Text => write_engagements(out, &engagements),
+        EngagementFormat::Json => {
+            sc_log_analyzer::write_engagements(out, &engagements, ExportFormat::Json)
+        }
+        EngagementFormat::Ndjson => {
+            sc_log_analyzer::write_engagements(out, &engagements, ExportFormat::Ndjson)
+        }
+        EngagementFormat::Csv => {
+            sc_log_analyzer::write_engagements(out, &engagements, ExportFormat::Csv)
+        }
+    };
+    result.map_err(|err| format!("Failed to write output: {}", err))
+}
+
+fn write_engagements<W: Write>(mut out: W, engagements: &[Engagement]) -> io::Result<()> {
+    for (index, engagement) in engagements.iter().enumerate() {
+        if index > 0 {
+            writeln!(out)?;
+        }
+        writeln!(
+            out,
+            "{} - {} | {} | {} for, {} against, {} other | {}s",
+            engagement.start.format("%Y-%m-%d %H:%M:%S"),
+            engagement.end.format("%H:%M:%S"),
+            engagement.outcome.label(),
+            engagement.kills_for,
+            engagement.kills_against,
+            engagement.other_kills,
+            engagement.duration().num_seconds()
+        )?;
+        writeln!(out, "  {}", engagement.participants.join(", "))?;
+        for event in &engagement.events {
+            writeln!(out, "  {}", event.summary_line())?;
+        }
+    }
+    out.flush()
+}
+
+fn info(file: &Path, json: bool, patterns: &PatternSet) -> Result<(), String> {
+    let metadata = parse_log(file, patterns)?.metadata;
+    let mut out = io::stdout().lock();
+    let result = if json {
+        serde_json::to_writer_pretty(&mut out, &metadata)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(out))
+    } else if metadata.is_known() {
+        write!(out, "{}", metadata.to_text()).and_then(|()| {
+            writeln!(
+                out,
+                "Parser profile: {}",
+                ParserProfile::select(&metadata.build).name
+            )
+        })
+    } else {
+        writeln!(
+            out,
+            "The log has no header with build or system information."
+        )
+    };
+    result.map_err(|err| format!("Failed to write output: {}", err))
+}
+
+fn write_health<W: Write>(mut out: W, build: &GameBuild, health: &ParserHealth) -> io::Result<()> {
+    writeln!(
+        out,
+        "Game build: {} · parser profile {}",
+        build,
+        ParserProfile::select(build).name
+    )?;
+    writeln!(out)?;
+    for (category, parser, markers) in ParserHealth::parsers() {
+        let stats = health.stats(category);
+        writeln!(
+            out,
+            "{:<26} {:>7} parsed {:>5} unmatched   {}",
+            parser,
+            stats.parsed,
+            stats.unmatched,
+            markers.join(" … ")
+        )?;
+    }
+    for (category, parser, _) in ParserHealth::parsers() {
+        let stats = health.stats(category);
+        if stats.samples.is_empty() {
+            continue;
+        }
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{} did not parse ({} of {} shown):",
+            parser,
+            stats.samples.len(),
+            stats.unmatched
+        )?;
+        for sample in &stats.samples {
+            writeln!(out, "  {}", sample)?;
+        }
+    }
+    Ok(())
+}
+
+fn open_history(database: Option<PathBuf>) -> Result<EventStore, String> {
+    let path = database
+        .or_else(settings::history_database_path)
+        .ok_or_else(|| "Failed to resolve settings directory".to_string())?;
+    EventStore::open(&path)
+}
+
+fn import(
+    files: &[PathBuf],
+    database: Option<PathBuf>,
+    patterns: &PatternSet,
+) -> Result<(), String> {
+    let mut store = open_history(database)?;
+    for file in files {
+        let parsed = parse_log(file, patterns)?;
+        let added = store.insert_log(&parsed)?;
+        eprintln!(
+            "{}: {} new of {} events",
+            file.display(),
+            added,
+            parsed.events.len()
+        );
+    }
+    eprintln!("The history now holds {} events", store.event_count()?);
+    Ok(())
+}
+
+fn library(install_dir: &Path, patterns: &PatternSet) -> Result<(), String> {
+    let mut out = io::stdout().lock();
+    for path in scan_library(install_dir)? {
+        let line = match LogSummary::from_path(&path, patterns) {
+            Ok(summary) => {
+                let range = match (summary.first_timestamp, summary.last_timestamp) {
+                    (Some(first), Some(last)) => format!(
+                        "{} – {}",
+                        first.format("%Y-%m-%d %H:%M"),
+                        last.format("%Y-%m-%d %H:%M")
+                    ),
+                    _ => "no timestamps".to_string(),
+                };
+                let counts: Vec<String> = summary
+                    .category_counts
+                    .iter()
+                    .filter(|(_, count)| *count > 0)
+                    .map(|(category, count)| format!("{} {}", count, category))
+                    .collect();
+                format!(
+                    "{} | {} | {} events ({})",
+                    path.display(),
+                    range,
+                    summary.event_count,
+                    counts.join(", ")
+                )
+            }
+            Err(err) => format!("{} | {}", path.display(), err),
+        };
+        writeln!(out, "{}", line).map_err(|err| format!("Failed to write output: {}", err))?;
+    }
+    Ok(())
+}
+
+fn write_text<W: Write>(mut out: W, events: &[&PlayerEvent], details: bool) -> io::Result<()> {
+    for event in events {
+        writeln!(out, "{}", event.summary_line())?;
+        if details {
+            for detail in event.detail_lines() {
+                writeln!(out, "    {}", detail)?;
+            }
+        }
+    }
+    out.flush()
+}
+
+/// The release binary uses the GUI subsystem on Windows, so it has to borrow the console of
+/// the shell it was started from to make its output visible.
+#[cfg(target_os = "windows")]
+fn attach_parent_console() {
+    use windows_sys::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_parent_console() {}
@@ -0,0 +1,324 @@
+use crate::event::{EventKind, PlayerEvent};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Longest quiet stretch inside one engagement by default.
+pub const DEFAULT_ENGAGEMENT_GAP_SECS: u32 = 60;
+
+/// How an engagement ended for our side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// Our side killed someone and lost nobody.
+    Won,
+    /// Our side lost someone and killed nobody.
+    Lost,
+    /// Both sides lost players.
+    Traded,
+    NoKills,
+    /// Nobody of our side took part.
+    NotInvolved,
+}
+
+impl Outcome {
+    /// The name used in exports.
+    pub fn key(self) -> &'static str {
+        match self {
+            Outcome::Won => "won",
+            Outcome::Lost => "lost",
+            Outcome::Traded => "traded",
+            Outcome::NoKills => "no_kills",
+            Outcome::NotInvolved => "not_involved",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Outcome::Won => "Won",
+            Outcome::Lost => "Lost",
+            Outcome::Traded => "Traded",
+            Outcome::NoKills => "No kills",
+            Outcome::NotInvolved => "Not involved",
+        }
+    }
+}
+
+/// A fight: kills, hits and vehicle damage among the same players, close together in time.
+#[derive(Clone, Debug, Serialize)]
+pub struct Engagement {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Everyone who took part, our side first.
+    pub participants: Vec<String>,
+    /// Kills of the other side by our side.
+    pub kills_for: usize,
+    /// Players of our side killed, including suicides and crashes.
+    pub kills_against: usize,
+    /// Kills that did not involve our side.
+    pub other_kills: usize,
+    pub outcome: Outcome,
+    /// Oldest first.
+    pub events: Vec<PlayerEvent>,
+}
+
+impl Engagement {
+    /// Groups the kill, hit and vehicle destruction events in `events` (in any order) into
+    /// engagements, oldest first. An event joins an engagement that shares a participant with
+    /// it and saw its last event at most `gap` earlier; an event that bridges several of them
+    /// merges them. `our_side` holds the player and their friends; everyone else is the other
+    /// side.
+    pub fn detect<'a>(
+        events: impl IntoIterator<Item = &'a PlayerEvent>,
+        gap: Duration,
+        our_side: &[String],
+    ) -> Vec<Engagement> {
+        let mut events: Vec<&PlayerEvent> = events
+            .into_iter()
+            .filter(|event| {
+                matches!(
+                    event.kind,
+                    EventKind::Kill(_) | EventKind::Hit(_) | EventKind::VehicleDestruction(_)
+                )
+            })
+            .collect();
+        events.sort_by_key(|event| event.timestamp);
+
+        let mut open: Vec<Cluster> = Vec::new();
+        let mut closed: Vec<Cluster> = Vec::new();
+        for event in events {
+            let names: HashSet<String> = players(event)
+                .into_iter()
+                .map(|name| name.trim().to_lowercase())
+                .filter(|name| !name.is_empty() && name != "unknown")
+                .collect();
+            if names.is_empty() {
+                continue;
+            }
+
+            let (stale, active): (Vec<Cluster>, Vec<Cluster>) = open
+                .drain(..)
+                .partition(|cluster| event.timestamp - cluster.last > gap);
+            closed.extend(stale);
+            let (joined, rest): (Vec<Cluster>, Vec<Cluster>) = active
+                .into_iter()
+                .partition(|cluster| !cluster.names.is_disjoint(&names));
+            open = rest;
+
+            let mut cluster = Cluster {
+                names,
+                last: event.timestamp,
+                events: Vec::new(),
+            };
+            for other in joined {
+                cluster.names.extend(other.names);
+                cluster.events.extend(other.events);
+            }
+            cluster.events.push(event);
+            open.push(cluster);
+        }
+        closed.extend(open);
+
+        let our_side: HashSet<String> = our_side
+            .iter()
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect();
+        let mut engagements: Vec<Engagement> = closed
+            .into_iter()
+            .map(|cluster| cluster.into_engagement(&our_side))
+            .collect();
+        engagements.sort_by_key(|engagement| engagement.start);
+        engagements
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    pub fn kill_count(&self) -> usize {
+        self.kills_for + self.kills_against + self.other_kills
+    }
+}
+
+/// The event's players, plus the player a hit was sent to. The hit target itself is often a
+/// ship, so it is left out like everywhere else.
+fn players(event: &PlayerEvent) -> Vec<String> {
+    let mut players = event.involved_players();
+    if let EventKind::Hit(hit) = &event.kind
+        && let Some(child) = hit.child.as_deref().map(str::trim)
+        && !child.is_empty()
+        && !child.eq_ignore_ascii_case("unknown")
+        && !players
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(child))
+    {
+        players.push(child.to_string());
+    }
+    players
+}
+
+struct Cluster<'a> {
+    /// Lowercase participant names.
+    names: HashSet<String>,
+    last: DateTime<Utc>,
+    events: Vec<&'a PlayerEvent>,
+}
+
+impl Cluster<'_> {
+    fn into_engagement(mut self, our_side: &HashSet<String>) -> Engagement {
+        self.events.sort_by_key(|event| event.timestamp);
+        let is_ours = |name: &str| our_side.contains(&name.trim().to_lowercase());
+
+        let mut kills_for = 0;
+        let mut kills_against = 0;
+        let mut other_kills = 0;
+        for event in &self.events {
+            let EventKind::Kill(kill) = &event.kind else {
+                continue;
+            };
+            if is_ours(&kill.victim_name) {
+                kills_against += 1;
+            } else if is_ours(&kill.killer_name) {
+                kills_for += 1;
+            } else {
+                other_kills += 1;
+            }
+        }
+
+        let mut participants: Vec<String> = Vec::new();
+        for event in &self.events {
+            for name in players(event) {
+                if !participants
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(&name))
+                {
+                    participants.push(name);
+                }
+            }
+        }
+        participants.sort_by_key(|name| !is_ours(name));
+
+        let involved = participants.iter().any(|name| is_ours(name));
+        let outcome = match (involved, kills_for > 0, kills_against > 0) {
+            (false, _, _) => Outcome::NotInvolved,
+            (true, true, false) => Outcome::Won,
+            (true, false, true) => Outcome::Lost,
+            (true, true, true) => Outcome::Traded,
+            (true, false, false) => Outcome::NoKills,
+        };
+
+        Engagement {
+            start: self
+                .events
+                .first()
+                .map_or(self.last, |event| event.timestamp),
+            end: self.last,
+            participants,
+            kills_for,
+            kills_against,
+            other_kills,
+            outcome,
+            events: self.events.into_iter().cloned().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_line;
+
+    fn kill_at(time: &str, victim: &str, killer: &str) -> PlayerEvent {
+        parse_line(&format!(
+            "<2024-09-14T{}.000Z> [Notice] <Actor Death> CActor::Kill: '{}' [1] in zone 'Z' killed by '{}' [2] using 'W_1' [Class W] with damage type 'Bullet' from direction x: 0, y: 0, z: 0 [Team_ActorTech][Actor]",
+            time, victim, killer
+        ))
+        .expect("kill line")
+    }
+
+    fn hit_at(time: &str, attacker: &str, target: &str) -> PlayerEvent {
+        parse_line(&format!(
+            "<2024-09-14T{}.000Z> [Notice] <Debug Hostility Events> [OnHandleHit] Fake hit FROM {} TO {}. Being sent to child {} [Team_CoreGameplayFeatures][Hostility]",
+            time, attacker, target, target
+        ))
+        .expect("hit line")
+    }
+
+    fn detect(events: &[PlayerEvent]) -> Vec<Engagement> {
+        let our_side = ["Me".to_string(), "Friend".to_string()];
+        Engagement::detect(events, Duration::seconds(30), &our_side)
+    }
+
+    fn outcome(events: &[PlayerEvent]) -> Outcome {
+        let engagements = detect(events);
+        assert_eq!(engagements.len(), 1);
+        engagements[0].outcome
+    }
+
+    #[test]
+    fn outcomes_follow_the_kills_of_our_side() {
+        assert_eq!(outcome(&[kill_at("18:00:00", "Foe", "me")]), Outcome::Won);
+        assert_eq!(outcome(&[kill_at("18:00:00", "Me", "Foe")]), Outcome::Lost);
+        assert_eq!(
+            outcome(&[
+                kill_at("18:00:00", "Foe", "Friend"),
+                kill_at("18:00:10", "Me", "Foe"),
+            ]),
+            Outcome::Traded
+        );
+        assert_eq!(
+            outcome(&[hit_at("18:00:00", "Foe", "Me")]),
+            Outcome::NoKills
+        );
+        assert_eq!(
+            outcome(&[kill_at("18:00:00", "Foe", "Other")]),
+            Outcome::NotInvolved
+        );
+    }
+
+    #[test]
+    fn counts_and_participants_put_our_side_first() {
+        let engagements = detect(&[
+            kill_at("18:00:20", "Me", "Foe"),
+            kill_at("18:00:00", "Foe", "Friend"),
+            kill_at("18:00:10", "Other", "Foe"),
+        ]);
+        assert_eq!(engagements.len(), 1);
+        let engagement = &engagements[0];
+        assert_eq!(
+            (
+                engagement.kills_for,
+                engagement.kills_against,
+                engagement.other_kills
+            ),
+            (1, 1, 1)
+        );
+        assert_eq!(engagement.participants, ["Friend", "Me", "Foe", "Other"]);
+        assert_eq!(engagement.duration(), Duration::seconds(20));
+    }
+
+    #[test]
+    fn bridging_events_merge_and_quiet_gaps_split() {
+        let engagements = detect(&[
+            kill_at("18:00:00", "B", "A"),
+            kill_at("18:00:10", "D", "C"),
+            kill_at("18:00:20", "C", "A"),
+            kill_at("18:01:00", "A", "B"),
+        ]);
+        let spans: Vec<(usize, DateTime<Utc>, DateTime<Utc>)> = engagements
+            .iter()
+            .map(|engagement| (engagement.events.len(), engagement.start, engagement.end))
+            .collect();
+        let at = |time: &str| -> DateTime<Utc> {
+            format!("2024-09-14T{}Z", time).parse().expect("timestamp")
+        };
+        assert_eq!(
+            spans,
+            [
+                (3, at("18:00:00"), at("18:00:20")),
+                (1, at("18:01:00"), at("18:01:00")),
+            ]
+        );
+    }
+}
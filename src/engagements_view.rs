@@ -0,0 +1,108 @@
+use eframe::egui::{self, Color32, RichText};
+use sc_log_analyzer::{Engagement, Outcome};
+
+/// Longest gap the slider offers, in seconds.
+const MAX_GAP_SECS: u32 = 600;
+
+/// One card per engagement, newest first. Returns `true` when the engagements should be
+/// exported.
+pub fn show(ui: &mut egui::Ui, engagements: &[Engagement], gap_secs: &mut u32) -> bool {
+    let mut export = false;
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new(format!("{} engagements", engagements.len()))
+                .strong()
+                .color(Color32::from_rgb(220, 220, 220)),
+        );
+        ui.separator();
+        ui.label("Gap");
+        ui.add(
+            egui::DragValue::new(gap_secs)
+                .clamp_range(1..=MAX_GAP_SECS)
+                .suffix(" s"),
+        )
+        .on_hover_text("Longest quiet stretch before a fight among the same players ends");
+        ui.separator();
+        export = ui
+            .add_enabled(!engagements.is_empty(), egui::Button::new("Export…"))
+            .on_hover_text("Save as JSON, NDJSON or CSV, chosen by the file extension")
+            .clicked();
+    });
+    ui.add_space(6.0);
+
+    if engagements.is_empty() {
+        ui.label(RichText::new("No engagements").color(Color32::from_rgb(140, 140, 140)));
+        return export;
+    }
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for engagement in engagements.iter().rev() {
+            card(ui, engagement);
+            ui.add_space(6.0);
+        }
+    });
+    export
+}
+
+fn card(ui: &mut egui::Ui, engagement: &Engagement) {
+    egui::Frame::none()
+        .fill(Color32::from_rgb(28, 32, 40))
+        .rounding(egui::Rounding::same(6.0))
+        .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal_wrapped(|ui| {
+                ui.label(
+                    RichText::new(format!(
+                        "{} – {}",
+                        engagement.start.format("%Y-%m-%d %H:%M:%S"),
+                        engagement.end.format("%H:%M:%S")
+                    ))
+                    .color(Color32::from_rgb(160, 160, 160)),
+                );
+                ui.label(
+                    RichText::new(format_duration(engagement.duration().num_seconds()))
+                        .color(Color32::from_rgb(160, 160, 160)),
+                );
+                ui.label(
+                    RichText::new(engagement.outcome.label())
+                        .strong()
+                        .color(outcome_color(engagement.outcome)),
+                );
+                ui.label(
+                    RichText::new(format!(
+                        "{} for · {} against · {} other",
+                        engagement.kills_for, engagement.kills_against, engagement.other_kills
+                    ))
+                    .color(Color32::from_rgb(190, 190, 190)),
+                );
+            });
+            ui.label(
+                RichText::new(engagement.participants.join(", "))
+                    .color(Color32::from_rgb(130, 185, 245)),
+            );
+            egui::CollapsingHeader::new(format!("{} events", engagement.events.len()))
+                .id_source(("engagement", engagement.start, &engagement.participants))
+                .show(ui, |ui| {
+                    for event in &engagement.events {
+                        ui.label(RichText::new(event.summary_line()).monospace());
+                    }
+                });
+        });
+}
+
+fn outcome_color(outcome: Outcome) -> Color32 {
+    match outcome {
+        Outcome::Won => Color32::from_rgb(140, 210, 140),
+        Outcome::Lost => Color32::from_rgb(240, 90, 80),
+        Outcome::Traded => Color32::from_rgb(240, 170, 80),
+        Outcome::NoKills | Outcome::NotInvolved => Color32::from_rgb(180, 180, 180),
+    }
+}
+
+fn format_duration(secs: i64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
@@ -0,0 +1,448 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+pub(crate) fn matches_ignore_case(value: &str, expected: &str) -> bool {
+    value.eq_ignore_ascii_case(expected)
+}
+
+pub(crate) fn format_status_stage(stage: &str, effect: &str) -> String {
+    if matches_ignore_case(stage, "start") {
+        format!("started {}", effect)
+    } else if matches_ignore_case(stage, "end") {
+        format!("ended {}", effect)
+    } else {
+        format!("{} {}", stage, effect)
+    }
+}
+
+/// Short label for a vehicle destroy level transition, e.g. `soft kill` for 0 → 1.
+pub fn describe_destroy_levels(from: u32, to: u32) -> &'static str {
+    match (from, to) {
+        (0, 1) => "soft kill",
+        (1, 2) => "hard kill",
+        (start, end) if end > start => "destroyed",
+        _ => "changed",
+    }
+}
+
+/// A single player-related line recognized in the log.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlayerEvent {
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: EventKind,
+    pub raw: String,
+}
+
+impl PlayerEvent {
+    pub fn summary_line(&self) -> String {
+        let ts = self.timestamp.format("%Y-%m-%d %H:%M:%S");
+        match &self.kind {
+            EventKind::Kill(event) => {
+                let weapon_display = if event.weapon.is_empty() {
+                    "unknown weapon".to_string()
+                } else if event.weapon_class.is_empty() {
+                    event.weapon.clone()
+                } else {
+                    format!("{} ({})", event.weapon, event.weapon_class)
+                };
+                format!(
+                    "{} | Kill | {} → {} with {}",
+                    ts, event.killer_name, event.victim_name, weapon_display
+                )
+            }
+            EventKind::SpawnReservation(event) => format!(
+                "{} | Spawn | {} lost {}",
+                ts, event.player_name, event.spawn_point
+            ),
+            EventKind::CorpseStatus(event) => format!(
+                "{} | Corpse | {} corpse {}",
+                ts,
+                event.player_name,
+                if event.corpse_enabled {
+                    "enabled"
+                } else {
+                    "disabled"
+                }
+            ),
+            EventKind::ZoneTransfer(event) => format!(
+                "{} | Zone | {} → {}",
+                ts,
+                event.player_name,
+                event
+                    .host_name
+                    .as_deref()
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or("unknown destination")
+            ),
+            EventKind::StatusEffect(event) => format!(
+                "{} | Status | {} {}",
+                ts,
+                event.player_name,
+                format_status_stage(&event.stage, &event.effect)
+            ),
+            EventKind::Hit(event) => {
+                format!("{} | Hit | {} → {}", ts, event.attacker, event.target)
+            }
+            EventKind::VehicleDestruction(event) => format!(
+                "{} | Vehicle | {} {} ({})",
+                ts,
+                event.attacker_name,
+                describe_destroy_levels(event.from_level, event.to_level),
+                event.vehicle_name
+            ),
+            EventKind::Custom(event) => format!("{} | {} | {}", ts, event.name, event.summary),
+        }
+    }
+
+    pub fn detail_lines(&self) -> Vec<String> {
+        match &self.kind {
+            EventKind::Kill(event) => {
+                let mut lines = Vec::new();
+                lines.push(format!(
+                    "Victim: {} [{}] in {}",
+                    event.victim_name, event.victim_id, event.zone
+                ));
+                lines.push(format!(
+                    "Killer: {} [{}]",
+                    event.killer_name, event.killer_id
+                ));
+                if !event.damage_type.is_empty() {
+                    lines.push(format!("Damage type: {}", event.damage_type));
+                }
+                lines
+            }
+            EventKind::SpawnReservation(event) => vec![
+                format!("Player: {} [{}]", event.player_name, event.player_id),
+                format!("Spawn point: {}", event.spawn_point),
+                format!("Spawn ID: {}", event.spawn_id),
+                format!("Location: {}", event.location),
+            ],
+            EventKind::CorpseStatus(event) => {
+                let mut lines = vec![format!(
+                    "Corpse state: {}",
+                    if event.corpse_enabled {
+                        "Enabled"
+                    } else {
+                        "Disabled"
+                    }
+                )];
+                if let Some(context) = event.context.as_ref()
+                    && !context.is_empty()
+                {
+                    lines.push(format!("Context: {}", context));
+                }
+                lines
+            }
+            EventKind::ZoneTransfer(event) => {
+                let mut lines = vec![format!("Player: {}", event.player_name)];
+                if let Some(child_id) = event.child_id.as_ref() {
+                    lines.push(format!("Child ID: {}", child_id));
+                }
+                if let Some(parent_name) = event.parent_name.as_ref()
+                    && !parent_name.is_empty()
+                {
+                    lines.push(format!("Parent: {}", parent_name));
+                }
+                if let Some(parent_id) = event.parent_id.as_ref() {
+                    lines.push(format!("Parent ID: {}", parent_id));
+                }
+                if let Some(host_name) = event.host_name.as_ref()
+                    && !host_name.is_empty()
+                {
+                    lines.push(format!("Zone host: {}", host_name));
+                }
+                if let Some(host_id) = event.host_id.as_ref() {
+                    lines.push(format!("Zone host ID: {}", host_id));
+                }
+                lines
+            }
+            EventKind::StatusEffect(event) => vec![
+                format!("Player: {}", event.player_name),
+                format!("Status effect: {}", event.effect),
+                format!(
+                    "Stage: {}",
+                    if matches_ignore_case(&event.stage, "start") {
+                        "Start".to_string()
+                    } else if matches_ignore_case(&event.stage, "end") {
+                        "End".to_string()
+                    } else {
+                        event.stage.clone()
+                    }
+                ),
+            ],
+            EventKind::Hit(event) => {
+                let mut lines = vec![format!("Attacker: {}", event.attacker)];
+                lines.push(format!("Target: {}", event.target));
+                if let Some(child) = event.child.as_ref() {
+                    lines.push(format!("Child channel: {}", child));
+                }
+                lines
+            }
+            EventKind::VehicleDestruction(event) => {
+                let mut lines = Vec::new();
+                lines.push(format!(
+                    "Vehicle: {} [{}]",
+                    event.vehicle_name, event.vehicle_id
+                ));
+                lines.push(format!(
+                    "Destroy level: {} → {} ({})",
+                    event.from_level,
+                    event.to_level,
+                    describe_destroy_levels(event.from_level, event.to_level)
+                ));
+                lines.push(format!(
+                    "Attacker: {} [{}] via {}",
+                    event.attacker_name, event.attacker_id, event.cause
+                ));
+                if !event.zone.is_empty() {
+                    lines.push(format!("Zone: {}", event.zone));
+                }
+                if !event.driver_name.is_empty() {
+                    lines.push(format!(
+                        "Driver: {} [{}]",
+                        event.driver_name, event.driver_id
+                    ));
+                }
+                lines
+            }
+            EventKind::Custom(event) => {
+                let mut lines = vec![format!("Pattern: {}", event.name)];
+                lines.extend(
+                    event
+                        .fields
+                        .iter()
+                        .filter(|(_, value)| !value.is_empty())
+                        .map(|(name, value)| format!("{}: {}", name, value)),
+                );
+                lines
+            }
+        }
+    }
+
+    pub fn matches_search(&self, needle: &str) -> bool {
+        let needle = needle.trim();
+        if needle.is_empty() {
+            return true;
+        }
+        self.search_blob().contains(needle)
+    }
+
+    pub fn should_ignore(&self, ignored: &str) -> bool {
+        let trimmed = ignored.trim();
+        if trimmed.is_empty() {
+            return false;
+        }
+        match &self.kind {
+            EventKind::Kill(event) => {
+                event.killer_name.eq_ignore_ascii_case(trimmed)
+                    && !event.victim_name.eq_ignore_ascii_case(trimmed)
+            }
+            EventKind::SpawnReservation(event) => event.player_name.eq_ignore_ascii_case(trimmed),
+            EventKind::CorpseStatus(event) => event.player_name.eq_ignore_ascii_case(trimmed),
+            EventKind::ZoneTransfer(event) => event.player_name.eq_ignore_ascii_case(trimmed),
+            EventKind::StatusEffect(event) => event.player_name.eq_ignore_ascii_case(trimmed),
+            EventKind::Hit(event) => event.attacker.eq_ignore_ascii_case(trimmed),
+            EventKind::VehicleDestruction(event) => {
+                event.attacker_name.eq_ignore_ascii_case(trimmed)
+                    || (!event.driver_name.is_empty()
+                        && event.driver_name.eq_ignore_ascii_case(trimmed))
+            }
+            EventKind::Custom(event) => event
+                .participants
+                .first()
+                .is_some_and(|name| name.eq_ignore_ascii_case(trimmed)),
+        }
+    }
+
+    pub fn search_blob(&self) -> String {
+        let mut blob = self.summary_line().to_lowercase();
+        for line in self.detail_lines() {
+            blob.push('\n');
+            blob.push_str(&line.to_lowercase());
+        }
+        blob.push('\n');
+        blob.push_str(&self.raw.to_lowercase());
+        blob
+    }
+
+    pub fn participants(&self) -> Vec<String> {
+        match &self.kind {
+            EventKind::Kill(event) => vec![
+                event.killer_name.to_lowercase(),
+                event.victim_name.to_lowercase(),
+            ],
+            EventKind::SpawnReservation(event) => vec![event.player_name.to_lowercase()],
+            EventKind::CorpseStatus(event) => vec![event.player_name.to_lowercase()],
+            EventKind::ZoneTransfer(event) => vec![event.player_name.to_lowercase()],
+            EventKind::StatusEffect(event) => vec![event.player_name.to_lowercase()],
+            EventKind::Hit(event) => vec![event.attacker.to_lowercase()],
+            EventKind::VehicleDestruction(event) => {
+                let mut names = vec![event.attacker_name.to_lowercase()];
+                if !event.driver_name.is_empty() {
+                    names.push(event.driver_name.to_lowercase());
+                }
+                names
+            }
+            EventKind::Custom(event) => event
+                .participants
+                .iter()
+                .map(|name| name.to_lowercase())
+                .collect(),
+        }
+    }
+
+    pub fn involved_players(&self) -> Vec<String> {
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut names = Vec::new();
+        let mut push_name = |name: &str| {
+            let trimmed = name.trim();
+            if trimmed.is_empty() {
+                return;
+            }
+            if trimmed.eq_ignore_ascii_case("unknown") {
+                return;
+            }
+            let key = trimmed.to_ascii_lowercase();
+            if seen.insert(key) {
+                names.push(trimmed.to_string());
+            }
+        };
+
+        match &self.kind {
+            EventKind::Kill(event) => {
+                push_name(&event.killer_name);
+                push_name(&event.victim_name);
+            }
+            EventKind::SpawnReservation(event) => {
+                push_name(&event.player_name);
+            }
+            EventKind::CorpseStatus(event) => {
+                push_name(&event.player_name);
+            }
+            EventKind::ZoneTransfer(event) => {
+                push_name(&event.player_name);
+            }
+            EventKind::StatusEffect(event) => {
+                push_name(&event.player_name);
+            }
+            EventKind::Hit(event) => {
+                push_name(&event.attacker);
+            }
+            EventKind::VehicleDestruction(event) => {
+                push_name(&event.attacker_name);
+                push_name(&event.driver_name);
+            }
+            EventKind::Custom(event) => {
+                for name in &event.participants {
+                    push_name(name);
+                }
+            }
+        }
+
+        names
+    }
+}
+
+/// Serialized as `"category": "<key>"` plus the structured fields under `"data"`, using the
+/// same category keys as `EventCategory`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "category", content = "data")]
+pub enum EventKind {
+    #[serde(rename = "kill")]
+    Kill(KillEvent),
+    #[serde(rename = "spawn")]
+    SpawnReservation(SpawnReservationEvent),
+    #[serde(rename = "corpse")]
+    CorpseStatus(CorpseStatusEvent),
+    #[serde(rename = "zone")]
+    ZoneTransfer(ZoneTransferEvent),
+    #[serde(rename = "status")]
+    StatusEffect(StatusEffectEvent),
+    #[serde(rename = "hit")]
+    Hit(HitEvent),
+    #[serde(rename = "vehicle")]
+    VehicleDestruction(VehicleDestructionEvent),
+    /// Matched by a user-defined pattern, see `patterns`.
+    #[serde(rename = "custom")]
+    Custom(CustomEvent),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KillEvent {
+    pub victim_name: String,
+    pub victim_id: String,
+    pub killer_name: String,
+    pub killer_id: String,
+    pub weapon: String,
+    pub weapon_class: String,
+    pub damage_type: String,
+    pub zone: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpawnReservationEvent {
+    pub player_name: String,
+    pub player_id: String,
+    pub spawn_point: String,
+    pub spawn_id: String,
+    pub location: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CorpseStatusEvent {
+    pub player_name: String,
+    pub context: Option<String>,
+    pub corpse_enabled: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ZoneTransferEvent {
+    pub player_name: String,
+    pub child_id: Option<String>,
+    pub parent_id: Option<String>,
+    pub parent_name: Option<String>,
+    pub host_id: Option<String>,
+    pub host_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StatusEffectEvent {
+    pub player_name: String,
+    pub effect: String,
+    pub stage: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HitEvent {
+    pub attacker: String,
+    pub target: String,
+    pub child: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VehicleDestructionEvent {
+    pub vehicle_name: String,
+    pub vehicle_id: String,
+    pub zone: String,
+    pub driver_name: String,
+    pub driver_id: String,
+    pub from_level: u32,
+    pub to_level: u32,
+    pub attacker_name: String,
+    pub attacker_id: String,
+    pub cause: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CustomEvent {
+    /// The name of the pattern that matched.
+    pub name: String,
+    pub summary: String,
+    /// The named captures of the pattern.
+    pub fields: BTreeMap<String, String>,
+    /// The captured player names, in the order of the pattern. The first one counts as the
+    /// player who caused the event when ignoring a player.
+    pub participants: Vec<String>,
+}
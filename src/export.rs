@@ -0,0 +1,489 @@
+use crate::engagement::Engagement;
+use crate::event::{EventKind, PlayerEvent, describe_destroy_levels};
+use crate::filter::EventCategory;
+use crate::metadata::LogMetadata;
+use chrono::SecondsFormat;
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// A single JSON array.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// One CSV file for all categories, with a `category` column and the union of all columns.
+    Csv,
+}
+
+impl ExportFormat {
+    /// Picks the format from a file extension, e.g. `.ndjson` or `.jsonl` for NDJSON.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(ExportFormat::Json),
+            "ndjson" | "jsonl" => Some(ExportFormat::Ndjson),
+            "csv" => Some(ExportFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+pub fn write_events<W: Write>(
+    mut writer: W,
+    events: &[&PlayerEvent],
+    format: ExportFormat,
+) -> io::Result<()> {
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, events)?;
+            writeln!(writer)?;
+        }
+        ExportFormat::Ndjson => {
+            for event in events {
+                serde_json::to_writer(&mut writer, event)?;
+                writeln!(writer)?;
+            }
+        }
+        ExportFormat::Csv => write_combined_csv(&mut writer, events)?,
+    }
+    writer.flush()
+}
+
+pub fn export_to_path(
+    path: &Path,
+    events: &[&PlayerEvent],
+    format: ExportFormat,
+) -> Result<(), String> {
+    let file = File::create(path)
+        .map_err(|err| format!("Failed to create {}: {}", path.display(), err))?;
+    write_events(BufWriter::new(file), events, format)
+        .map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+}
+
+/// Writes one `<category>.csv` per category present in `events` into `dir` and returns the
+/// files that were written.
+pub fn export_csv_per_category(
+    dir: &Path,
+    events: &[&PlayerEvent],
+) -> Result<Vec<PathBuf>, String> {
+    fs::create_dir_all(dir)
+        .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+    let mut written = Vec::new();
+    for category in EventCategory::ALL {
+        let matching: Vec<&PlayerEvent> = events
+            .iter()
+            .copied()
+            .filter(|event| EventCategory::of(&event.kind) == category)
+            .collect();
+        if matching.is_empty() {
+            continue;
+        }
+        let path = dir.join(format!("{}.csv", category.key()));
+        let file = File::create(&path)
+            .map_err(|err| format!("Failed to create {}: {}", path.display(), err))?;
+        write_category_csv(BufWriter::new(file), category, &matching)
+            .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Columns of the engagement CSV. The events of each engagement are only in the JSON formats.
+pub const ENGAGEMENT_COLUMNS: [&str; 9] = [
+    "start",
+    "end",
+    "duration_secs",
+    "outcome",
+    "kills_for",
+    "kills_against",
+    "other_kills",
+    "participants",
+    "event_count",
+];
+
+/// Writes engagements with their events in the JSON formats, or one CSV row each.
+pub fn write_engagements<W: Write>(
+    mut writer: W,
+    engagements: &[Engagement],
+    format: ExportFormat,
+) -> io::Result<()> {
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, engagements)?;
+            writeln!(writer)?;
+        }
+        ExportFormat::Ndjson => {
+            for engagement in engagements {
+                serde_json::to_writer(&mut writer, engagement)?;
+                writeln!(writer)?;
+            }
+        }
+        ExportFormat::Csv => {
+            let mut csv = csv::Writer::from_writer(&mut writer);
+            csv.write_record(ENGAGEMENT_COLUMNS)?;
+            for engagement in engagements {
+                csv.write_record([
+                    engagement
+                        .start
+                        .to_rfc3339_opts(SecondsFormat::Millis, true),
+                    engagement.end.to_rfc3339_opts(SecondsFormat::Millis, true),
+                    engagement.duration().num_seconds().to_string(),
+                    engagement.outcome.key().to_string(),
+                    engagement.kills_for.to_string(),
+                    engagement.kills_against.to_string(),
+                    engagement.other_kills.to_string(),
+                    engagement.participants.join("; "),
+                    engagement.events.len().to_string(),
+                ])?;
+            }
+            csv.flush()?;
+        }
+    }
+    writer.flush()
+}
+
+pub fn export_engagements_to_path(
+    path: &Path,
+    engagements: &[Engagement],
+    format: ExportFormat,
+) -> Result<(), String> {
+    let file = File::create(path)
+        .map_err(|err| format!("Failed to create {}: {}", path.display(), err))?;
+    write_engagements(BufWriter::new(file), engagements, format)
+        .map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+}
+
+/// File name of the log metadata in a folder of per-category CSV files.
+pub const METADATA_FILE: &str = "metadata.json";
+
+/// Where [`export_metadata`] puts the log metadata of an export: `<name>.meta.json` next to a
+/// file, or [`METADATA_FILE`] in a folder of per-category CSV files.
+pub fn metadata_path(export_path: &Path) -> PathBuf {
+    if export_path.is_dir() {
+        export_path.join(METADATA_FILE)
+    } else {
+        let stem = export_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        export_path.with_file_name(format!("{}.meta.json", stem))
+    }
+}
+
+/// Writes the log metadata next to an export, see [`metadata_path`], replacing the file of an
+/// earlier export to the same place. Without known metadata that file is removed instead, so it
+/// never describes another log. Returns the file written.
+pub fn export_metadata(
+    export_path: &Path,
+    metadata: Option<&LogMetadata>,
+) -> Result<Option<PathBuf>, String> {
+    let path = metadata_path(export_path);
+    let Some(metadata) = metadata.filter(|metadata| metadata.is_known()) else {
+        return match fs::remove_file(&path) {
+            Ok(()) => Ok(None),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(format!("Failed to remove {}: {}", path.display(), err)),
+        };
+    };
+    let mut value = serde_json::to_value(metadata)
+        .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+    if let Some(object) = value.as_object_mut() {
+        object.insert("regions".to_string(), metadata.regions().into());
+    }
+    let file = File::create(&path)
+        .map_err(|err| format!("Failed to create {}: {}", path.display(), err))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, &value)
+        .map_err(io::Error::from)
+        .and_then(|()| writeln!(writer))
+        .and_then(|()| writer.flush())
+        .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+    Ok(Some(path))
+}
+
+/// Column names of the per-category CSV schema, excluding the trailing `raw` column.
+pub fn csv_columns(category: EventCategory) -> &'static [&'static str] {
+    match category {
+        EventCategory::Kill => &[
+            "timestamp",
+            "killer",
+            "killer_id",
+            "victim",
+            "victim_id",
+            "weapon",
+            "weapon_class",
+            "damage_type",
+            "zone",
+        ],
+        EventCategory::Spawn => &[
+            "timestamp",
+            "player",
+            "player_id",
+            "spawn_point",
+            "spawn_id",
+            "location",
+        ],
+        EventCategory::Corpse => &["timestamp", "player", "context", "corpse_enabled"],
+        EventCategory::Zone => &[
+            "timestamp",
+            "player",
+            "child_id",
+            "parent_id",
+            "parent_name",
+            "host_id",
+            "host_name",
+        ],
+        EventCategory::Status => &["timestamp", "player", "effect", "stage"],
+        EventCategory::Hit => &["timestamp", "attacker", "target", "child"],
+        EventCategory::Vehicle => &[
+            "timestamp",
+            "vehicle",
+            "vehicle_id",
+            "zone",
+            "driver",
+            "driver_id",
+            "from_level",
+            "to_level",
+            "destroy_level",
+            "attacker",
+            "attacker_id",
+            "cause",
+        ],
+        EventCategory::Custom => &["timestamp", "pattern", "summary", "participants", "fields"],
+    }
+}
+
+/// Values in the order of `csv_columns` for the event's category.
+pub fn csv_values(event: &PlayerEvent) -> Vec<String> {
+    let timestamp = event.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true);
+    match &event.kind {
+        EventKind::Kill(kill) => vec![
+            timestamp,
+            kill.killer_name.clone(),
+            kill.killer_id.clone(),
+            kill.victim_name.clone(),
+            kill.victim_id.clone(),
+            kill.weapon.clone(),
+            kill.weapon_class.clone(),
+            kill.damage_type.clone(),
+            kill.zone.clone(),
+        ],
+        EventKind::SpawnReservation(spawn) => vec![
+            timestamp,
+            spawn.player_name.clone(),
+            spawn.player_id.clone(),
+            spawn.spawn_point.clone(),
+            spawn.spawn_id.clone(),
+            spawn.location.clone(),
+        ],
+        EventKind::CorpseStatus(corpse) => vec![
+            timestamp,
+            corpse.player_name.clone(),
+            corpse.context.clone().unwrap_or_default(),
+            corpse.corpse_enabled.to_string(),
+        ],
+        EventKind::ZoneTransfer(zone) => vec![
+            timestamp,
+            zone.player_name.clone(),
+            zone.child_id.clone().unwrap_or_default(),
+            zone.parent_id.clone().unwrap_or_default(),
+            zone.parent_name.clone().unwrap_or_default(),
+            zone.host_id.clone().unwrap_or_default(),
+            zone.host_name.clone().unwrap_or_default(),
+        ],
+        EventKind::StatusEffect(status) => vec![
+            timestamp,
+            status.player_name.clone(),
+            status.effect.clone(),
+            status.stage.clone(),
+        ],
+        EventKind::Hit(hit) => vec![
+            timestamp,
+            hit.attacker.clone(),
+            hit.target.clone(),
+            hit.child.clone().unwrap_or_default(),
+        ],
+        EventKind::VehicleDestruction(vehicle) => vec![
+            timestamp,
+            vehicle.vehicle_name.clone(),
+            vehicle.vehicle_id.clone(),
+            vehicle.zone.clone(),
+            vehicle.driver_name.clone(),
+            vehicle.driver_id.clone(),
+            vehicle.from_level.to_string(),
+            vehicle.to_level.to_string(),
+            describe_destroy_levels(vehicle.from_level, vehicle.to_level).to_string(),
+            vehicle.attacker_name.clone(),
+            vehicle.attacker_id.clone(),
+            vehicle.cause.clone(),
+        ],
+        EventKind::Custom(custom) => vec![
+            timestamp,
+            custom.name.clone(),
+            custom.summary.clone(),
+            custom.participants.join("; "),
+            serde_json::to_string(&custom.fields).unwrap_or_default(),
+        ],
+    }
+}
+
+fn write_category_csv<W: Write>(
+    writer: W,
+    category: EventCategory,
+    events: &[&PlayerEvent],
+) -> io::Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    let mut header = csv_columns(category).to_vec();
+    header.push("raw");
+    csv.write_record(&header)?;
+    for event in events {
+        let mut record = csv_values(event);
+        record.push(event.raw.clone());
+        csv.write_record(&record)?;
+    }
+    csv.flush()
+}
+
+fn write_combined_csv<W: Write>(writer: W, events: &[&PlayerEvent]) -> io::Result<()> {
+    let mut columns: Vec<&str> = vec!["timestamp", "category"];
+    for category in EventCategory::ALL {
+        for column in csv_columns(category) {
+            if !columns.contains(column) {
+                columns.push(column);
+            }
+        }
+    }
+    columns.push("raw");
+
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(&columns)?;
+    for event in events {
+        let category = EventCategory::of(&event.kind);
+        let mut record = vec![String::new(); columns.len()];
+        for (name, value) in csv_columns(category).iter().zip(csv_values(event)) {
+            if let Some(index) = columns.iter().position(|column| column == name) {
+                record[index] = value;
+            }
+        }
+        record[1] = category.key().to_string();
+        record[columns.len() - 1] = event.raw.clone();
+        csv.write_record(&record)?;
+    }
+    csv.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_line;
+    use serde_json::{Value, json};
+
+    fn kill() -> PlayerEvent {
+        parse_line("<2024-09-14T18:00:00.000Z> [Notice] <Actor Death> CActor::Kill: 'A' [1] in zone 'Z' killed by 'B' [2] using 'W_1' [Class W] with damage type 'Bullet' from direction x: 0, y: 0, z: 0 [Team_ActorTech][Actor]")
+            .expect("kill line")
+    }
+
+    fn status_effect() -> PlayerEvent {
+        parse_line("<2024-09-14T18:00:05.000Z> Logged a start of a status effect! nickname: A, status effect: Bleeding")
+            .expect("status effect line")
+    }
+
+    fn written(events: &[&PlayerEvent], format: ExportFormat) -> String {
+        let mut out = Vec::new();
+        write_events(&mut out, events, format).expect("writes");
+        String::from_utf8(out).expect("UTF-8")
+    }
+
+    #[test]
+    fn json_records_hold_category_data_and_raw_line() {
+        let (kill, status) = (kill(), status_effect());
+        let records: Value =
+            serde_json::from_str(&written(&[&kill, &status], ExportFormat::Json)).expect("JSON");
+        assert_eq!(records.as_array().map(Vec::len), Some(2));
+        assert_eq!(records[0]["category"], "kill");
+        assert_eq!(records[0]["timestamp"], "2024-09-14T18:00:00Z");
+        assert_eq!(records[0]["data"]["killer_name"], "B");
+        assert_eq!(records[0]["raw"], kill.raw.as_str());
+        assert_eq!(
+            records[1]["data"],
+            json!({ "player_name": "A", "effect": "Bleeding", "stage": "start" })
+        );
+
+        let ndjson = written(&[&kill, &status], ExportFormat::Ndjson);
+        let lines: Vec<Value> = ndjson
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("one JSON document per line"))
+            .collect();
+        assert_eq!(lines, records.as_array().cloned().unwrap_or_default());
+    }
+
+    #[test]
+    fn combined_csv_puts_every_category_into_the_union_of_columns() {
+        let (kill, status) = (kill(), status_effect());
+        let csv = written(&[&kill, &status], ExportFormat::Csv);
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let header: Vec<String> = reader
+            .headers()
+            .expect("header")
+            .iter()
+            .map(str::to_string)
+            .collect();
+        assert_eq!(header[..3], ["timestamp", "category", "killer"]);
+        assert_eq!(header.last().map(String::as_str), Some("raw"));
+        for category in EventCategory::ALL {
+            for column in csv_columns(category) {
+                assert_eq!(
+                    header.iter().filter(|name| name == column).count(),
+                    1,
+                    "{}",
+                    column
+                );
+            }
+        }
+
+        let rows: Vec<csv::StringRecord> = reader.records().map(|row| row.expect("row")).collect();
+        let field = |row: usize, column: &str| {
+            let index = header.iter().position(|name| name == column).expect(column);
+            rows[row][index].to_string()
+        };
+        assert_eq!(rows.len(), 2);
+        assert_eq!(field(0, "category"), "kill");
+        assert_eq!(field(0, "weapon"), "W_1");
+        assert_eq!(field(0, "effect"), "");
+        assert_eq!(field(0, "raw"), kill.raw);
+        assert_eq!(field(1, "category"), "status");
+        assert_eq!(field(1, "timestamp"), "2024-09-14T18:00:05.000Z");
+        assert_eq!(field(1, "player"), "A");
+        assert_eq!(field(1, "effect"), "Bleeding");
+        assert_eq!(field(1, "killer"), "");
+    }
+
+    #[test]
+    fn csv_values_match_the_columns_of_their_category() {
+        for event in [kill(), status_effect()] {
+            let category = EventCategory::of(&event.kind);
+            assert_eq!(csv_values(&event).len(), csv_columns(category).len());
+        }
+    }
+
+    #[test]
+    fn formats_follow_the_file_extension() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.JSON")),
+            Some(ExportFormat::Json)
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.jsonl")),
+            Some(ExportFormat::Ndjson)
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.csv")),
+            Some(ExportFormat::Csv)
+        );
+        assert_eq!(ExportFormat::from_path(Path::new("out.txt")), None);
+    }
+}
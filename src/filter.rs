@@ -0,0 +1,361 @@
+use crate::event::{EventKind, PlayerEvent};
+use crate::patterns::PatternSet;
+use crate::query::Query;
+use crate::roster::{Roster, RosterScope};
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    ops::{Range, RangeInclusive},
+    str::FromStr,
+};
+
+/// The event types the viewer can show or hide as a group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventCategory {
+    Kill,
+    Spawn,
+    Corpse,
+    Zone,
+    Status,
+    Hit,
+    Vehicle,
+    Custom,
+}
+
+impl EventCategory {
+    pub const ALL: [EventCategory; 8] = [
+        EventCategory::Kill,
+        EventCategory::Spawn,
+        EventCategory::Corpse,
+        EventCategory::Zone,
+        EventCategory::Status,
+        EventCategory::Hit,
+        EventCategory::Vehicle,
+        EventCategory::Custom,
+    ];
+
+    pub fn of(kind: &EventKind) -> Self {
+        match kind {
+            EventKind::Kill(_) => EventCategory::Kill,
+            EventKind::SpawnReservation(_) => EventCategory::Spawn,
+            EventKind::CorpseStatus(_) => EventCategory::Corpse,
+            EventKind::ZoneTransfer(_) => EventCategory::Zone,
+            EventKind::StatusEffect(_) => EventCategory::Status,
+            EventKind::Hit(_) => EventCategory::Hit,
+            EventKind::VehicleDestruction(_) => EventCategory::Vehicle,
+            EventKind::Custom(_) => EventCategory::Custom,
+        }
+    }
+
+    /// Stable lowercase name used on the command line and in exports.
+    pub fn key(self) -> &'static str {
+        match self {
+            EventCategory::Kill => "kill",
+            EventCategory::Spawn => "spawn",
+            EventCategory::Corpse => "corpse",
+            EventCategory::Zone => "zone",
+            EventCategory::Status => "status",
+            EventCategory::Hit => "hit",
+            EventCategory::Vehicle => "vehicle",
+            EventCategory::Custom => "custom",
+        }
+    }
+
+    /// The plural of `key`, e.g. `kills` or `statuses`, also accepted on the command line.
+    fn plural_key(self) -> String {
+        let key = self.key();
+        if key.ends_with('s') {
+            format!("{}es", key)
+        } else {
+            format!("{}s", key)
+        }
+    }
+}
+
+impl fmt::Display for EventCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+impl FromStr for EventCategory {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let normalized = value.trim().to_ascii_lowercase();
+        EventCategory::ALL
+            .into_iter()
+            .find(|category| category.key() == normalized)
+            .or_else(|| {
+                EventCategory::ALL
+                    .into_iter()
+                    .find(|category| category.plural_key() == normalized)
+            })
+            .ok_or_else(|| {
+                let known = EventCategory::ALL.map(EventCategory::key).join(", ");
+                format!(
+                    "Unknown event category '{}' (expected one of {})",
+                    value, known
+                )
+            })
+    }
+}
+
+/// The category toggles, ignored player, search text and time scope shared by the GUI and the
+/// CLI.
+#[derive(Clone, Debug)]
+pub struct EventFilter {
+    pub show_kills: bool,
+    pub show_spawns: bool,
+    pub show_corpse: bool,
+    pub show_zone_moves: bool,
+    pub show_status_effects: bool,
+    pub show_hits: bool,
+    pub show_vehicle_destruction: bool,
+    /// Events of user-defined patterns.
+    pub show_custom: bool,
+    pub ignored_player: String,
+    /// A [`Query`], or a regular expression with `search_regex`. Text that does not parse
+    /// falls back to a plain substring search.
+    pub search_text: String,
+    pub search_regex: bool,
+    /// User-defined event types the search can name with `type:`.
+    pub patterns: PatternSet,
+    /// Ignored handles on top of `ignored_player`, and the friendly/hostile lists for
+    /// `roster_scope`.
+    pub roster: Roster,
+    pub roster_scope: RosterScope,
+    /// Only keep events inside this range, e.g. the bounds of one game session.
+    pub time_range: Option<RangeInclusive<DateTime<Utc>>>,
+}
+
+impl Default for EventFilter {
+    fn default() -> Self {
+        Self {
+            show_kills: true,
+            show_spawns: true,
+            show_corpse: true,
+            show_zone_moves: true,
+            show_status_effects: true,
+            show_hits: true,
+            show_vehicle_destruction: true,
+            show_custom: true,
+            ignored_player: String::new(),
+            search_text: String::new(),
+            search_regex: false,
+            patterns: PatternSet::default(),
+            roster: Roster::default(),
+            roster_scope: RosterScope::All,
+            time_range: None,
+        }
+    }
+}
+
+impl EventFilter {
+    pub fn shows(&self, category: EventCategory) -> bool {
+        match category {
+            EventCategory::Kill => self.show_kills,
+            EventCategory::Spawn => self.show_spawns,
+            EventCategory::Corpse => self.show_corpse,
+            EventCategory::Zone => self.show_zone_moves,
+            EventCategory::Status => self.show_status_effects,
+            EventCategory::Hit => self.show_hits,
+            EventCategory::Vehicle => self.show_vehicle_destruction,
+            EventCategory::Custom => self.show_custom,
+        }
+    }
+
+    pub fn set_shown(&mut self, category: EventCategory, shown: bool) {
+        let flag = match category {
+            EventCategory::Kill => &mut self.show_kills,
+            EventCategory::Spawn => &mut self.show_spawns,
+            EventCategory::Corpse => &mut self.show_corpse,
+            EventCategory::Zone => &mut self.show_zone_moves,
+            EventCategory::Status => &mut self.show_status_effects,
+            EventCategory::Hit => &mut self.show_hits,
+            EventCategory::Vehicle => &mut self.show_vehicle_destruction,
+            EventCategory::Custom => &mut self.show_custom,
+        };
+        *flag = shown;
+    }
+
+    /// Parses or compiles the search text. Callers that filter repeatedly should keep the
+    /// result until the text or the regex toggle changes.
+    pub fn compile_search(&self) -> CompiledSearch {
+        if self.search_text.trim().is_empty() {
+            return CompiledSearch {
+                matcher: SearchMatcher::Everything,
+                error: None,
+            };
+        }
+        let fallback = |error: String| CompiledSearch {
+            matcher: SearchMatcher::Substring(self.search_text.to_lowercase()),
+            error: Some(error),
+        };
+        if self.search_regex {
+            return match RegexBuilder::new(&self.search_text)
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(regex) => CompiledSearch {
+                    matcher: SearchMatcher::Regex(regex),
+                    error: None,
+                },
+                Err(err) => fallback(format!("Invalid regex: {}", err)),
+            };
+        }
+        match Query::parse(&self.search_text, &self.patterns) {
+            Ok(Some(query)) => CompiledSearch {
+                matcher: SearchMatcher::Query(query),
+                error: None,
+            },
+            Ok(None) => CompiledSearch {
+                matcher: SearchMatcher::Everything,
+                error: None,
+            },
+            Err(err) => fallback(err.to_string()),
+        }
+    }
+
+    pub fn matches(&self, event: &PlayerEvent) -> bool {
+        self.matches_compiled(event, &self.compile_search())
+    }
+
+    pub fn apply<'a>(&self, events: &'a [PlayerEvent]) -> Vec<&'a PlayerEvent> {
+        self.apply_compiled(events, &self.compile_search())
+    }
+
+    /// Like [`EventFilter::apply`] with a search compiled earlier.
+    pub fn apply_compiled<'a>(
+        &self,
+        events: &'a [PlayerEvent],
+        search: &CompiledSearch,
+    ) -> Vec<&'a PlayerEvent> {
+        events
+            .iter()
+            .filter(|event| self.matches_compiled(event, search))
+            .collect()
+    }
+
+    pub fn in_time_range(&self, event: &PlayerEvent) -> bool {
+        self.time_range
+            .as_ref()
+            .is_none_or(|range| range.contains(&event.timestamp))
+    }
+
+    pub fn matches_compiled(&self, event: &PlayerEvent, search: &CompiledSearch) -> bool {
+        self.matches_compiled_in(event, search, self.time_range.as_ref())
+    }
+
+    /// Like [`EventFilter::matches_compiled`], with `time_range` in place of the filter's own.
+    pub fn matches_compiled_in(
+        &self,
+        event: &PlayerEvent,
+        search: &CompiledSearch,
+        time_range: Option<&RangeInclusive<DateTime<Utc>>>,
+    ) -> bool {
+        let in_range = time_range.is_none_or(|range| range.contains(&event.timestamp));
+        if !self.shows(EventCategory::of(&event.kind)) || !in_range {
+            return false;
+        }
+        let ignored = self.ignored_player.trim();
+        if !ignored.is_empty() && event.should_ignore(ignored) {
+            return false;
+        }
+        if self.roster.should_ignore(event) || !self.roster.in_scope(event, self.roster_scope) {
+            return false;
+        }
+        search.is_match(event)
+    }
+}
+
+/// The search box, ready to be matched against events.
+#[derive(Clone, Debug)]
+pub struct CompiledSearch {
+    matcher: SearchMatcher,
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+enum SearchMatcher {
+    Everything,
+    Query(Query),
+    Substring(String),
+    Regex(Regex),
+}
+
+impl CompiledSearch {
+    /// Why the search text could not be used as typed. The search then matches it as plain
+    /// text.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn is_match(&self, event: &PlayerEvent) -> bool {
+        match &self.matcher {
+            SearchMatcher::Everything => true,
+            SearchMatcher::Query(query) => query.matches(event),
+            SearchMatcher::Substring(needle) => event.matches_search(needle),
+            SearchMatcher::Regex(regex) => {
+                regex.is_match(&event.summary_line())
+                    || event.detail_lines().iter().any(|line| regex.is_match(line))
+                    || regex.is_match(&event.raw)
+            }
+        }
+    }
+
+    /// Byte ranges of `text` to highlight. Only regex searches highlight anything.
+    pub fn highlights(&self, text: &str) -> Vec<Range<usize>> {
+        match &self.matcher {
+            SearchMatcher::Regex(regex) => regex
+                .find_iter(text)
+                .map(|found| found.range())
+                .filter(|range| !range.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categories_parse_from_their_key() {
+        for category in EventCategory::ALL {
+            assert_eq!(category.key().parse::<EventCategory>(), Ok(category));
+            assert_eq!(
+                category.key().to_ascii_uppercase().parse::<EventCategory>(),
+                Ok(category)
+            );
+        }
+    }
+
+    #[test]
+    fn categories_parse_from_their_plural() {
+        let plurals = [
+            ("kills", EventCategory::Kill),
+            ("spawns", EventCategory::Spawn),
+            ("corpses", EventCategory::Corpse),
+            ("zones", EventCategory::Zone),
+            ("statuses", EventCategory::Status),
+            ("hits", EventCategory::Hit),
+            ("vehicles", EventCategory::Vehicle),
+            ("customs", EventCategory::Custom),
+        ];
+        for (plural, category) in plurals {
+            assert_eq!(plural.parse::<EventCategory>(), Ok(category), "{}", plural);
+        }
+    }
+
+    #[test]
+    fn unknown_categories_are_rejected() {
+        for value in ["", "statu", "killss", "weapon"] {
+            assert!(value.parse::<EventCategory>().is_err(), "{}", value);
+        }
+    }
+}
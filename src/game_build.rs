@@ -0,0 +1,176 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// The release channel, taken from the install folder the game was started from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum GameChannel {
+    Live,
+    Ptu,
+    Eptu,
+    TechPreview,
+}
+
+impl GameChannel {
+    pub const ALL: [GameChannel; 4] = [
+        GameChannel::Live,
+        GameChannel::Ptu,
+        GameChannel::Eptu,
+        GameChannel::TechPreview,
+    ];
+
+    /// The folder name the launcher installs the channel into.
+    pub fn folder(self) -> &'static str {
+        match self {
+            GameChannel::Live => "LIVE",
+            GameChannel::Ptu => "PTU",
+            GameChannel::Eptu => "EPTU",
+            GameChannel::TechPreview => "TECH-PREVIEW",
+        }
+    }
+
+    /// Finds the channel folder in the path of the game executable.
+    pub fn from_path(path: &str) -> Option<Self> {
+        path.split(['\\', '/']).find_map(|component| {
+            Self::ALL
+                .into_iter()
+                .find(|channel| component.eq_ignore_ascii_case(channel.folder()))
+        })
+    }
+}
+
+impl fmt::Display for GameChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.folder())
+    }
+}
+
+/// A game patch such as `3.24.1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GameVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl GameVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// The first `major.minor[.patch]` in `text`, e.g. in `sc-alpha-3.24.1` or `4.0`.
+    pub fn parse(text: &str) -> Option<Self> {
+        static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+)\.(\d+)(?:\.(\d+))?").unwrap());
+        let caps = RE.captures(text)?;
+        let number = |index: usize| match caps.get(index) {
+            Some(m) => m.as_str().parse().ok(),
+            None => Some(0),
+        };
+        Some(Self::new(number(1)?, number(2)?, number(3)?))
+    }
+}
+
+impl Serialize for GameVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The game build that wrote a log, read from the header lines at the top of `Game.log`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct GameBuild {
+    pub channel: Option<GameChannel>,
+    pub version: Option<GameVersion>,
+    pub changelist: Option<u64>,
+    /// The branch as logged, e.g. `sc-alpha-3.24.1`.
+    pub branch: Option<String>,
+}
+
+impl GameBuild {
+    /// Takes what a header line tells about the build. Returns whether it was one.
+    pub fn read_header_line(&mut self, line: &str) -> bool {
+        static RE: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"^(?:<[^>]+>\s*)?(?P<key>Executable|FileVersion|ProductVersion|Branch|Changelist):\s*(?P<value>\S.*?)\s*$")
+                .unwrap()
+        });
+        static FILE_VERSION: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"^\d+\.\d+\.\d+\.(?P<changelist>\d+)$").unwrap());
+
+        let Some(caps) = RE.captures(line) else {
+            return false;
+        };
+        let value = &caps["value"];
+        match &caps["key"] {
+            "Executable" => {
+                self.channel = GameChannel::from_path(value).or(self.channel);
+            }
+            "Branch" => {
+                self.branch = Some(value.to_string());
+                self.version = GameVersion::parse(value).or(self.version);
+            }
+            "Changelist" => {
+                self.changelist = value.parse().ok().or(self.changelist);
+            }
+            // `FileVersion` and `ProductVersion` carry the changelist as the last number.
+            _ => {
+                self.version = self.version.or_else(|| GameVersion::parse(value));
+                self.changelist = self.changelist.or_else(|| {
+                    FILE_VERSION
+                        .captures(value)
+                        .and_then(|caps| caps["changelist"].parse().ok())
+                });
+            }
+        }
+        true
+    }
+
+    pub fn is_known(&self) -> bool {
+        self.channel.is_some()
+            || self.version.is_some()
+            || self.changelist.is_some()
+            || self.branch.is_some()
+    }
+
+    /// Whether all header lines the parser looks for were seen.
+    pub fn is_complete(&self) -> bool {
+        self.channel.is_some()
+            && self.version.is_some()
+            && self.changelist.is_some()
+            && self.branch.is_some()
+    }
+}
+
+impl fmt::Display for GameBuild {
+    /// `LIVE 3.24.1 (9270245)`, leaving out what the header did not say.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(channel) = self.channel {
+            parts.push(channel.to_string());
+        }
+        match (self.version, &self.branch) {
+            (Some(version), _) => parts.push(version.to_string()),
+            (None, Some(branch)) => parts.push(branch.clone()),
+            (None, None) => {}
+        }
+        if let Some(changelist) = self.changelist {
+            parts.push(format!("({})", changelist));
+        }
+        if parts.is_empty() {
+            f.write_str("unknown")
+        } else {
+            f.write_str(&parts.join(" "))
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::filter::EventCategory;
+use std::collections::BTreeMap;
+
+/// Unmatched lines kept per parser to show what changed.
+pub const MAX_SAMPLES: usize = 5;
+
+/// The built-in parsers with the text every line they handle contains. A line that contains
+/// all markers of a parser but does not parse most likely means the game changed the format.
+const PARSERS: [(EventCategory, &str, &[&str]); 7] = [
+    (EventCategory::Kill, "parse_actor_death", &["<Actor Death>"]),
+    (
+        EventCategory::Spawn,
+        "parse_spawn_reservation",
+        &["<Spawn Flow>", "lost reservation"],
+    ),
+    // Enabled corpses are not events.
+    (
+        EventCategory::Corpse,
+        "parse_corpse_status",
+        &["IsCorpseEnabled: No"],
+    ),
+    (
+        EventCategory::Zone,
+        "parse_zone_transfer",
+        &["moving zone hosted child"],
+    ),
+    (
+        EventCategory::Status,
+        "parse_status_effect",
+        &["of a status effect"],
+    ),
+    (EventCategory::Hit, "parse_hit_event", &["Fake hit FROM"]),
+    (
+        EventCategory::Vehicle,
+        "parse_vehicle_destruction",
+        &["<Vehicle Destruction>"],
+    ),
+];
+
+/// Counts for one parser.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParserStats {
+    pub parsed: u64,
+    /// Lines with the parser's markers that no parser recognized.
+    pub unmatched: u64,
+    /// The first unmatched lines, at most [`MAX_SAMPLES`].
+    pub samples: Vec<String>,
+}
+
+/// How well the parsers kept up with a log: the lines each one recognized and the lines that
+/// look like its events but failed to parse.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParserHealth {
+    stats: BTreeMap<EventCategory, ParserStats>,
+}
+
+impl ParserHealth {
+    pub fn record_parsed(&mut self, category: EventCategory) {
+        self.stats.entry(category).or_default().parsed += 1;
+    }
+
+    /// Checks a line that no parser recognized for the markers of a built-in parser.
+    pub fn record_unparsed(&mut self, line: &str) {
+        let Some((category, _, _)) = PARSERS
+            .iter()
+            .find(|(_, _, markers)| markers.iter().all(|marker| line.contains(marker)))
+        else {
+            return;
+        };
+        let stats = self.stats.entry(*category).or_default();
+        stats.unmatched += 1;
+        if stats.samples.len() < MAX_SAMPLES {
+            stats.samples.push(line.to_string());
+        }
+    }
+
+    /// Adds the counts of a later part of the same log, or of another log.
+    pub fn merge(&mut self, other: ParserHealth) {
+        for (category, other) in other.stats {
+            let stats = self.stats.entry(category).or_default();
+            stats.parsed += other.parsed;
+            stats.unmatched += other.unmatched;
+            let room = MAX_SAMPLES.saturating_sub(stats.samples.len());
+            stats.samples.extend(other.samples.into_iter().take(room));
+        }
+    }
+
+    /// Unmatched lines over all parsers.
+    pub fn unmatched(&self) -> u64 {
+        self.stats.values().map(|stats| stats.unmatched).sum()
+    }
+
+    pub fn stats(&self, category: EventCategory) -> ParserStats {
+        self.stats.get(&category).cloned().unwrap_or_default()
+    }
+
+    /// The built-in parsers in category order, with the function name and the markers.
+    pub fn parsers() -> impl Iterator<Item = (EventCategory, &'static str, &'static [&'static str])>
+    {
+        PARSERS.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_reader;
+    use crate::patterns::PatternSet;
+
+    fn health(lines: &[String]) -> ParserHealth {
+        let log = lines.join("\n") + "\n";
+        parse_reader(log.as_bytes(), &PatternSet::default())
+            .expect("reads")
+            .health
+    }
+
+    fn broken_status(index: usize) -> String {
+        format!(
+            "<2024-09-14T18:00:00.000Z> Logged a sort-of start of a status effect #{}",
+            index
+        )
+    }
+
+    #[test]
+    fn lines_with_parser_markers_count_as_unmatched() {
+        let mut lines = vec![
+            "<2024-09-14T18:00:00.000Z> [Notice] <Actor Death> CActor::Kill: 'A' [1] in zone 'Z' killed by 'B' [2] using 'W_1' [Class W] with damage type 'Bullet' from direction x: 0, y: 0, z: 0 [Team_ActorTech][Actor]".to_string(),
+            "<2024-09-14T18:00:01.000Z> [Notice] <Actor Death> something new".to_string(),
+            "<2024-09-14T18:00:02.000Z> [Notice] <Spawn Flow> without a reservation".to_string(),
+            "<2024-09-14T18:00:03.000Z> [Notice] Unrelated line".to_string(),
+        ];
+        lines.extend((0..MAX_SAMPLES + 1).map(broken_status));
+        let health = health(&lines);
+
+        let kills = health.stats(EventCategory::Kill);
+        assert_eq!((kills.parsed, kills.unmatched), (1, 1));
+        assert_eq!(kills.samples, [lines[1].clone()]);
+        assert_eq!(health.stats(EventCategory::Spawn), ParserStats::default());
+        let status = health.stats(EventCategory::Status);
+        assert_eq!(status.unmatched, MAX_SAMPLES as u64 + 1);
+        assert_eq!(status.samples, lines[4..4 + MAX_SAMPLES]);
+        assert_eq!(health.unmatched(), MAX_SAMPLES as u64 + 2);
+    }
+
+    #[test]
+    fn merged_counts_add_up_and_keep_the_first_samples() {
+        let mut total = health(&[broken_status(0), broken_status(1)]);
+        let later: Vec<String> = (2..2 + MAX_SAMPLES).map(broken_status).collect();
+        total.merge(health(&later));
+
+        let status = total.stats(EventCategory::Status);
+        assert_eq!(status.unmatched, MAX_SAMPLES as u64 + 2);
+        assert_eq!(
+            status.samples,
+            (0..MAX_SAMPLES).map(broken_status).collect::<Vec<_>>()
+        );
+    }
+}
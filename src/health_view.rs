@@ -0,0 +1,81 @@
+use eframe::egui::{self, Color32, RichText};
+use sc_log_analyzer::ParserHealth;
+
+/// Per-parser counts of the loaded log and samples of the lines that look like events but did
+/// not parse, which usually means a game update changed the log format.
+#[derive(Default)]
+pub struct HealthWindow {
+    pub open: bool,
+}
+
+impl HealthWindow {
+    pub fn show(&mut self, ctx: &egui::Context, health: &ParserHealth) {
+        if !self.open {
+            return;
+        }
+        let mut open = self.open;
+        egui::Window::new("Parser health")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(
+                        "Lines that contain the markers of an event type but match no parser.",
+                    )
+                    .color(Color32::from_rgb(160, 160, 160)),
+                );
+                egui::Grid::new("parser_health")
+                    .num_columns(4)
+                    .spacing([16.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("Parser").strong());
+                        ui.label(RichText::new("Markers").strong());
+                        ui.label(RichText::new("Parsed").strong());
+                        ui.label(RichText::new("Unmatched").strong());
+                        ui.end_row();
+                        for (category, parser, markers) in ParserHealth::parsers() {
+                            let stats = health.stats(category);
+                            ui.label(RichText::new(parser).monospace())
+                                .on_hover_text(category.key());
+                            ui.label(RichText::new(markers.join(" … ")).monospace());
+                            ui.label(stats.parsed.to_string());
+                            let color = if stats.unmatched > 0 {
+                                Color32::from_rgb(240, 170, 80)
+                            } else {
+                                Color32::from_rgb(140, 210, 140)
+                            };
+                            ui.colored_label(color, stats.unmatched.to_string());
+                            ui.end_row();
+                        }
+                    });
+
+                for (category, parser, _) in ParserHealth::parsers() {
+                    let stats = health.stats(category);
+                    if stats.samples.is_empty() {
+                        continue;
+                    }
+                    ui.add_space(4.0);
+                    egui::CollapsingHeader::new(format!(
+                        "{}: {} unmatched",
+                        parser, stats.unmatched
+                    ))
+                    .id_source(parser)
+                    .show(ui, |ui| {
+                        for sample in &stats.samples {
+                            ui.label(RichText::new(sample).monospace());
+                        }
+                        if ui
+                            .small_button("Copy")
+                            .on_hover_text("Copy the samples, e.g. for a bug report")
+                            .clicked()
+                        {
+                            let text = stats.samples.join("\n");
+                            ui.output_mut(|output| output.copied_text = text);
+                        }
+                    });
+                }
+            });
+        self.open = open;
+    }
+}
@@ -0,0 +1,309 @@
+use crate::event::{EventKind, PlayerEvent};
+use crate::filter::EventCategory;
+use crate::health::ParserHealth;
+use crate::metadata::LogMetadata;
+use crate::parser::ParsedLog;
+use crate::session::{SessionMarker, SessionMarkerKind};
+use chrono::{DateTime, Datelike, SecondsFormat, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::{fs, ops::RangeInclusive, path::Path};
+
+const SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS events (
+        id INTEGER PRIMARY KEY,
+        timestamp TEXT NOT NULL,
+        category TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        raw TEXT NOT NULL,
+        UNIQUE (timestamp, raw)
+    );
+    CREATE INDEX IF NOT EXISTS events_by_time ON events (timestamp);
+    CREATE TABLE IF NOT EXISTS markers (
+        id INTEGER PRIMARY KEY,
+        timestamp TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        UNIQUE (timestamp, kind)
+    );
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+/// SQLite database that keeps every ingested event, so history survives the game overwriting
+/// `Game.log`. Events are deduplicated by timestamp and raw line, so the same log can be
+/// ingested any number of times.
+pub struct EventStore {
+    conn: Connection,
+}
+
+impl EventStore {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(dir) = path.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            fs::create_dir_all(dir)
+                .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+        }
+        let conn = Connection::open(path)
+            .map_err(|err| format!("Failed to open database {}: {}", path.display(), err))?;
+        let store = Self { conn };
+        store
+            .migrate()
+            .map_err(|err| format!("Failed to prepare database {}: {}", path.display(), err))?;
+        Ok(store)
+    }
+
+    fn migrate(&self) -> Result<(), String> {
+        let version: i64 = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(|err| err.to_string())?;
+        if version > SCHEMA_VERSION {
+            return Err(format!(
+                "database schema {} is newer than this version supports ({})",
+                version, SCHEMA_VERSION
+            ));
+        }
+        self.conn
+            .execute_batch(SCHEMA)
+            .and_then(|()| {
+                self.conn
+                    .pragma_update(None, "user_version", SCHEMA_VERSION)
+            })
+            .map_err(|err| err.to_string())
+    }
+
+    /// Stores events and session markers, skipping those already in the database.
+    /// Returns the number of new events.
+    pub fn insert(
+        &mut self,
+        events: &[PlayerEvent],
+        markers: &[SessionMarker],
+    ) -> Result<usize, String> {
+        let tx = self.conn.transaction().map_err(db_error)?;
+        let mut inserted = 0;
+        {
+            let mut insert_event = tx
+                .prepare_cached(
+                    "INSERT OR IGNORE INTO events (timestamp, category, kind, raw)
+                     VALUES (?1, ?2, ?3, ?4)",
+                )
+                .map_err(db_error)?;
+            for event in events {
+                let kind = serde_json::to_string(&event.kind)
+                    .map_err(|err| format!("Failed to encode event: {}", err))?;
+                inserted += insert_event
+                    .execute(params![
+                        format_timestamp(event.timestamp),
+                        EventCategory::of(&event.kind).key(),
+                        kind,
+                        event.raw,
+                    ])
+                    .map_err(db_error)?;
+            }
+
+            let mut insert_marker = tx
+                .prepare_cached("INSERT OR IGNORE INTO markers (timestamp, kind) VALUES (?1, ?2)")
+                .map_err(db_error)?;
+            for marker in markers {
+                let kind = serde_json::to_string(&marker.kind)
+                    .map_err(|err| format!("Failed to encode session marker: {}", err))?;
+                insert_marker
+                    .execute(params![format_timestamp(marker.timestamp), kind])
+                    .map_err(db_error)?;
+            }
+        }
+        tx.commit().map_err(db_error)?;
+        Ok(inserted)
+    }
+
+    /// Convenience for ingesting a whole parsed log, including its nickname.
+    pub fn insert_log(&mut self, log: &ParsedLog) -> Result<usize, String> {
+        if let Some(nickname) = &log.primary_nickname {
+            self.set_primary_nickname(nickname)?;
+        }
+        self.insert(&log.events, &log.markers)
+    }
+
+    /// Remembers the player the history belongs to. The latest nickname wins.
+    pub fn set_primary_nickname(&self, nickname: &str) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT INTO meta (key, value) VALUES ('primary_nickname', ?1)
+                 ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+                params![nickname],
+            )
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    pub fn event_count(&self) -> Result<usize, String> {
+        self.conn
+            .query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0))
+            .map_err(db_error)
+    }
+
+    /// Loads the stored history, newest first like [`crate::parse_log`], optionally limited to
+    /// a time range.
+    pub fn load(&self, range: Option<&RangeInclusive<DateTime<Utc>>>) -> Result<ParsedLog, String> {
+        let from = range.and_then(|range| range_bound(*range.start()));
+        let to = range.and_then(|range| range_bound(*range.end()));
+
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT timestamp, kind, raw FROM events
+                 WHERE (?1 IS NULL OR timestamp >= ?1) AND (?2 IS NULL OR timestamp <= ?2)
+                 ORDER BY timestamp DESC, id DESC",
+            )
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map(params![from, to], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(db_error)?;
+        let mut events = Vec::new();
+        for row in rows {
+            let (timestamp, kind, raw) = row.map_err(db_error)?;
+            let kind: EventKind = serde_json::from_str(&kind)
+                .map_err(|err| format!("Corrupt event in database: {}", err))?;
+            events.push(PlayerEvent {
+                timestamp: parse_stored_timestamp(&timestamp)?,
+                kind,
+                raw,
+            });
+        }
+
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT timestamp, kind FROM markers
+                 WHERE (?1 IS NULL OR timestamp >= ?1) AND (?2 IS NULL OR timestamp <= ?2)
+                 ORDER BY timestamp, id",
+            )
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map(params![from, to], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(db_error)?;
+        let mut markers = Vec::new();
+        for row in rows {
+            let (timestamp, kind) = row.map_err(db_error)?;
+            let kind: SessionMarkerKind = serde_json::from_str(&kind)
+                .map_err(|err| format!("Corrupt session marker in database: {}", err))?;
+            markers.push(SessionMarker {
+                timestamp: parse_stored_timestamp(&timestamp)?,
+                kind,
+            });
+        }
+
+        let primary_nickname = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'primary_nickname'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_error)?;
+
+        Ok(ParsedLog {
+            events,
+            primary_nickname,
+            markers,
+            health: ParserHealth::default(),
+            metadata: LogMetadata::default(),
+        })
+    }
+}
+
+/// Fixed-width UTC timestamps, so that text order is time order.
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Open ended ranges use the extremes of `DateTime`, which do not format to four digit years.
+fn range_bound(timestamp: DateTime<Utc>) -> Option<String> {
+    (0..=9999)
+        .contains(&timestamp.year())
+        .then(|| format_timestamp(timestamp))
+}
+
+fn parse_stored_timestamp(raw: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(raw)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|err| format!("Corrupt timestamp {:?} in database: {}", raw, err))
+}
+
+fn db_error(err: impl std::fmt::Display) -> String {
+    format!("Database error: {}", err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_reader;
+    use crate::patterns::PatternSet;
+
+    fn log(times: &[&str]) -> ParsedLog {
+        let lines: String = times
+            .iter()
+            .map(|time| {
+                format!(
+                    "<2024-09-14T{}.000Z> Logged a start of a status effect! nickname: Me, status effect: Bleeding\n",
+                    time
+                )
+            })
+            .collect();
+        parse_reader(lines.as_bytes(), &PatternSet::default()).expect("reads")
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        format!("2024-09-14T{}Z", time).parse().expect("timestamp")
+    }
+
+    fn times(parsed: &ParsedLog) -> Vec<DateTime<Utc>> {
+        parsed.events.iter().map(|event| event.timestamp).collect()
+    }
+
+    #[test]
+    fn ingesting_a_log_twice_stores_it_once() {
+        let mut store = EventStore::open(Path::new(":memory:")).expect("opens");
+        let parsed = log(&["18:00:00", "18:00:01", "18:00:02"]);
+
+        assert_eq!(store.insert_log(&parsed), Ok(3));
+        assert_eq!(store.insert_log(&parsed), Ok(0));
+        assert_eq!(store.event_count(), Ok(3));
+        assert_eq!(
+            times(&store.load(None).expect("loads")),
+            [at("18:00:02"), at("18:00:01"), at("18:00:00")]
+        );
+    }
+
+    #[test]
+    fn ranges_may_be_open_ended() {
+        let mut store = EventStore::open(Path::new(":memory:")).expect("opens");
+        store
+            .insert_log(&log(&["18:00:00", "18:00:01", "18:00:02"]))
+            .expect("inserts");
+
+        let until = DateTime::<Utc>::MIN_UTC..=at("18:00:01");
+        assert_eq!(
+            times(&store.load(Some(&until)).expect("loads")),
+            [at("18:00:01"), at("18:00:00")]
+        );
+        let since = at("18:00:01")..=DateTime::<Utc>::MAX_UTC;
+        assert_eq!(
+            times(&store.load(Some(&since)).expect("loads")),
+            [at("18:00:02"), at("18:00:01")]
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use sc_log_analyzer::{EventStore, PlayerEvent, session::SessionMarker};
+use std::{
+    path::Path,
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+enum HistoryBatch {
+    Events {
+        events: Vec<PlayerEvent>,
+        markers: Vec<SessionMarker>,
+    },
+    PrimaryNickname(String),
+}
+
+/// Writes ingested events into the history database on a worker thread, so large logs do not
+/// stall the UI.
+pub struct HistoryWriter {
+    tx: Sender<HistoryBatch>,
+    errors: Receiver<String>,
+}
+
+impl HistoryWriter {
+    pub fn spawn(path: &Path) -> Result<Self, String> {
+        let mut store = EventStore::open(path)?;
+        let (tx, rx) = mpsc::channel();
+        let (error_tx, errors) = mpsc::channel();
+        std::thread::spawn(move || {
+            for batch in rx {
+                let result = match batch {
+                    HistoryBatch::Events { events, markers } => {
+                        store.insert(&events, &markers).map(|_| ())
+                    }
+                    HistoryBatch::PrimaryNickname(nickname) => {
+                        store.set_primary_nickname(&nickname)
+                    }
+                };
+                if let Err(err) = result
+                    && error_tx.send(err).is_err()
+                {
+                    return;
+                }
+            }
+        });
+        Ok(Self { tx, errors })
+    }
+
+    pub fn record(&self, events: &[PlayerEvent], markers: &[SessionMarker]) {
+        if events.is_empty() && markers.is_empty() {
+            return;
+        }
+        let _ = self.tx.send(HistoryBatch::Events {
+            events: events.to_vec(),
+            markers: markers.to_vec(),
+        });
+    }
+
+    pub fn record_primary_nickname(&self, nickname: &str) {
+        let _ = self
+            .tx
+            .send(HistoryBatch::PrimaryNickname(nickname.to_string()));
+    }
+
+    /// The latest write failure, if any happened since the last call.
+    pub fn take_error(&self) -> Option<String> {
+        self.errors.try_iter().last()
+    }
+}
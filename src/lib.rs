@@ -0,0 +1,58 @@
+//! Parsing for Star Citizen `Game.log` files.
+//!
+//! The GUI binary is built on top of this crate, but everything here works without it:
+//! `parse_line` recognizes a single line, `parse_reader`/`parse_log` handle whole logs and
+//! `LogTail` follows a log that is still being written. `LogMetadata` holds what the log
+//! header says about the game build and the machine; the build selects the `ParserProfile` for
+//! its log format. `PatternSet` adds event types defined in a user-editable pattern file; it is
+//! handed to the log parsers and to `Query::parse`.
+//! `scan_library` and `merge_logs` work on the rotated logs in `logbackups`, and `EventStore`
+//! keeps events in a SQLite database across game sessions. `AlertEngine` checks newly tailed
+//! events against alert rules and `render_payload` fills in webhook templates.
+
+pub mod alerts;
+pub mod engagement;
+pub mod event;
+pub mod export;
+pub mod filter;
+pub mod game_build;
+pub mod health;
+pub mod history;
+pub mod library;
+pub mod log_tail;
+pub mod metadata;
+pub mod parser;
+pub mod patterns;
+pub mod profile;
+pub mod query;
+pub mod recap;
+pub mod roster;
+pub mod session;
+pub mod stats;
+pub mod webhook;
+
+pub use alerts::{Alert, AlertContext, AlertEngine, AlertRule, AlertTrigger};
+pub use engagement::{DEFAULT_ENGAGEMENT_GAP_SECS, Engagement, Outcome};
+pub use event::{
+    CorpseStatusEvent, CustomEvent, EventKind, HitEvent, KillEvent, PlayerEvent,
+    SpawnReservationEvent, StatusEffectEvent, VehicleDestructionEvent, ZoneTransferEvent,
+    describe_destroy_levels,
+};
+pub use export::{
+    ExportFormat, csv_columns, csv_values, export_csv_per_category, export_engagements_to_path,
+    export_metadata, export_to_path, metadata_path, write_engagements, write_events,
+};
+pub use filter::{CompiledSearch, EventCategory, EventFilter};
+pub use game_build::{GameBuild, GameChannel, GameVersion};
+pub use health::{ParserHealth, ParserStats};
+pub use history::EventStore;
+pub use library::{LogSummary, is_backup, merge_logs, scan_library};
+pub use log_tail::{LogChunk, LogTail, TailUpdate, merge_newest_first};
+pub use metadata::LogMetadata;
+pub use parser::{LineParser, ParsedLog, parse_line, parse_log, parse_reader};
+pub use patterns::{EventPattern, PatternSet};
+pub use profile::{EventParser, ParserProfile};
+pub use query::{Query, QueryError};
+pub use recap::{DEFAULT_RECAP_WINDOW_SECS, DeathRecap};
+pub use roster::{Roster, RosterList, RosterScope};
+pub use webhook::{WebhookConfig, render_payload};
@@ -0,0 +1,282 @@
+use crate::event::PlayerEvent;
+use crate::filter::EventCategory;
+use crate::health::ParserHealth;
+use crate::log_tail::merge_newest_first;
+use crate::metadata::LogMetadata;
+use crate::parser::{ParsedLog, parse_log};
+use crate::patterns::PatternSet;
+use chrono::{DateTime, Utc};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Folder next to `Game.log` that the game rotates old logs into.
+pub const BACKUPS_DIR: &str = "logbackups";
+
+/// Overview of one log file for the log library.
+#[derive(Clone, Debug)]
+pub struct LogSummary {
+    pub path: PathBuf,
+    pub size: u64,
+    pub primary_nickname: Option<String>,
+    /// Earliest and latest timestamp of any event or session marker in the file.
+    pub first_timestamp: Option<DateTime<Utc>>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    pub event_count: usize,
+    /// Events per category, in the order of [`EventCategory::ALL`].
+    pub category_counts: Vec<(EventCategory, usize)>,
+}
+
+impl LogSummary {
+    /// Parses the whole file. Meant to run on a worker thread for larger libraries.
+    pub fn from_path(path: &Path, patterns: &PatternSet) -> Result<Self, String> {
+        let size = fs::metadata(path)
+            .map(|meta| meta.len())
+            .map_err(|err| format!("Failed to open {}: {}", path.display(), err))?;
+        let parsed = parse_log(path, patterns)?;
+
+        let timestamps = parsed
+            .events
+            .iter()
+            .map(|event| event.timestamp)
+            .chain(parsed.markers.iter().map(|marker| marker.timestamp));
+        let (first_timestamp, last_timestamp) =
+            timestamps.fold((None, None), |(first, last), timestamp| {
+                (
+                    Some(first.map_or(timestamp, |first: DateTime<Utc>| first.min(timestamp))),
+                    Some(last.map_or(timestamp, |last: DateTime<Utc>| last.max(timestamp))),
+                )
+            });
+
+        let category_counts = EventCategory::ALL
+            .into_iter()
+            .map(|category| {
+                let count = parsed
+                    .events
+                    .iter()
+                    .filter(|event| EventCategory::of(&event.kind) == category)
+                    .count();
+                (category, count)
+            })
+            .collect();
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            size,
+            primary_nickname: parsed.primary_nickname,
+            first_timestamp,
+            last_timestamp,
+            event_count: parsed.events.len(),
+            category_counts,
+        })
+    }
+}
+
+/// Whether `path` is a rotated log in `logbackups`, which the game no longer writes to.
+pub fn is_backup(path: &Path) -> bool {
+    path.parent()
+        .and_then(|parent| parent.file_name())
+        .is_some_and(|name| name.eq_ignore_ascii_case(BACKUPS_DIR))
+}
+
+/// Finds the logs of a game install: the live `Game.log` followed by the `.log` files in
+/// `logbackups`, newest first. `install_dir` may also point at `Game.log` or at `logbackups`.
+pub fn scan_library(install_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let install_dir = if install_dir.is_file() {
+        install_dir.parent().unwrap_or(Path::new("."))
+    } else if install_dir
+        .file_name()
+        .is_some_and(|name| name.eq_ignore_ascii_case(BACKUPS_DIR))
+    {
+        install_dir.parent().unwrap_or(install_dir)
+    } else {
+        install_dir
+    };
+
+    let mut logs = Vec::new();
+    let backups_dir = install_dir.join(BACKUPS_DIR);
+    if backups_dir.is_dir() {
+        let entries = fs::read_dir(&backups_dir)
+            .map_err(|err| format!("Failed to read {}: {}", backups_dir.display(), err))?;
+        let mut backups: Vec<(Option<std::time::SystemTime>, PathBuf)> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .is_some_and(|ext| ext.eq_ignore_ascii_case("log"))
+            })
+            .map(|path| {
+                let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+                (modified, path)
+            })
+            .collect();
+        backups.sort_by(|a, b| b.cmp(a));
+        logs.extend(backups.into_iter().map(|(_, path)| path));
+    }
+
+    let live_log = install_dir.join("Game.log");
+    if live_log.is_file() {
+        logs.insert(0, live_log);
+    }
+    if logs.is_empty() {
+        return Err(format!(
+            "No Game.log or {} folder found in {}",
+            BACKUPS_DIR,
+            install_dir.display()
+        ));
+    }
+    Ok(logs)
+}
+
+/// Parses several logs into one timeline. Each file starts with a log start marker, so the
+/// sessions of different files stay apart. A file named twice, e.g. by overlapping globs, is
+/// read once. The nickname and the log metadata are taken from the first file that has them.
+pub fn merge_logs(paths: &[PathBuf], patterns: &PatternSet) -> Result<ParsedLog, String> {
+    let mut events: Vec<PlayerEvent> = Vec::new();
+    let mut markers = Vec::new();
+    let mut health = ParserHealth::default();
+    let mut primary_nickname = None;
+    let mut metadata = LogMetadata::default();
+    let mut seen = Vec::new();
+    for path in paths {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if seen.contains(&canonical) {
+            continue;
+        }
+        seen.push(canonical);
+        let parsed = parse_log(path, patterns)?;
+        merge_newest_first(&mut events, parsed.events);
+        markers.extend(parsed.markers);
+        health.merge(parsed.health);
+        primary_nickname = primary_nickname.or(parsed.primary_nickname);
+        if !metadata.is_known() {
+            metadata = parsed.metadata;
+        }
+    }
+    markers.sort_by_key(|marker| marker.timestamp);
+    Ok(ParsedLog {
+        events,
+        primary_nickname,
+        markers,
+        health,
+        metadata,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs::File, time::SystemTime};
+
+    fn temp_install(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "sc_log_analyzer-library-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join(BACKUPS_DIR)).expect("temp dir is writable");
+        dir
+    }
+
+    fn write_log(path: &Path, lines: &[&str], modified_secs: u64) {
+        fs::write(path, lines.join("\n") + "\n").expect("temp log is writable");
+        File::options()
+            .write(true)
+            .open(path)
+            .and_then(|file| {
+                file.set_modified(
+                    SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(modified_secs),
+                )
+            })
+            .expect("modification time is settable");
+    }
+
+    fn status(time: &str, nickname: &str) -> String {
+        format!(
+            "<2024-09-14T{}.000Z> Logged a start of a status effect! nickname: {}, status effect: Bleeding",
+            time, nickname
+        )
+    }
+
+    fn log_started(time: &str) -> String {
+        format!(
+            "<2024-09-14T{}.000Z> Log started on Sat Sep 14 20:00:00 2024",
+            time
+        )
+    }
+
+    #[test]
+    fn the_live_log_comes_before_the_newest_backups() {
+        let dir = temp_install("scan");
+        let backups = dir.join(BACKUPS_DIR);
+        write_log(&backups.join("older.log"), &[], 1_000);
+        write_log(&backups.join("newer.log"), &[], 2_000);
+        write_log(&backups.join("notes.txt"), &[], 3_000);
+        write_log(&dir.join("Game.log"), &[], 500);
+
+        let expected = [
+            dir.join("Game.log"),
+            backups.join("newer.log"),
+            backups.join("older.log"),
+        ];
+        assert_eq!(scan_library(&dir), Ok(expected.to_vec()));
+        assert_eq!(scan_library(&dir.join("Game.log")), Ok(expected.to_vec()));
+        assert_eq!(scan_library(&backups), Ok(expected.to_vec()));
+        assert!(is_backup(&expected[1]));
+        assert!(!is_backup(&expected[0]));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn merged_logs_interleave_and_are_read_once() {
+        let dir = temp_install("merge");
+        let first = dir.join(BACKUPS_DIR).join("first.log");
+        let second = dir.join("Game.log");
+        write_log(
+            &first,
+            &[
+                &log_started("18:00:00"),
+                &status("18:00:01", "Me"),
+                &status("18:00:03", "Me"),
+            ],
+            0,
+        );
+        write_log(
+            &second,
+            &[
+                &log_started("19:00:00"),
+                "<2024-09-14T19:00:01.000Z> [Notice] AccountLoginCharacterStatus_Character - nickname=\"Other\"",
+                &status("18:00:02", "Other"),
+            ],
+            0,
+        );
+
+        let merged = merge_logs(
+            &[
+                second.clone(),
+                first.clone(),
+                dir.join(".").join("Game.log"),
+            ],
+            &PatternSet::default(),
+        )
+        .expect("merges");
+        let times: Vec<String> = merged
+            .events
+            .iter()
+            .map(|event| event.timestamp.format("%H:%M:%S").to_string())
+            .collect();
+        assert_eq!(times, ["18:00:03", "18:00:02", "18:00:01"]);
+        let marker_times: Vec<String> = merged
+            .markers
+            .iter()
+            .map(|marker| marker.timestamp.format("%H:%M").to_string())
+            .collect();
+        assert_eq!(marker_times, ["18:00", "19:00"]);
+        assert_eq!(merged.primary_nickname.as_deref(), Some("Other"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
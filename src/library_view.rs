@@ -0,0 +1,286 @@
+use eframe::egui::{self, Color32, RichText};
+use rfd::FileDialog;
+use sc_log_analyzer::{LogSummary, PatternSet, scan_library};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+/// What the user picked in the log library window.
+pub enum LibraryAction {
+    Open(PathBuf),
+    Merge(Vec<PathBuf>),
+}
+
+struct LibraryEntry {
+    path: PathBuf,
+    summary: Option<Result<LogSummary, String>>,
+    selected: bool,
+}
+
+struct SummaryMessage {
+    generation: u64,
+    path: PathBuf,
+    summary: Result<LogSummary, String>,
+}
+
+/// Lists `Game.log` and the rotated logs of an install, summarized on a worker thread.
+pub struct LibraryWindow {
+    open: bool,
+    install_dir: String,
+    entries: Vec<LibraryEntry>,
+    scan_error: Option<String>,
+    scan_generation: u64,
+    tx: Sender<SummaryMessage>,
+    rx: Receiver<SummaryMessage>,
+}
+
+impl LibraryWindow {
+    pub fn new(install_dir: Option<String>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            open: false,
+            install_dir: install_dir.unwrap_or_default(),
+            entries: Vec::new(),
+            scan_error: None,
+            scan_generation: 0,
+            tx,
+            rx,
+        }
+    }
+
+    /// The folder last scanned or typed in, for the settings.
+    pub fn install_dir(&self) -> Option<&str> {
+        Some(self.install_dir.trim()).filter(|dir| !dir.is_empty())
+    }
+
+    /// Opens the window and scans `default_dir` unless a folder was scanned before.
+    pub fn open(&mut self, default_dir: Option<PathBuf>, patterns: &PatternSet) {
+        self.open = true;
+        if self.install_dir.trim().is_empty()
+            && let Some(dir) = default_dir.as_deref().and_then(Path::to_str)
+        {
+            self.install_dir = dir.to_string();
+        }
+        if self.entries.is_empty() {
+            self.scan(patterns);
+        }
+    }
+
+    /// Summarizes the logs with `patterns` for the custom events.
+    fn scan(&mut self, patterns: &PatternSet) {
+        self.scan_generation += 1;
+        self.entries.clear();
+        self.scan_error = None;
+        let dir = PathBuf::from(self.install_dir.trim());
+        if dir.as_os_str().is_empty() {
+            self.scan_error = Some("Choose the folder that contains Game.log.".to_string());
+            return;
+        }
+        let paths = match scan_library(&dir) {
+            Ok(paths) => paths,
+            Err(err) => {
+                self.scan_error = Some(err);
+                return;
+            }
+        };
+        self.entries = paths
+            .iter()
+            .map(|path| LibraryEntry {
+                path: path.clone(),
+                summary: None,
+                selected: false,
+            })
+            .collect();
+
+        let generation = self.scan_generation;
+        let tx = self.tx.clone();
+        let patterns = patterns.clone();
+        std::thread::spawn(move || {
+            for path in paths {
+                let summary = LogSummary::from_path(&path, &patterns);
+                let message = SummaryMessage {
+                    generation,
+                    path,
+                    summary,
+                };
+                if tx.send(message).is_err() {
+                    return;
+                }
+            }
+        });
+    }
+
+    fn poll(&mut self) {
+        while let Ok(message) = self.rx.try_recv() {
+            if message.generation != self.scan_generation {
+                continue;
+            }
+            if let Some(entry) = self
+                .entries
+                .iter_mut()
+                .find(|entry| entry.path == message.path)
+            {
+                entry.summary = Some(message.summary);
+            }
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, patterns: &PatternSet) -> Option<LibraryAction> {
+        self.poll();
+        if !self.open {
+            return None;
+        }
+
+        let mut action = None;
+        let mut open = self.open;
+        egui::Window::new("Log library")
+            .collapsible(false)
+            .default_width(640.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new("Install folder:").color(Color32::from_rgb(210, 210, 210)),
+                    );
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.install_dir).desired_width(320.0),
+                    );
+                    if response.lost_focus()
+                        && ui.input(|input| input.key_pressed(egui::Key::Enter))
+                    {
+                        self.scan(patterns);
+                    }
+                    if ui.button("Browse…").clicked() {
+                        let mut dialog = FileDialog::new();
+                        let current = PathBuf::from(self.install_dir.trim());
+                        if current.is_dir() {
+                            dialog = dialog.set_directory(current);
+                        }
+                        if let Some(dir) = dialog.pick_folder()
+                            && let Some(dir) = dir.to_str()
+                        {
+                            self.install_dir = dir.to_string();
+                            self.scan(patterns);
+                        }
+                    }
+                    if ui.button("Rescan").clicked() {
+                        self.scan(patterns);
+                    }
+                });
+                if let Some(error) = &self.scan_error {
+                    ui.colored_label(Color32::from_rgb(240, 90, 80), error);
+                }
+                ui.add_space(6.0);
+
+                egui::ScrollArea::vertical()
+                    .max_height(360.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("library_entries")
+                            .num_columns(5)
+                            .striped(true)
+                            .spacing([12.0, 6.0])
+                            .show(ui, |ui| {
+                                for entry in &mut self.entries {
+                                    ui.checkbox(&mut entry.selected, "");
+                                    let name = entry
+                                        .path
+                                        .file_name()
+                                        .map(|name| name.to_string_lossy().into_owned())
+                                        .unwrap_or_else(|| entry.path.display().to_string());
+                                    ui.label(
+                                        RichText::new(name).color(Color32::from_rgb(220, 220, 220)),
+                                    )
+                                    .on_hover_text(entry.path.display().to_string());
+                                    match &entry.summary {
+                                        None => {
+                                            ui.spinner();
+                                            ui.label("");
+                                        }
+                                        Some(Err(err)) => {
+                                            ui.colored_label(
+                                                Color32::from_rgb(240, 90, 80),
+                                                "Failed to read",
+                                            )
+                                            .on_hover_text(err);
+                                            ui.label("");
+                                        }
+                                        Some(Ok(summary)) => {
+                                            ui.label(
+                                                RichText::new(date_range(summary))
+                                                    .color(Color32::from_rgb(190, 190, 190)),
+                                            );
+                                            ui.label(
+                                                RichText::new(format!(
+                                                    "{} events",
+                                                    summary.event_count
+                                                ))
+                                                .color(Color32::from_rgb(190, 190, 190)),
+                                            )
+                                            .on_hover_text(category_breakdown(summary));
+                                        }
+                                    }
+                                    if ui.button("Open").clicked() {
+                                        action = Some(LibraryAction::Open(entry.path.clone()));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.add_space(6.0);
+                let selected: Vec<PathBuf> = self
+                    .entries
+                    .iter()
+                    .filter(|entry| entry.selected)
+                    .map(|entry| entry.path.clone())
+                    .collect();
+                ui.horizontal(|ui| {
+                    let merge = ui.add_enabled(
+                        selected.len() >= 2,
+                        egui::Button::new(
+                            RichText::new(format!("Merge {} selected", selected.len()))
+                                .color(Color32::WHITE),
+                        )
+                        .fill(Color32::from_rgb(0, 95, 145)),
+                    );
+                    if merge.clicked() {
+                        action = Some(LibraryAction::Merge(selected.clone()));
+                    }
+                    ui.label(
+                        RichText::new(
+                            "Merged logs are shown as one timeline with a session per log.",
+                        )
+                        .color(Color32::from_rgb(150, 150, 150)),
+                    );
+                });
+            });
+        self.open = open && action.is_none();
+        action
+    }
+}
+
+fn date_range(summary: &LogSummary) -> String {
+    match (summary.first_timestamp, summary.last_timestamp) {
+        (Some(first), Some(last)) if first.date_naive() == last.date_naive() => format!(
+            "{} – {}",
+            first.format("%Y-%m-%d %H:%M"),
+            last.format("%H:%M")
+        ),
+        (Some(first), Some(last)) => format!(
+            "{} – {}",
+            first.format("%Y-%m-%d %H:%M"),
+            last.format("%Y-%m-%d %H:%M")
+        ),
+        _ => "no timestamps".to_string(),
+    }
+}
+
+fn category_breakdown(summary: &LogSummary) -> String {
+    summary
+        .category_counts
+        .iter()
+        .map(|(category, count)| format!("{}: {}", category, count))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,54 @@
+use eframe::egui::{self, Color32, RichText};
+use sc_log_analyzer::{LogMetadata, ParserProfile};
+
+/// The game build, machine and shards from the header of the loaded log, to copy into bug
+/// reports.
+#[derive(Default)]
+pub struct LogInfoWindow {
+    pub open: bool,
+}
+
+impl LogInfoWindow {
+    pub fn show(&mut self, ctx: &egui::Context, metadata: Option<&LogMetadata>) {
+        if !self.open {
+            return;
+        }
+        let mut open = self.open;
+        egui::Window::new("Log info")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let Some(metadata) = metadata.filter(|metadata| metadata.is_known()) else {
+                    ui.label(
+                        RichText::new("The log has no header with build or system information.")
+                            .color(Color32::from_rgb(160, 160, 160)),
+                    );
+                    return;
+                };
+                egui::Grid::new("log_info")
+                    .num_columns(2)
+                    .spacing([12.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (label, value) in metadata.fields() {
+                            ui.label(RichText::new(label).strong());
+                            ui.add(egui::Label::new(value).wrap(true));
+                            ui.end_row();
+                        }
+                        ui.label(RichText::new("Parser profile").strong());
+                        ui.label(ParserProfile::select(&metadata.build).name);
+                        ui.end_row();
+                    });
+                ui.add_space(4.0);
+                if ui
+                    .button("Copy")
+                    .on_hover_text("Copy as text, e.g. for a bug report")
+                    .clicked()
+                {
+                    let text = metadata.to_text();
+                    ui.output_mut(|output| output.copied_text = text);
+                }
+            });
+        self.open = open;
+    }
+}
@@ -0,0 +1,170 @@
+use sc_log_analyzer::{EventStore, LogChunk, LogTail, PatternSet, is_backup};
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+    },
+};
+
+pub enum LoadMessage {
+    /// Events parsed since the last batch, together with the progress so far.
+    Batch {
+        generation: u64,
+        chunk: LogChunk,
+        primary_nickname: Option<String>,
+        processed: u64,
+        total: u64,
+    },
+    /// `tail` is only set when a single log was parsed; merged logs are not followed.
+    Finished {
+        generation: u64,
+        tail: Option<Box<LogTail>>,
+    },
+    Failed {
+        generation: u64,
+        error: String,
+    },
+}
+
+/// A full parse of one or more logs running on a worker thread.
+pub struct LoadHandle {
+    pub generation: u64,
+    pub processed: u64,
+    pub total: u64,
+    cancel: Arc<AtomicBool>,
+}
+
+impl LoadHandle {
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (self.processed as f64 / self.total as f64).min(1.0) as f32
+        }
+    }
+}
+
+/// Parses `paths` one after another. Progress is reported over the combined size of all files.
+/// Merged logs and backups are complete, so their last line is parsed even without a newline.
+pub fn spawn_full_parse(
+    paths: Vec<PathBuf>,
+    patterns: PatternSet,
+    generation: u64,
+    tx: Sender<LoadMessage>,
+) -> LoadHandle {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let total = paths
+        .iter()
+        .map(|path| std::fs::metadata(path).map(|meta| meta.len()).unwrap_or(0))
+        .sum();
+    let handle = LoadHandle {
+        generation,
+        processed: 0,
+        total,
+        cancel: Arc::clone(&cancel),
+    };
+
+    std::thread::spawn(move || {
+        let single = paths.len() == 1;
+        let mut sent_nickname = false;
+        let mut done_bytes = 0;
+        let mut last_tail = None;
+        for path in &paths {
+            let mut tail = LogTail::new(path, patterns.clone());
+            let mut file_bytes = 0;
+            let mut send = |chunk: LogChunk, processed: u64, nickname: Option<&str>| {
+                let primary_nickname = nickname.filter(|_| !sent_nickname).map(str::to_string);
+                sent_nickname |= primary_nickname.is_some();
+                let processed = done_bytes + processed;
+                tx.send(LoadMessage::Batch {
+                    generation,
+                    chunk,
+                    primary_nickname,
+                    processed,
+                    total: total.max(processed),
+                })
+                .is_ok()
+            };
+            let result = tail.read_appended_in_batches(|chunk, processed, nickname| {
+                if cancel.load(Ordering::Relaxed) {
+                    return false;
+                }
+                file_bytes = processed;
+                send(chunk, processed, nickname)
+            });
+            match result {
+                Ok(true) if !single || is_backup(path) => {
+                    let rest = tail.finish();
+                    if !send(rest, file_bytes, tail.primary_nickname()) {
+                        return;
+                    }
+                }
+                Ok(true) => {}
+                Ok(false) => return,
+                Err(error) => {
+                    let _ = tx.send(LoadMessage::Failed { generation, error });
+                    return;
+                }
+            }
+            done_bytes += file_bytes;
+            last_tail = Some(tail);
+        }
+
+        let tail = last_tail.filter(|_| single).map(Box::new);
+        let _ = tx.send(LoadMessage::Finished { generation, tail });
+    });
+
+    handle
+}
+
+/// Loads the history database on a worker thread. The whole history arrives as one batch.
+pub fn spawn_history_load(path: PathBuf, generation: u64, tx: Sender<LoadMessage>) -> LoadHandle {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let handle = LoadHandle {
+        generation,
+        processed: 0,
+        total: std::fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0),
+        cancel: Arc::clone(&cancel),
+    };
+    let total = handle.total;
+
+    std::thread::spawn(move || {
+        let loaded = EventStore::open(&path).and_then(|store| store.load(None));
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        let message = match loaded {
+            Ok(parsed) => {
+                let batch = LoadMessage::Batch {
+                    generation,
+                    chunk: LogChunk {
+                        events: parsed.events,
+                        markers: parsed.markers,
+                        health: parsed.health,
+                        metadata: None,
+                    },
+                    primary_nickname: parsed.primary_nickname,
+                    processed: total,
+                    total,
+                };
+                if tx.send(batch).is_err() {
+                    return;
+                }
+                LoadMessage::Finished {
+                    generation,
+                    tail: None,
+                }
+            }
+            Err(error) => LoadMessage::Failed { generation, error },
+        };
+        let _ = tx.send(message);
+    });
+
+    handle
+}
@@ -0,0 +1,383 @@
+use crate::event::PlayerEvent;
+use crate::health::ParserHealth;
+use crate::metadata::LogMetadata;
+use crate::parser::LineParser;
+use crate::patterns::PatternSet;
+use crate::session::SessionMarker;
+use std::{
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+/// Number of leading bytes remembered to notice a log that was replaced in place.
+const FINGERPRINT_LEN: usize = 512;
+const READ_CHUNK_LEN: usize = 256 * 1024;
+
+/// Follows a log file across refreshes and only parses bytes appended since the last read.
+pub struct LogTail {
+    path: PathBuf,
+    offset: u64,
+    partial: Vec<u8>,
+    identity: Option<FileIdentity>,
+    fingerprint: Vec<u8>,
+    parser: LineParser,
+}
+
+/// Everything recognized in one stretch of newly read lines.
+#[derive(Default)]
+pub struct LogChunk {
+    pub events: Vec<PlayerEvent>,
+    pub markers: Vec<SessionMarker>,
+    pub health: ParserHealth,
+    /// The log header and the shards joined so far, once any of it was read.
+    pub metadata: Option<Box<LogMetadata>>,
+}
+
+pub enum TailUpdate {
+    Unchanged,
+    Appended(LogChunk),
+    /// The file was truncated or replaced; the caller has to start over with a full parse.
+    Reset,
+}
+
+impl LogTail {
+    pub fn new(path: &Path, patterns: PatternSet) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            offset: 0,
+            partial: Vec::new(),
+            identity: None,
+            fingerprint: Vec::new(),
+            parser: LineParser::new(patterns),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn primary_nickname(&self) -> Option<&str> {
+        self.parser.primary_nickname()
+    }
+
+    /// Parses everything between the remembered offset and the current end of the file.
+    /// A trailing line without a newline is kept back until the rest of it arrives.
+    pub fn read_appended(&mut self) -> Result<LogChunk, String> {
+        let mut chunk = LogChunk::default();
+        self.read_appended_in_batches(|batch, _, _| {
+            chunk.events.extend(batch.events);
+            chunk.markers.extend(batch.markers);
+            chunk.health.merge(batch.health);
+            chunk.metadata = batch.metadata.or(chunk.metadata.take());
+            true
+        })?;
+        Ok(chunk)
+    }
+
+    /// Like `read_appended`, but hands the results over after every chunk together with the
+    /// number of bytes consumed so far and the nickname detected up to that point. Returning
+    /// `false` from the callback stops reading; the result tells whether the end of the file
+    /// was reached.
+    pub fn read_appended_in_batches(
+        &mut self,
+        mut on_batch: impl FnMut(LogChunk, u64, Option<&str>) -> bool,
+    ) -> Result<bool, String> {
+        let mut file = File::open(&self.path)
+            .map_err(|err| format!("Failed to open {}: {}", self.path.display(), err))?;
+        let metadata = file
+            .metadata()
+            .map_err(|err| format!("Failed to stat {}: {}", self.path.display(), err))?;
+        if self.offset == 0 {
+            self.identity = FileIdentity::from_metadata(&metadata);
+        }
+
+        file.seek(SeekFrom::Start(self.offset))
+            .map_err(|err| format!("Failed to seek in {}: {}", self.path.display(), err))?;
+
+        let mut chunk = vec![0; READ_CHUNK_LEN];
+        loop {
+            let read = match file.read(&mut chunk) {
+                Ok(0) => return Ok(true),
+                Ok(read) => read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    return Err(format!("Failed to read {}: {}", self.path.display(), err));
+                }
+            };
+            self.remember_fingerprint(&chunk[..read]);
+            self.offset += read as u64;
+            let mut events = Vec::new();
+            self.consume(&chunk[..read], &mut events);
+            let batch = LogChunk {
+                events,
+                markers: self.parser.take_markers(),
+                health: self.parser.take_health(),
+                metadata: Some(self.parser.metadata())
+                    .filter(|metadata| metadata.is_known())
+                    .map(|metadata| Box::new(metadata.clone())),
+            };
+            if !on_batch(batch, self.offset, self.parser.primary_nickname()) {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Parses the trailing line that has no newline, for files that are known to be complete,
+    /// such as rotated backups or logs that are merged. A log that is still being written must
+    /// not be finished, or a line cut off mid-write would be parsed as it is.
+    pub fn finish(&mut self) -> LogChunk {
+        let mut events = Vec::new();
+        let line = std::mem::take(&mut self.partial);
+        if !line.is_empty()
+            && let Some(event) = self.parser.push_line(&line)
+        {
+            events.push(event);
+        }
+        LogChunk {
+            events,
+            markers: self.parser.take_markers(),
+            health: self.parser.take_health(),
+            metadata: Some(self.parser.metadata())
+                .filter(|metadata| metadata.is_known())
+                .map(|metadata| Box::new(metadata.clone())),
+        }
+    }
+
+    fn consume(&mut self, bytes: &[u8], events: &mut Vec<PlayerEvent>) {
+        let mut data = std::mem::take(&mut self.partial);
+        data.extend_from_slice(bytes);
+        let mut start = 0;
+        while let Some(position) = data[start..].iter().position(|byte| *byte == b'\n') {
+            let end = start + position;
+            if let Some(event) = self.parser.push_line(&data[start..end]) {
+                events.push(event);
+            }
+            start = end + 1;
+        }
+        data.drain(..start);
+        self.partial = data;
+    }
+
+    /// Checks the file for new content without re-reading what was already parsed.
+    pub fn poll(&mut self) -> Result<TailUpdate, String> {
+        let metadata = fs::metadata(&self.path)
+            .map_err(|err| format!("Failed to stat {}: {}", self.path.display(), err))?;
+        let len = metadata.len();
+
+        if len < self.offset {
+            return Ok(TailUpdate::Reset);
+        }
+        let identity = FileIdentity::from_metadata(&metadata);
+        if identity.is_some() && self.identity.is_some() && identity != self.identity {
+            return Ok(TailUpdate::Reset);
+        }
+        if len == self.offset {
+            return Ok(TailUpdate::Unchanged);
+        }
+        if !self.fingerprint_matches()? {
+            return Ok(TailUpdate::Reset);
+        }
+
+        self.read_appended().map(TailUpdate::Appended)
+    }
+
+    fn remember_fingerprint(&mut self, appended: &[u8]) {
+        let missing = FINGERPRINT_LEN.saturating_sub(self.fingerprint.len());
+        let take = missing.min(appended.len());
+        self.fingerprint.extend_from_slice(&appended[..take]);
+    }
+
+    fn fingerprint_matches(&self) -> Result<bool, String> {
+        if self.fingerprint.is_empty() {
+            return Ok(true);
+        }
+        let mut file = File::open(&self.path)
+            .map_err(|err| format!("Failed to open {}: {}", self.path.display(), err))?;
+        let mut head = vec![0; self.fingerprint.len()];
+        match file.read_exact(&mut head) {
+            Ok(()) => Ok(head == self.fingerprint),
+            Err(_) => Ok(false),
+        }
+    }
+}
+
+/// Identifies the file behind a path so a rotated log is not mistaken for the old one.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct FileIdentity {
+    device: u64,
+    inode: u64,
+}
+
+impl FileIdentity {
+    #[cfg(unix)]
+    fn from_metadata(metadata: &fs::Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+        Some(Self {
+            device: metadata.dev(),
+            inode: metadata.ino(),
+        })
+    }
+
+    #[cfg(windows)]
+    fn from_metadata(metadata: &fs::Metadata) -> Option<Self> {
+        use std::os::windows::fs::MetadataExt;
+        // File indices are not exposed on stable, the creation time changes when the game
+        // moves the old log into logbackups and starts a fresh one.
+        Some(Self {
+            device: 0,
+            inode: metadata.creation_time(),
+        })
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn from_metadata(_metadata: &fs::Metadata) -> Option<Self> {
+        None
+    }
+}
+
+/// Merges freshly parsed events into a list that is kept newest first.
+pub fn merge_newest_first(events: &mut Vec<PlayerEvent>, mut incoming: Vec<PlayerEvent>) {
+    if incoming.is_empty() {
+        return;
+    }
+    incoming.sort_by_key(|event| event.timestamp);
+    incoming.reverse();
+
+    let oldest_incoming = incoming.last().map(|event| event.timestamp);
+    let newest_existing = events.first().map(|event| event.timestamp);
+    match (oldest_incoming, newest_existing) {
+        (_, None) => {
+            *events = incoming;
+            return;
+        }
+        (Some(oldest), Some(newest)) if oldest >= newest => {
+            events.splice(0..0, incoming);
+            return;
+        }
+        _ => {}
+    }
+
+    let existing = std::mem::take(events);
+    let mut merged = Vec::with_capacity(existing.len() + incoming.len());
+    let mut existing = existing.into_iter().peekable();
+    let mut incoming = incoming.into_iter().peekable();
+    loop {
+        let take_incoming = match (incoming.peek(), existing.peek()) {
+            (Some(new), Some(old)) => new.timestamp >= old.timestamp,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => break,
+        };
+        let next = if take_incoming {
+            incoming.next()
+        } else {
+            existing.next()
+        };
+        merged.extend(next);
+    }
+    *events = merged;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventKind;
+    use std::io::Write;
+
+    fn line(second: u32, effect: &str) -> String {
+        format!(
+            "<2024-09-14T18:00:{:02}.000Z> Logged a start of a status effect! nickname: Me, status effect: {}\n",
+            second, effect
+        )
+    }
+
+    fn temp_log(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "sc_log_analyzer-tail-{}-{}.log",
+            std::process::id(),
+            name
+        ));
+        fs::write(&path, contents).expect("temp log is writable");
+        path
+    }
+
+    fn append(path: &Path, text: &str) {
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .expect("temp log opens");
+        file.write_all(text.as_bytes())
+            .expect("temp log is writable");
+    }
+
+    fn effects(events: &[PlayerEvent]) -> Vec<&str> {
+        events
+            .iter()
+            .map(|event| match &event.kind {
+                EventKind::StatusEffect(status) => status.effect.as_str(),
+                _ => "",
+            })
+            .collect()
+    }
+
+    fn appended(tail: &mut LogTail) -> Vec<PlayerEvent> {
+        match tail.poll().expect("polls") {
+            TailUpdate::Appended(chunk) => chunk.events,
+            TailUpdate::Unchanged => Vec::new(),
+            TailUpdate::Reset => panic!("unexpected reset"),
+        }
+    }
+
+    #[test]
+    fn partial_lines_are_parsed_once_completed() {
+        let second = line(1, "Bleeding");
+        let (head, rest) = second.split_at(30);
+        let path = temp_log("partial", &(line(0, "Burning") + head));
+        let mut tail = LogTail::new(&path, PatternSet::default());
+
+        assert_eq!(effects(&appended(&mut tail)), ["Burning"]);
+        assert!(matches!(tail.poll(), Ok(TailUpdate::Unchanged)));
+        append(&path, rest);
+        append(&path, &line(2, "Stunned"));
+        assert_eq!(effects(&appended(&mut tail)), ["Bleeding", "Stunned"]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn truncated_logs_reset() {
+        let path = temp_log("truncated", &(line(0, "Burning") + &line(1, "Bleeding")));
+        let mut tail = LogTail::new(&path, PatternSet::default());
+        assert_eq!(appended(&mut tail).len(), 2);
+
+        fs::write(&path, line(2, "Stunned")).expect("temp log is writable");
+        assert!(matches!(tail.poll(), Ok(TailUpdate::Reset)));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn logs_replaced_in_place_reset() {
+        let path = temp_log("replaced", &line(0, "Burning"));
+        let mut tail = LogTail::new(&path, PatternSet::default());
+        assert_eq!(appended(&mut tail).len(), 1);
+
+        // The same file, rewritten from the start with a different first line.
+        fs::write(&path, line(1, "Bleeding") + &line(2, "Stunned")).expect("temp log is writable");
+        assert!(matches!(tail.poll(), Ok(TailUpdate::Reset)));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rotated_logs_reset() {
+        let path = temp_log("rotated", &line(0, "Burning"));
+        let mut tail = LogTail::new(&path, PatternSet::default());
+        assert_eq!(appended(&mut tail).len(), 1);
+
+        // Another file with the same start, moved over the followed one.
+        let next = temp_log("rotated-next", &(line(0, "Burning") + &line(1, "Bleeding")));
+        fs::rename(&next, &path).expect("temp log is movable");
+        assert!(matches!(tail.poll(), Ok(TailUpdate::Reset)));
+        let _ = fs::remove_file(&path);
+    }
+}
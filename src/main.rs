@@ -1,30 +1,97 @@
 #![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
 
 use chrono::{DateTime, Utc};
+use clap::Parser;
 use eframe::egui::{self, Color32, IconData, RichText, Sense};
-use once_cell::sync::Lazy;
-use regex::Regex;
 use rfd::FileDialog;
+use sc_log_analyzer::{
+    AlertContext, AlertEngine, CompiledSearch, DeathRecap, Engagement, EventCategory, EventFilter,
+    EventKind, ExportFormat, LogChunk, LogMetadata, LogTail, ParserHealth, ParserProfile,
+    PatternSet, PlayerEvent, RosterScope, TailUpdate, WebhookConfig, merge_newest_first,
+    patterns::EXAMPLE_PATTERNS,
+    render_payload,
+    session::{GameSession, SessionMarker, segment_sessions},
+    stats::CombatStats,
+};
 use std::{
-    collections::{HashMap, HashSet},
-    env,
-    fs::File,
-    io::{BufRead, BufReader},
+    collections::HashMap,
+    env, fs,
+    ops::RangeInclusive,
     path::{Path, PathBuf},
+    process::ExitCode,
     sync::{
         Arc,
         mpsc::{self, Receiver, Sender},
     },
-    time::{Duration, Instant, SystemTime},
+    time::{Duration, Instant},
 };
 
+use alert_notifier::AlertNotifier;
+use alerts_view::AlertsWindow;
+use chart_view::ChartView;
+use health_view::HealthWindow;
+use history_writer::HistoryWriter;
+use library_view::{LibraryAction, LibraryWindow};
+use log_info_view::LogInfoWindow;
+use log_loader::{LoadHandle, LoadMessage};
+use overlay_server::OverlayServer;
+use patterns_view::{PatternsAction, PatternsWindow};
+use roster_view::RosterWindow;
+use settings::{Settings, WindowSize};
+use webhook_sender::WebhookSender;
+use webhook_view::WebhookWindow;
+
+mod alert_notifier;
+mod alerts_view;
+mod chart_view;
+mod cli;
+mod engagements_view;
+mod health_view;
+mod history_writer;
+mod library_view;
+mod log_info_view;
+mod log_loader;
+mod overlay_server;
+mod patterns_view;
 mod player_info;
+mod recap_view;
+mod roster_view;
 mod settings;
+mod stats_view;
+mod webhook_sender;
+mod webhook_view;
+
+const MIN_REFRESH_SECS: f32 = 0.5;
+const MAX_REFRESH_SECS: f32 = 60.0;
+/// How often events streamed in by a running load are added to the view.
+const LOAD_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+fn main() -> ExitCode {
+    let args = cli::Cli::parse();
+    if let Some(command) = args.command {
+        return cli::run(command, args.patterns);
+    }
+
+    match run_gui(args.patterns) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Failed to start the GUI: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-fn main() -> eframe::Result<()> {
+fn run_gui(patterns_path: Option<PathBuf>) -> eframe::Result<()> {
+    let settings = Settings::load();
     let mut native_options = eframe::NativeOptions {
         ..Default::default()
     };
+    if let Some(window) = settings.window {
+        native_options.viewport = native_options
+            .viewport
+            .clone()
+            .with_inner_size([window.width, window.height]);
+    }
 
     if let Some(icon) = load_app_icon() {
         native_options.viewport = native_options.viewport.clone().with_icon(Arc::new(icon));
@@ -32,12 +99,13 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "SC Log Analyzer",
         native_options,
-        Box::new(|cc| Box::new(LogApp::new(cc))),
+        Box::new(move |cc| Box::new(LogApp::new(cc, settings, patterns_path))),
     )
 }
 
 fn load_app_icon() -> Option<IconData> {
-    const ICON_BYTES: &[u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/icon.png"));
+    const ICON_BYTES: &[u8] =
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/icon.png"));
     let image = image::load_from_memory(ICON_BYTES).ok()?.into_rgba8();
     let (width, height) = image.dimensions();
 
@@ -50,22 +118,80 @@ fn load_app_icon() -> Option<IconData> {
 
 struct LogApp {
     file_path_input: String,
+    source: LogSource,
+    history_enabled: bool,
+    history: Option<HistoryWriter>,
+    history_error: Option<String>,
+    library: LibraryWindow,
+    overlay_enabled: bool,
+    overlay_port: u16,
+    overlay: Option<OverlayServer>,
+    overlay_error: Option<String>,
     events: Vec<PlayerEvent>,
+    events_revision: u64,
+    primary_nickname: Option<String>,
+    central_view: CentralView,
+    stats_cache: Option<(u64, Option<TimeRange>, CombatStats)>,
+    recap_window_secs: u32,
+    recap_cache: Option<(u64, Option<TimeRange>, u32, Vec<DeathRecap>)>,
+    engagement_gap_secs: u32,
+    engagement_cache: Option<EngagementCache>,
+    search_cache: Option<(String, bool, CompiledSearch)>,
+    /// Bumped on every change to `filter`, so `filtered_cache` knows when it is stale.
+    filter_generation: u64,
+    filtered_cache: Option<FilteredCache>,
+    session_markers: Vec<SessionMarker>,
+    sessions_cache: Option<(u64, Vec<GameSession>)>,
+    selected_session: Option<usize>,
+    chart: ChartView,
+    show_chart: bool,
+    /// The time range brushed in the chart, applied on top of the selected session.
+    chart_brush: Option<TimeRange>,
+    session_range: Option<TimeRange>,
     app_version: String,
-    filter_show_kills: bool,
-    filter_show_spawns: bool,
-    filter_show_corpse: bool,
-    filter_show_zone_moves: bool,
-    filter_show_status_effects: bool,
-    filter_show_hits: bool,
-    filter_show_vehicle_destruction: bool,
-    search_text: String,
-    ignored_player: String,
+    filter: EventFilter,
     ignored_player_user_override: bool,
+    roster_window: RosterWindow,
+    alerts: AlertEngine,
+    alerts_window: AlertsWindow,
+    alert_notifier: AlertNotifier,
+    alert_error: Option<String>,
+    webhook: WebhookConfig,
+    webhook_window: WebhookWindow,
+    webhook_preview_cache: Option<WebhookPreviewCache>,
+    webhook_sender: WebhookSender,
+    webhook_status: Option<Result<String, String>>,
+    /// The custom event pattern file, `--patterns` or the one in the settings directory.
+    patterns_path: Option<PathBuf>,
+    /// The patterns loaded from it, handed to the parsers, the search and the alert rules.
+    patterns: PatternSet,
+    patterns_window: PatternsWindow,
+    patterns_error: Option<String>,
+    /// Parser counts of everything loaded since the last reload.
+    parser_health: ParserHealth,
+    health_window: HealthWindow,
+    /// The header and shards of the log read last.
+    log_metadata: Option<LogMetadata>,
+    log_info_window: LogInfoWindow,
     load_error: Option<String>,
+    export_status: Option<Result<String, String>>,
     auto_refresh_interval: Duration,
+    /// The settings as last written, to notice changes.
+    saved_settings: Settings,
+    last_settings_save: Instant,
+    last_loaded_path: Option<String>,
+    window_size: Option<WindowSize>,
     last_auto_check: Instant,
-    last_modified: Option<SystemTime>,
+    log_tail: Option<LogTail>,
+    active_load: Option<LoadHandle>,
+    /// Events of the running load that have not been added to the view yet.
+    pending_load: LogChunk,
+    pending_nickname: Option<String>,
+    last_load_flush: Instant,
+    load_cancelled: bool,
+    load_generation: u64,
+    load_tx: Sender<LoadMessage>,
+    load_rx: Receiver<LoadMessage>,
     player_info_cache: HashMap<String, PlayerInfoEntry>,
     player_info_window: Option<String>,
     player_info_tx: Sender<PlayerInfoResponse>,
@@ -73,116 +199,592 @@ struct LogApp {
 }
 
 impl LogApp {
-    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let initial_path = settings::load_last_log_path().unwrap_or_else(|| "Game.log".to_string());
+    fn new(
+        _cc: &eframe::CreationContext<'_>,
+        settings: Settings,
+        patterns_path: Option<PathBuf>,
+    ) -> Self {
+        let initial_path = settings
+            .last_log_path
+            .clone()
+            .unwrap_or_else(|| "Game.log".to_string());
         let (initial_ignored_player, ignored_player_user_override) =
-            match settings::load_ignored_player() {
+            match settings.ignored_player.clone() {
                 Some(value) => (value, true),
                 None => (String::new(), false),
             };
+        let mut filter = EventFilter {
+            ignored_player: initial_ignored_player,
+            search_text: settings.search_text.clone(),
+            search_regex: settings.search_regex,
+            roster: settings.roster.clone(),
+            roster_scope: settings.roster_scope,
+            ..EventFilter::default()
+        };
+        for category in &settings.hidden_categories {
+            filter.set_shown(*category, false);
+        }
         let (player_info_tx, player_info_rx) = mpsc::channel();
+        let (load_tx, load_rx) = mpsc::channel();
         let mut app = Self {
             file_path_input: initial_path,
+            source: LogSource::File,
+            history_enabled: false,
+            history: None,
+            history_error: None,
+            library: LibraryWindow::new(settings.library_dir.clone()),
+            overlay_enabled: false,
+            overlay_port: settings.overlay_port,
+            overlay: None,
+            overlay_error: None,
             events: Vec::new(),
+            events_revision: 0,
+            primary_nickname: None,
+            central_view: CentralView::Timeline,
+            stats_cache: None,
+            recap_window_secs: settings.recap_window_secs.max(1),
+            recap_cache: None,
+            engagement_gap_secs: settings.engagement_gap_secs.max(1),
+            engagement_cache: None,
+            search_cache: None,
+            filter_generation: 0,
+            filtered_cache: None,
+            session_markers: Vec::new(),
+            sessions_cache: None,
+            selected_session: None,
+            chart: ChartView::default(),
+            show_chart: settings.show_chart,
+            chart_brush: None,
+            session_range: None,
             app_version: env!("SC_LOG_ANALYZER_VERSION").to_string(),
-            filter_show_kills: true,
-            filter_show_spawns: true,
-            filter_show_corpse: true,
-            filter_show_zone_moves: true,
-            filter_show_status_effects: true,
-            filter_show_hits: true,
-            filter_show_vehicle_destruction: true,
-            search_text: String::new(),
-            ignored_player: initial_ignored_player,
+            filter,
             ignored_player_user_override,
+            roster_window: RosterWindow::default(),
+            alerts: AlertEngine::new(settings.alert_rules.clone(), PatternSet::default()),
+            alerts_window: AlertsWindow::default(),
+            alert_notifier: AlertNotifier::spawn(),
+            alert_error: None,
+            webhook: settings.webhook.clone(),
+            webhook_window: WebhookWindow::default(),
+            webhook_preview_cache: None,
+            webhook_sender: WebhookSender::spawn(),
+            webhook_status: None,
+            patterns_path: patterns_path.or_else(settings::patterns_path),
+            patterns: PatternSet::default(),
+            patterns_window: PatternsWindow::default(),
+            patterns_error: None,
+            parser_health: ParserHealth::default(),
+            health_window: HealthWindow::default(),
+            log_metadata: None,
+            log_info_window: LogInfoWindow::default(),
             load_error: None,
-            auto_refresh_interval: Duration::from_secs(2),
+            export_status: None,
+            auto_refresh_interval: Duration::from_secs_f32(
+                settings
+                    .auto_refresh_secs
+                    .clamp(MIN_REFRESH_SECS, MAX_REFRESH_SECS),
+            ),
+            saved_settings: Settings::default(),
+            last_settings_save: Instant::now(),
+            last_loaded_path: settings.last_log_path.clone(),
+            window_size: settings.window,
             last_auto_check: Instant::now(),
-            last_modified: None,
+            log_tail: None,
+            active_load: None,
+            pending_load: LogChunk::default(),
+            pending_nickname: None,
+            last_load_flush: Instant::now(),
+            load_cancelled: false,
+            load_generation: 0,
+            load_tx,
+            load_rx,
             player_info_cache: HashMap::new(),
             player_info_window: None,
             player_info_tx,
             player_info_rx,
         };
+        if settings.history_enabled {
+            app.set_history_enabled(true);
+        }
+        if settings.overlay_enabled {
+            app.set_overlay_enabled(true);
+        }
+        app.saved_settings = settings;
+        app.load_patterns();
         app.reload();
         app
     }
 
-    fn reload(&mut self) {
-        let path = resolve_input_path(&self.file_path_input);
-        if path.as_os_str().is_empty() {
-            self.events.clear();
-            self.load_error = Some("No log file selected.".to_string());
-            self.last_modified = None;
+    /// Reads the pattern file. On errors the patterns loaded before stay active.
+    fn load_patterns(&mut self) {
+        let Some(path) = self.patterns_path.as_deref() else {
             return;
+        };
+        match PatternSet::load(path) {
+            Ok(patterns) => {
+                self.filter.patterns = patterns.clone();
+                self.alerts.set_patterns(patterns.clone());
+                self.patterns = patterns;
+                self.patterns_error = None;
+            }
+            Err(err) => self.patterns_error = Some(err),
         }
-        match parse_log(&path) {
-            Ok(parsed) => {
-                self.events = parsed.events;
-                if !self.ignored_player_user_override {
-                    if let Some(nickname) = parsed.primary_nickname {
-                        let trimmed = nickname.trim();
-                        if !trimmed.is_empty() {
-                            self.ignored_player = trimmed.to_string();
-                        }
-                    }
+        // `type:` terms may name a pattern.
+        self.search_cache = None;
+        self.filter_generation += 1;
+    }
+
+    fn create_example_patterns(&mut self) {
+        let Some(path) = self.patterns_path.clone() else {
+            return;
+        };
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, EXAMPLE_PATTERNS));
+        if let Err(err) = written {
+            self.patterns_error = Some(format!("Failed to write {}: {}", path.display(), err));
+            return;
+        }
+        self.load_patterns();
+        self.reload();
+    }
+
+    fn reload(&mut self) {
+        self.cancel_load();
+        self.load_cancelled = false;
+        self.log_tail = None;
+        self.events.clear();
+        self.session_markers.clear();
+        self.parser_health = ParserHealth::default();
+        self.log_metadata = None;
+        self.selected_session = None;
+        self.chart_brush = None;
+        self.primary_nickname = None;
+        self.events_revision += 1;
+        if let Some(overlay) = &self.overlay {
+            overlay.reset();
+        }
+        let paths = match &self.source {
+            LogSource::File => {
+                let path = resolve_input_path(&self.file_path_input);
+                if path.as_os_str().is_empty() {
+                    self.load_error = Some("No log file selected.".to_string());
+                    return;
                 }
+                vec![path]
+            }
+            LogSource::Merged(paths) => paths.clone(),
+            LogSource::History => {
+                let Some(path) = settings::history_database_path() else {
+                    self.load_error = Some("Failed to resolve settings directory".to_string());
+                    return;
+                };
                 self.load_error = None;
-                if let Ok(metadata) = std::fs::metadata(&path) {
-                    self.last_modified = metadata.modified().ok();
+                self.load_generation += 1;
+                self.active_load = Some(log_loader::spawn_history_load(
+                    path,
+                    self.load_generation,
+                    self.load_tx.clone(),
+                ));
+                return;
+            }
+        };
+        for path in &paths {
+            if let Err(err) = std::fs::metadata(path) {
+                self.load_error = Some(format!("Failed to open {}: {}", path.display(), err));
+                self.last_auto_check = Instant::now();
+                return;
+            }
+        }
+        self.load_error = None;
+        self.load_generation += 1;
+        self.active_load = Some(log_loader::spawn_full_parse(
+            paths,
+            self.patterns.clone(),
+            self.load_generation,
+            self.load_tx.clone(),
+        ));
+        self.last_auto_check = Instant::now();
+    }
+
+    /// Stops the running load. Its generation is retired, so messages it already sent cannot
+    /// replace the current view.
+    fn cancel_load(&mut self) {
+        if let Some(load) = self.active_load.take() {
+            load.cancel();
+            self.load_generation += 1;
+        }
+        self.pending_load = LogChunk::default();
+        self.pending_nickname = None;
+    }
+
+    fn poll_load_messages(&mut self) {
+        while let Ok(message) = self.load_rx.try_recv() {
+            match message {
+                LoadMessage::Batch {
+                    generation,
+                    chunk,
+                    primary_nickname,
+                    processed,
+                    total,
+                } => {
+                    let Some(load) = self
+                        .active_load
+                        .as_mut()
+                        .filter(|load| load.generation == generation)
+                    else {
+                        continue;
+                    };
+                    load.processed = processed;
+                    load.total = total;
+                    let LogChunk {
+                        events,
+                        markers,
+                        health,
+                        metadata,
+                    } = chunk;
+                    self.parser_health.merge(health);
+                    if let Some(metadata) = metadata {
+                        self.log_metadata = Some(*metadata);
+                    }
+                    self.pending_load.events.extend(events);
+                    self.pending_load.markers.extend(markers);
+                    if primary_nickname.is_some() {
+                        self.pending_nickname = primary_nickname;
+                    }
+                }
+                LoadMessage::Finished { generation, tail } => {
+                    if generation != self.load_generation {
+                        continue;
+                    }
+                    self.active_load = None;
+                    self.flush_pending_load();
+                    if let Some(tail) = &tail {
+                        self.last_loaded_path = Some(tail.path().to_string_lossy().into_owned());
+                    }
+                    self.log_tail = tail.map(|tail| *tail);
+                    self.last_auto_check = Instant::now();
                 }
-                if let Err(err) = settings::save_last_log_path(&path) {
-                    eprintln!("Failed to persist last log path: {}", err);
+                LoadMessage::Failed { generation, error } => {
+                    if generation != self.load_generation {
+                        continue;
+                    }
+                    self.active_load = None;
+                    self.pending_load = LogChunk::default();
+                    self.pending_nickname = None;
+                    self.events.clear();
+                    self.events_revision += 1;
+                    self.load_error = Some(error);
+                    self.last_auto_check = Instant::now();
                 }
             }
-            Err(err) => {
-                self.events.clear();
-                self.load_error = Some(err);
+        }
+        if self.active_load.is_some() && self.last_load_flush.elapsed() >= LOAD_FLUSH_INTERVAL {
+            self.flush_pending_load();
+        }
+    }
+
+    /// Adds the events a running load streamed in since the last flush. Every flush rebuilds
+    /// the cached views, so batches are collected and added a few times a second.
+    fn flush_pending_load(&mut self) {
+        self.last_load_flush = Instant::now();
+        let chunk = std::mem::take(&mut self.pending_load);
+        let nickname = self.pending_nickname.take();
+        if chunk.events.is_empty() && chunk.markers.is_empty() && nickname.is_none() {
+            return;
+        }
+        self.ingest(chunk.events, chunk.markers, nickname.as_deref());
+    }
+
+    /// Adds newly parsed events to the timeline and, unless they came from there, to the
+    /// history database.
+    fn ingest(
+        &mut self,
+        events: Vec<PlayerEvent>,
+        markers: Vec<SessionMarker>,
+        nickname: Option<&str>,
+    ) {
+        if self.source != LogSource::History
+            && let Some(history) = &self.history
+        {
+            history.record(&events, &markers);
+            if let Some(nickname) = nickname {
+                history.record_primary_nickname(nickname);
             }
         }
-        self.last_auto_check = Instant::now();
+        if let Some(overlay) = &self.overlay {
+            overlay.extend(&events);
+        }
+        merge_newest_first(&mut self.events, events);
+        self.session_markers.extend(markers);
+        if matches!(self.source, LogSource::Merged(_)) {
+            self.session_markers.sort_by_key(|marker| marker.timestamp);
+        }
+        self.events_revision += 1;
+        self.apply_primary_nickname(nickname);
     }
 
-    fn persist_ignored_player(&self) {
-        if !self.ignored_player_user_override {
+    fn set_history_enabled(&mut self, enabled: bool) {
+        self.history_enabled = enabled;
+        self.history = None;
+        self.history_error = None;
+        if !enabled {
             return;
         }
-        if let Err(err) = settings::save_ignored_player(&self.ignored_player) {
-            eprintln!("Failed to persist ignored player: {}", err);
+        match settings::history_database_path()
+            .ok_or_else(|| "Failed to resolve settings directory".to_string())
+            .and_then(|path| HistoryWriter::spawn(&path))
+        {
+            Ok(history) => {
+                // Keep what is already on screen, the database skips events it has seen.
+                if self.source != LogSource::History {
+                    history.record(&self.events, &self.session_markers);
+                    if let Some(nickname) = &self.primary_nickname {
+                        history.record_primary_nickname(nickname);
+                    }
+                }
+                self.history = Some(history);
+            }
+            Err(err) => self.history_error = Some(err),
         }
     }
 
-    fn filtered_events(&self) -> Vec<PlayerEvent> {
-        let search_lower = self.search_text.to_lowercase();
-        let ignored = self.ignored_player.trim();
+    fn set_overlay_enabled(&mut self, enabled: bool) {
+        self.overlay_enabled = enabled;
+        self.overlay = None;
+        self.overlay_error = None;
+        if !enabled {
+            return;
+        }
+        match OverlayServer::start(self.overlay_port) {
+            Ok(overlay) => {
+                overlay.extend(&self.events);
+                self.overlay = Some(overlay);
+            }
+            Err(err) => {
+                self.overlay_enabled = false;
+                self.overlay_error = Some(err);
+            }
+        }
+    }
 
-        self.events
+    /// Sends the events the webhook is set up for. Only followed lines get here, so loading
+    /// an old log never floods the channel.
+    fn post_to_webhook(&mut self, events: &[PlayerEvent], nickname: Option<&str>) {
+        if !self.webhook.enabled {
+            return;
+        }
+        let mut selected: Vec<&PlayerEvent> = events
             .iter()
-            .filter(|event| match event.kind {
-                EventKind::Kill(_) => self.filter_show_kills,
-                EventKind::SpawnReservation(_) => self.filter_show_spawns,
-                EventKind::CorpseStatus(_) => self.filter_show_corpse,
-                EventKind::ZoneTransfer(_) => self.filter_show_zone_moves,
-                EventKind::StatusEffect(_) => self.filter_show_status_effects,
-                EventKind::Hit(_) => self.filter_show_hits,
-                EventKind::VehicleDestruction(_) => self.filter_show_vehicle_destruction,
-            })
-            .filter(|event| {
-                if ignored.is_empty() {
-                    true
-                } else {
-                    !event.should_ignore(ignored)
-                }
-            })
-            .filter(|event| {
-                if search_lower.is_empty() {
-                    true
-                } else {
-                    event.matches_search(&search_lower)
-                }
-            })
-            .cloned()
-            .collect()
+            .filter(|event| self.webhook.selects(event, nickname))
+            .collect();
+        selected.sort_by_key(|event| event.timestamp);
+        for event in selected {
+            let result = render_payload(&self.webhook.template, event)
+                .and_then(|payload| self.webhook_sender.send(&self.webhook, payload));
+            if let Err(err) = result {
+                self.webhook_status = Some(Err(err));
+                return;
+            }
+        }
+    }
+
+    fn show_history(&mut self) {
+        self.source = LogSource::History;
+        self.reload();
+    }
+
+    fn apply_primary_nickname(&mut self, nickname: Option<&str>) {
+        let Some(trimmed) = nickname.map(str::trim).filter(|name| !name.is_empty()) else {
+            return;
+        };
+        if self.primary_nickname.is_none() {
+            self.primary_nickname = Some(trimmed.to_string());
+            self.events_revision += 1;
+        }
+        if !self.ignored_player_user_override && self.filter.ignored_player != trimmed {
+            self.filter.ignored_player = trimmed.to_string();
+            self.filter_generation += 1;
+        }
+    }
+
+    fn current_settings(&self) -> Settings {
+        Settings {
+            last_log_path: self.last_loaded_path.clone(),
+            ignored_player: self
+                .ignored_player_user_override
+                .then(|| self.filter.ignored_player.clone()),
+            hidden_categories: EventCategory::ALL
+                .into_iter()
+                .filter(|category| !self.filter.shows(*category))
+                .collect(),
+            search_text: self.filter.search_text.clone(),
+            search_regex: self.filter.search_regex,
+            roster: self.filter.roster.clone(),
+            roster_scope: self.filter.roster_scope,
+            auto_refresh_secs: self.auto_refresh_interval.as_secs_f32(),
+            window: self.window_size,
+            history_enabled: self.history_enabled,
+            library_dir: self.library.install_dir().map(str::to_string),
+            alert_rules: self.alerts.rules().to_vec(),
+            overlay_enabled: self.overlay_enabled,
+            overlay_port: self.overlay_port,
+            webhook: self.webhook.clone(),
+            show_chart: self.show_chart,
+            recap_window_secs: self.recap_window_secs,
+            engagement_gap_secs: self.engagement_gap_secs,
+            ..Settings::default()
+        }
+    }
+
+    /// Writes the settings when something changed. Unless `force` is set this happens at most
+    /// once a second, so typing in the search box or resizing the window is not a write per
+    /// frame.
+    fn persist_settings(&mut self, force: bool) {
+        if !force && self.last_settings_save.elapsed() < Duration::from_secs(1) {
+            return;
+        }
+        let settings = self.current_settings();
+        if settings == self.saved_settings {
+            return;
+        }
+        self.last_settings_save = Instant::now();
+        match settings.save() {
+            Ok(()) => self.saved_settings = settings,
+            Err(err) => eprintln!("Failed to persist settings: {}", err),
+        }
+    }
+
+    /// The compiled search box, recompiled only when the text or the regex toggle changes.
+    fn search(&mut self) -> CompiledSearch {
+        let stale = self.search_cache.as_ref().is_none_or(|(text, regex, _)| {
+            *text != self.filter.search_text || *regex != self.filter.search_regex
+        });
+        if stale {
+            self.search_cache = Some((
+                self.filter.search_text.clone(),
+                self.filter.search_regex,
+                self.filter.compile_search(),
+            ));
+        }
+        self.search_cache
+            .as_ref()
+            .expect("search cache was just filled")
+            .2
+            .clone()
+    }
+
+    /// Filters the events again if they or the filter changed since the last frame.
+    fn refresh_filtered(&mut self) {
+        let stale = self
+            .filtered_cache
+            .as_ref()
+            .is_none_or(|(revision, generation, _, _)| {
+                *revision != self.events_revision || *generation != self.filter_generation
+            });
+        if !stale {
+            return;
+        }
+        let search = self.search();
+        let mut shown = Vec::new();
+        let mut charted = Vec::new();
+        for (index, event) in self.events.iter().enumerate() {
+            if self.filter.matches_compiled(event, &search) {
+                shown.push(index);
+            }
+            if self
+                .filter
+                .matches_compiled_in(event, &search, self.session_range.as_ref())
+            {
+                charted.push(index);
+            }
+        }
+        self.filtered_cache = Some((self.events_revision, self.filter_generation, shown, charted));
+    }
+
+    fn export_filtered(&mut self) {
+        let mut dialog = FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .add_filter("Newline-delimited JSON", &["ndjson", "jsonl"])
+            .add_filter("CSV", &["csv"])
+            .set_file_name("events.json");
+        if let Some(dir) = self.dialog_start_dir() {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(path) = dialog.save_file() else {
+            return;
+        };
+        let format = ExportFormat::from_path(&path).unwrap_or(ExportFormat::Json);
+        let search = self.search();
+        let events = self.filter.apply_compiled(&self.events, &search);
+        let count = events.len();
+        self.export_status = Some(sc_log_analyzer::export_to_path(&path, &events, format).map(
+            |()| {
+                let status = format!("Exported {} events to {}", count, path.display());
+                self.with_log_metadata_status(status, &path)
+            },
+        ));
+    }
+
+    fn export_filtered_csv_per_category(&mut self) {
+        let mut dialog = FileDialog::new();
+        if let Some(dir) = self.dialog_start_dir() {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(dir) = dialog.pick_folder() else {
+            return;
+        };
+        let search = self.search();
+        let events = self.filter.apply_compiled(&self.events, &search);
+        let count = events.len();
+        self.export_status = Some(sc_log_analyzer::export_csv_per_category(&dir, &events).map(
+            |written| {
+                let status = format!(
+                    "Exported {} events into {} CSV files in {}",
+                    count,
+                    written.len(),
+                    dir.display()
+                );
+                self.with_log_metadata_status(status, &dir)
+            },
+        ));
+    }
+
+    fn export_engagements(&mut self) {
+        let mut dialog = FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .add_filter("Newline-delimited JSON", &["ndjson", "jsonl"])
+            .add_filter("CSV", &["csv"])
+            .set_file_name("engagements.json");
+        if let Some(dir) = self.dialog_start_dir() {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(path) = dialog.save_file() else {
+            return;
+        };
+        let format = ExportFormat::from_path(&path).unwrap_or(ExportFormat::Json);
+        let engagements = self.engagements().to_vec();
+        self.export_status = Some(
+            sc_log_analyzer::export_engagements_to_path(&path, &engagements, format).map(|()| {
+                format!(
+                    "Exported {} engagements to {}",
+                    engagements.len(),
+                    path.display()
+                )
+            }),
+        );
+    }
+
+    /// Writes the log metadata next to an export, if the log had any, and adds where it went
+    /// to the export `status`.
+    fn with_log_metadata_status(&self, mut status: String, path: &Path) -> String {
+        match sc_log_analyzer::export_metadata(path, self.log_metadata.as_ref()) {
+            Ok(Some(written)) => status.push_str(&format!(", log info to {}", written.display())),
+            Ok(None) => {}
+            Err(err) => status.push_str(&format!("; log info not saved: {}", err)),
+        }
+        status
     }
 
     fn maybe_refresh(&mut self) {
@@ -192,6 +794,9 @@ impl LogApp {
 
         self.last_auto_check = Instant::now();
 
+        if self.active_load.is_some() || self.source != LogSource::File {
+            return;
+        }
         let path = resolve_input_path(&self.file_path_input);
         if path.as_os_str().is_empty() {
             return;
@@ -200,15 +805,41 @@ impl LogApp {
             return;
         }
 
-        if let Ok(metadata) = std::fs::metadata(&path) {
-            if let Ok(modified) = metadata.modified() {
-                let changed = self
-                    .last_modified
-                    .map_or(true, |previous| modified > previous);
-                if changed {
-                    self.reload();
+        let Some(tail) = self.log_tail.as_mut().filter(|tail| tail.path() == path) else {
+            if !self.load_cancelled {
+                self.reload();
+            }
+            return;
+        };
+        let had_nickname = tail.primary_nickname().is_some();
+        match tail.poll() {
+            Ok(TailUpdate::Unchanged) => {}
+            Ok(TailUpdate::Appended(chunk)) => {
+                let nickname = tail
+                    .primary_nickname()
+                    .filter(|_| !had_nickname)
+                    .map(str::to_string);
+                // Only lines appended while following the log raise alerts, never a load.
+                let context = AlertContext {
+                    nickname: nickname.as_deref().or(self.primary_nickname.as_deref()),
+                    roster: &self.filter.roster,
+                };
+                for alert in self.alerts.check(&chunk.events, context) {
+                    self.alert_notifier.send(alert);
+                }
+                if let Some(overlay) = &self.overlay {
+                    overlay.broadcast(&chunk.events);
                 }
+                let nickname_for_webhook = nickname.clone().or(self.primary_nickname.clone());
+                self.post_to_webhook(&chunk.events, nickname_for_webhook.as_deref());
+                self.parser_health.merge(chunk.health);
+                if let Some(metadata) = chunk.metadata {
+                    self.log_metadata = Some(*metadata);
+                }
+                self.ingest(chunk.events, chunk.markers, nickname.as_deref());
             }
+            Ok(TailUpdate::Reset) => self.reload(),
+            Err(err) => self.load_error = Some(err),
         }
     }
 
@@ -232,6 +863,7 @@ impl LogApp {
         match path.to_str() {
             Some(as_str) => {
                 self.file_path_input = as_str.to_string();
+                self.source = LogSource::File;
                 self.reload();
             }
             None => {
@@ -302,6 +934,346 @@ impl LogApp {
         });
     }
 
+    fn render_timeline(&mut self, ui: &mut egui::Ui) {
+        let search = self.search();
+        self.refresh_filtered();
+        let (_, _, filtered, charted) = self
+            .filtered_cache
+            .as_ref()
+            .expect("filtered cache was just refreshed");
+        if self.show_chart {
+            // The chart shows the whole session, so the brush can be moved anywhere in it.
+            let charted: Vec<&PlayerEvent> =
+                charted.iter().map(|&index| &self.events[index]).collect();
+            self.chart.show(
+                ui,
+                &charted,
+                self.primary_nickname.as_deref(),
+                &self.patterns,
+                &mut self.chart_brush,
+            );
+            ui.separator();
+        }
+
+        let header_text = format!(
+            "Showing {} events ({} total parsed)",
+            filtered.len(),
+            self.events.len()
+        );
+        ui.label(RichText::new(header_text).color(Color32::from_rgb(200, 200, 200)));
+
+        if filtered.is_empty() {
+            ui.label(
+                RichText::new("No events match the current filters.")
+                    .color(Color32::from_rgb(200, 200, 200)),
+            );
+            return;
+        }
+
+        let mut clicked_player = None;
+        let mut roster_edited = false;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for event in filtered.iter().map(|&index| &self.events[index]) {
+                let summary = event.summary_line();
+                let (fill, text_color, border) = match &event.kind {
+                    EventKind::Kill(_) => (
+                        Color32::from_rgb(50, 25, 30),
+                        Color32::from_rgb(235, 130, 130),
+                        Color32::from_rgb(120, 45, 55),
+                    ),
+                    EventKind::SpawnReservation(_) => (
+                        Color32::from_rgb(24, 36, 52),
+                        Color32::from_rgb(130, 185, 245),
+                        Color32::from_rgb(55, 95, 150),
+                    ),
+                    EventKind::CorpseStatus(_) => (
+                        Color32::from_rgb(32, 38, 24),
+                        Color32::from_rgb(200, 220, 150),
+                        Color32::from_rgb(80, 110, 40),
+                    ),
+                    EventKind::ZoneTransfer(_) => (
+                        Color32::from_rgb(36, 30, 48),
+                        Color32::from_rgb(190, 160, 235),
+                        Color32::from_rgb(90, 70, 150),
+                    ),
+                    EventKind::StatusEffect(_) => (
+                        Color32::from_rgb(44, 28, 24),
+                        Color32::from_rgb(245, 180, 140),
+                        Color32::from_rgb(130, 70, 40),
+                    ),
+                    EventKind::Hit(_) => (
+                        Color32::from_rgb(25, 45, 30),
+                        Color32::from_rgb(160, 240, 160),
+                        Color32::from_rgb(60, 120, 70),
+                    ),
+                    EventKind::VehicleDestruction(_) => (
+                        Color32::from_rgb(48, 30, 30),
+                        Color32::from_rgb(245, 150, 150),
+                        Color32::from_rgb(120, 60, 60),
+                    ),
+                    EventKind::Custom(custom) => {
+                        let color = chart_view::custom_color(&self.patterns, &custom.name);
+                        let darker = |factor: f32| {
+                            let [r, g, b, _] = color.to_array();
+                            let scale = |channel: u8| (channel as f32 * factor) as u8;
+                            Color32::from_rgb(scale(r), scale(g), scale(b))
+                        };
+                        (darker(0.2), color, darker(0.5))
+                    }
+                };
+                egui::Frame::none()
+                    .fill(fill)
+                    .stroke(egui::Stroke::new(1.0, border))
+                    .rounding(egui::Rounding::same(6.0))
+                    .inner_margin(egui::Margin::symmetric(10.0, 6.0))
+                    .show(ui, |ui| {
+                        ui.label(highlighted(ui, &summary, &search, text_color));
+                        let detail_color = Color32::from_rgb(220, 220, 220);
+                        for detail in event.detail_lines() {
+                            ui.label(highlighted(ui, &detail, &search, detail_color));
+                        }
+                        let players = event.involved_players();
+                        if !players.is_empty() {
+                            ui.add_space(6.0);
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(
+                                    RichText::new("Players:")
+                                        .color(Color32::from_rgb(190, 190, 190)),
+                                );
+                                for (index, player) in players.iter().enumerate() {
+                                    let color =
+                                        roster_view::player_color(&self.filter.roster, player);
+                                    let response = ui.add(
+                                        egui::Label::new(
+                                            RichText::new(player.clone()).underline().color(color),
+                                        )
+                                        .sense(Sense::click()),
+                                    );
+                                    if response.clicked() {
+                                        clicked_player = Some(player.clone());
+                                    }
+                                    roster_edited |= roster_view::player_context_menu(
+                                        &response,
+                                        &mut self.filter.roster,
+                                        player,
+                                    );
+                                    if index + 1 < players.len() {
+                                        ui.label(
+                                            RichText::new("•")
+                                                .color(Color32::from_rgb(120, 120, 120)),
+                                        );
+                                    }
+                                }
+                            });
+                        }
+                    });
+                ui.add_space(8.0);
+            }
+        });
+        if let Some(player) = clicked_player {
+            self.open_player_info(&player);
+        }
+        if roster_edited {
+            self.roster_changed();
+        }
+    }
+
+    fn combat_stats(&mut self) -> &CombatStats {
+        let stale = self
+            .stats_cache
+            .as_ref()
+            .is_none_or(|(revision, range, _)| {
+                *revision != self.events_revision || *range != self.filter.time_range
+            });
+        if stale {
+            let scoped = self
+                .events
+                .iter()
+                .filter(|event| self.filter.in_time_range(event));
+            let stats = CombatStats::compute(scoped, self.primary_nickname.as_deref());
+            self.stats_cache = Some((self.events_revision, self.filter.time_range.clone(), stats));
+        }
+        &self
+            .stats_cache
+            .as_ref()
+            .expect("stats cache was just filled")
+            .2
+    }
+
+    fn death_recaps(&mut self) -> &[DeathRecap] {
+        let stale = self
+            .recap_cache
+            .as_ref()
+            .is_none_or(|(revision, range, window, _)| {
+                *revision != self.events_revision
+                    || *range != self.filter.time_range
+                    || *window != self.recap_window_secs
+            });
+        if stale {
+            let scoped = self
+                .events
+                .iter()
+                .filter(|event| self.filter.in_time_range(event));
+            let recaps = match self.primary_nickname.as_deref() {
+                Some(player) => DeathRecap::collect(
+                    scoped,
+                    player,
+                    chrono::Duration::seconds(i64::from(self.recap_window_secs)),
+                ),
+                None => Vec::new(),
+            };
+            self.recap_cache = Some((
+                self.events_revision,
+                self.filter.time_range.clone(),
+                self.recap_window_secs,
+                recaps,
+            ));
+        }
+        &self
+            .recap_cache
+            .as_ref()
+            .expect("recap cache was just filled")
+            .3
+    }
+
+    /// The index into `events` of the newest event the webhook would post, shown as the payload
+    /// preview.
+    fn webhook_preview(&mut self) -> Option<usize> {
+        let stale = self
+            .webhook_preview_cache
+            .as_ref()
+            .is_none_or(|(revision, config, _)| {
+                *revision != self.events_revision || *config != self.webhook
+            });
+        if stale {
+            let nickname = self.primary_nickname.as_deref();
+            let index = self
+                .events
+                .iter()
+                .position(|event| self.webhook.selects(event, nickname));
+            self.webhook_preview_cache = Some((self.events_revision, self.webhook.clone(), index));
+        }
+        self.webhook_preview_cache
+            .as_ref()
+            .expect("webhook preview cache was just filled")
+            .2
+    }
+
+    /// Our side is the player and the friendly roster. Roster edits drop the cache, see
+    /// `roster_changed`.
+    fn engagements(&mut self) -> &[Engagement] {
+        let stale = self
+            .engagement_cache
+            .as_ref()
+            .is_none_or(|(revision, range, gap, _)| {
+                *revision != self.events_revision
+                    || *range != self.filter.time_range
+                    || *gap != self.engagement_gap_secs
+            });
+        if stale {
+            let mut our_side = self.filter.roster.friendly.clone();
+            if let Some(player) = &self.primary_nickname {
+                our_side.insert(0, player.clone());
+            }
+            let scoped = self
+                .events
+                .iter()
+                .filter(|event| self.filter.in_time_range(event));
+            let engagements = Engagement::detect(
+                scoped,
+                chrono::Duration::seconds(i64::from(self.engagement_gap_secs)),
+                &our_side,
+            );
+            self.engagement_cache = Some((
+                self.events_revision,
+                self.filter.time_range.clone(),
+                self.engagement_gap_secs,
+                engagements,
+            ));
+        }
+        &self
+            .engagement_cache
+            .as_ref()
+            .expect("engagement cache was just filled")
+            .3
+    }
+
+    /// Called after the roster was edited: the ignored list and the scope filter the events,
+    /// the friendly list decides the sides of the engagements, and the edit is saved right
+    /// away.
+    fn roster_changed(&mut self) {
+        self.filter_generation += 1;
+        self.engagement_cache = None;
+        self.persist_settings(true);
+    }
+
+    fn sessions(&mut self) -> &[GameSession] {
+        let stale = self
+            .sessions_cache
+            .as_ref()
+            .is_none_or(|(revision, _)| *revision != self.events_revision);
+        if stale {
+            let sessions = segment_sessions(&self.session_markers, &self.events);
+            self.sessions_cache = Some((self.events_revision, sessions));
+        }
+        &self
+            .sessions_cache
+            .as_ref()
+            .expect("sessions cache was just filled")
+            .1
+    }
+
+    /// Keeps the filter's time range in sync with the selected session, whose bounds move
+    /// while the log grows, and the range brushed in the chart.
+    fn sync_session_scope(&mut self) {
+        let selected = self.selected_session;
+        let range = selected.and_then(|index| {
+            self.sessions()
+                .get(index)
+                .map(|session| session.time_range())
+        });
+        if range.is_none() {
+            self.selected_session = None;
+        }
+        if !self.show_chart {
+            self.chart_brush = None;
+        }
+        let time_range = match (&range, &self.chart_brush) {
+            (Some(session), Some(brush)) => {
+                Some(*session.start().max(brush.start())..=*session.end().min(brush.end()))
+            }
+            (session, brush) => session.clone().or_else(|| brush.clone()),
+        };
+        if time_range != self.filter.time_range || range != self.session_range {
+            self.filter.time_range = time_range;
+            self.session_range = range;
+            self.filter_generation += 1;
+        }
+    }
+
+    fn render_session_picker(&mut self, ui: &mut egui::Ui) {
+        let labels: Vec<String> = self
+            .sessions()
+            .iter()
+            .enumerate()
+            .map(|(index, session)| session.label(index))
+            .collect();
+        let selected_text = self
+            .selected_session
+            .and_then(|index| labels.get(index).cloned())
+            .unwrap_or_else(|| "All sessions".to_string());
+        ui.label(RichText::new("Session:").color(Color32::from_rgb(210, 210, 210)));
+        egui::ComboBox::from_id_source("session_picker")
+            .selected_text(selected_text)
+            .width(360.0)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.selected_session, None, "All sessions");
+                for (index, label) in labels.into_iter().enumerate() {
+                    ui.selectable_value(&mut self.selected_session, Some(index), label);
+                }
+            });
+    }
+
     fn render_player_info_window(&mut self, ctx: &egui::Context) {
         let Some(current_key) = self.player_info_window.clone() else {
             return;
@@ -401,7 +1373,24 @@ impl eframe::App for LogApp {
         ctx.request_repaint_after(wake_interval);
 
         self.poll_player_info_responses();
+        self.poll_load_messages();
         self.maybe_refresh();
+        if let Some(err) = self.history.as_ref().and_then(HistoryWriter::take_error) {
+            self.history_error = Some(err);
+        }
+        if let Some(err) = self.alert_notifier.take_error() {
+            self.alert_error = Some(err);
+        }
+        if let Some(status) = self.webhook_sender.take_status() {
+            self.webhook_status = Some(status);
+        }
+        self.sync_session_scope();
+        if let Some(rect) = ctx.input(|i| i.viewport().inner_rect) {
+            self.window_size = Some(WindowSize {
+                width: rect.width(),
+                height: rect.height(),
+            });
+        }
 
         egui::TopBottomPanel::top("controls").show(ctx, |ui| {
             egui::Frame::none()
@@ -415,6 +1404,23 @@ impl eframe::App for LogApp {
                                 RichText::new(format!("Version: {}", self.app_version))
                                     .color(Color32::from_rgb(160, 160, 160)),
                             );
+                            if let Some(build) = self
+                                .log_metadata
+                                .as_ref()
+                                .map(|metadata| &metadata.build)
+                                .filter(|build| build.is_known())
+                            {
+                                let profile = ParserProfile::select(build);
+                                let mut hover = format!("Parser profile: {}", profile.name);
+                                if let Some(branch) = &build.branch {
+                                    hover = format!("Branch: {}\n{}", branch, hover);
+                                }
+                                ui.label(
+                                    RichText::new(format!("Game: {}", build))
+                                        .color(Color32::from_rgb(160, 160, 160)),
+                                )
+                                .on_hover_text(hover);
+                            }
                         });
                         ui.add_space(4.0);
 
@@ -427,6 +1433,7 @@ impl eframe::App for LogApp {
                             if response.lost_focus()
                                 && ui.input(|input| input.key_pressed(egui::Key::Enter))
                             {
+                                self.source = LogSource::File;
                                 self.reload();
                             }
                             if ui
@@ -457,55 +1464,256 @@ impl eframe::App for LogApp {
                             {
                                 self.reload();
                             }
+                            if ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new("Log library…").color(Color32::WHITE),
+                                    )
+                                    .fill(Color32::from_rgb(70, 70, 70)),
+                                )
+                                .clicked()
+                            {
+                                self.library.open(self.dialog_start_dir(), &self.patterns);
+                            }
+                            if ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new("History").color(Color32::WHITE),
+                                    )
+                                    .fill(Color32::from_rgb(70, 70, 70)),
+                                )
+                                .on_hover_text("Show every event stored in the history database")
+                                .clicked()
+                            {
+                                self.show_history();
+                            }
+                            let mut history_enabled = self.history_enabled;
+                            if ui
+                                .checkbox(&mut history_enabled, "Save to history")
+                                .on_hover_text(
+                                    "Keep parsed events in a local database, so they survive the game overwriting Game.log",
+                                )
+                                .changed()
+                            {
+                                self.set_history_enabled(history_enabled);
+                            }
+                            let mut overlay_enabled = self.overlay_enabled;
+                            if ui
+                                .checkbox(&mut overlay_enabled, "Overlay server")
+                                .on_hover_text(
+                                    "Serve the events and a kill feed overlay page on localhost, for OBS browser sources",
+                                )
+                                .changed()
+                            {
+                                self.set_overlay_enabled(overlay_enabled);
+                            }
+                            match &self.overlay {
+                                Some(overlay) => {
+                                    let url = overlay.url();
+                                    ui.hyperlink_to(&url, &url);
+                                }
+                                None => {
+                                    ui.add(
+                                        egui::DragValue::new(&mut self.overlay_port)
+                                            .clamp_range(1024..=65535)
+                                            .prefix("port "),
+                                    );
+                                }
+                            }
+                            ui.menu_button(RichText::new("Export…").color(Color32::WHITE), |ui| {
+                                if ui
+                                    .button("Filtered events (JSON, NDJSON or CSV)…")
+                                    .clicked()
+                                {
+                                    ui.close_menu();
+                                    self.export_filtered();
+                                }
+                                if ui
+                                    .button("Filtered events, one CSV per category…")
+                                    .clicked()
+                                {
+                                    ui.close_menu();
+                                    self.export_filtered_csv_per_category();
+                                }
+                            });
                         });
 
+                        let source_note = match &self.source {
+                            LogSource::File => None,
+                            LogSource::Merged(paths) => Some(format!(
+                                "Showing {} merged logs. Open a single log to follow it again.",
+                                paths.len()
+                            )),
+                            LogSource::History => Some(format!(
+                                "Showing {} events from the history database. Open a log to follow it again.",
+                                self.events.len()
+                            )),
+                        };
+                        if let Some(note) = source_note {
+                            ui.label(RichText::new(note).color(Color32::from_rgb(160, 200, 240)));
+                        }
                         if let Some(error) = &self.load_error {
                             ui.colored_label(Color32::from_rgb(240, 90, 80), error);
                         }
+                        if let Some(error) = &self.history_error {
+                            ui.colored_label(Color32::from_rgb(240, 90, 80), error);
+                        }
+                        if let Some(error) = &self.alert_error {
+                            ui.colored_label(Color32::from_rgb(240, 90, 80), error);
+                        }
+                        if let Some(error) = &self.overlay_error {
+                            ui.colored_label(Color32::from_rgb(240, 90, 80), error);
+                        }
+                        if let Some(error) = &self.patterns_error {
+                            ui.colored_label(Color32::from_rgb(240, 90, 80), error);
+                        }
+                        let unmatched = self.parser_health.unmatched();
+                        if unmatched > 0
+                            && ui
+                                .add(
+                                    egui::Label::new(
+                                        RichText::new(format!(
+                                            "⚠ {} line(s) look like events but did not parse, the log format may have changed. Show parser health",
+                                            unmatched
+                                        ))
+                                        .color(Color32::from_rgb(240, 170, 80))
+                                        .underline(),
+                                    )
+                                    .sense(Sense::click()),
+                                )
+                                .clicked()
+                        {
+                            self.health_window.open = true;
+                        }
+                        match &self.webhook_status {
+                            Some(Ok(message)) => {
+                                ui.colored_label(Color32::from_rgb(140, 210, 140), message);
+                            }
+                            Some(Err(error)) => {
+                                ui.colored_label(Color32::from_rgb(240, 90, 80), error);
+                            }
+                            None => {}
+                        }
+                        match &self.export_status {
+                            Some(Ok(message)) => {
+                                ui.colored_label(Color32::from_rgb(140, 210, 140), message);
+                            }
+                            Some(Err(error)) => {
+                                ui.colored_label(Color32::from_rgb(240, 90, 80), error);
+                            }
+                            None => {}
+                        }
+
+                        let mut cancel_requested = false;
+                        if let Some(load) = &self.active_load {
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::ProgressBar::new(load.fraction())
+                                        .desired_width(320.0)
+                                        .text(format!(
+                                            "Parsing… {} / {}",
+                                            format_bytes(load.processed),
+                                            format_bytes(load.total)
+                                        )),
+                                );
+                                if ui
+                                    .add(
+                                        egui::Button::new(
+                                            RichText::new("Cancel").color(Color32::WHITE),
+                                        )
+                                        .fill(Color32::from_rgb(80, 80, 80)),
+                                    )
+                                    .clicked()
+                                {
+                                    cancel_requested = true;
+                                }
+                            });
+                        }
+                        if cancel_requested {
+                            self.cancel_load();
+                            self.load_cancelled = true;
+                            self.load_error = Some("Parsing cancelled.".to_string());
+                        }
 
-                        ui.label(
-                            RichText::new(
-                                "The view refreshes automatically when the selected file changes.",
-                            )
-                            .color(Color32::from_rgb(160, 160, 160)),
-                        );
-
-                        ui.add_space(6.0);
-
                         ui.horizontal_wrapped(|ui| {
-                            ui.checkbox(
-                                &mut self.filter_show_kills,
-                                RichText::new("Show kills").color(Color32::from_rgb(210, 210, 210)),
+                            ui.label(
+                                RichText::new(
+                                    "The view refreshes automatically when the selected file changes. Check every",
+                                )
+                                .color(Color32::from_rgb(160, 160, 160)),
                             );
-                            ui.checkbox(
-                                &mut self.filter_show_spawns,
+                            let mut secs = self.auto_refresh_interval.as_secs_f32();
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut secs)
+                                        .clamp_range(MIN_REFRESH_SECS..=MAX_REFRESH_SECS)
+                                        .speed(0.1)
+                                        .suffix(" s"),
+                                )
+                                .changed()
+                            {
+                                self.auto_refresh_interval = Duration::from_secs_f32(secs);
+                            }
+                        });
+
+                        ui.add_space(6.0);
+
+                        let mut filter_changed = false;
+                        ui.horizontal_wrapped(|ui| {
+                            filter_changed |= ui
+                                .checkbox(
+                                &mut self.filter.show_kills,
+                                RichText::new("Show kills").color(Color32::from_rgb(210, 210, 210)),
+                            )
+                            .changed();
+                            filter_changed |= ui
+                                .checkbox(
+                                &mut self.filter.show_spawns,
                                 RichText::new("Show spawns")
                                     .color(Color32::from_rgb(210, 210, 210)),
-                            );
-                            ui.checkbox(
-                                &mut self.filter_show_corpse,
+                            )
+                            .changed();
+                            filter_changed |= ui
+                                .checkbox(
+                                &mut self.filter.show_corpse,
                                 RichText::new("Show corpse toggles")
                                     .color(Color32::from_rgb(210, 210, 210)),
-                            );
-                            ui.checkbox(
-                                &mut self.filter_show_zone_moves,
+                            )
+                            .changed();
+                            filter_changed |= ui
+                                .checkbox(
+                                &mut self.filter.show_zone_moves,
                                 RichText::new("Show zone moves")
                                     .color(Color32::from_rgb(210, 210, 210)),
-                            );
-                            ui.checkbox(
-                                &mut self.filter_show_status_effects,
+                            )
+                            .changed();
+                            filter_changed |= ui
+                                .checkbox(
+                                &mut self.filter.show_status_effects,
                                 RichText::new("Show status effects")
                                     .color(Color32::from_rgb(210, 210, 210)),
-                            );
-                            ui.checkbox(
-                                &mut self.filter_show_hits,
+                            )
+                            .changed();
+                            filter_changed |= ui
+                                .checkbox(
+                                &mut self.filter.show_hits,
                                 RichText::new("Show hits").color(Color32::from_rgb(210, 210, 210)),
-                            );
-                            ui.checkbox(
-                                &mut self.filter_show_vehicle_destruction,
+                            )
+                            .changed();
+                            filter_changed |= ui
+                                .checkbox(
+                                &mut self.filter.show_vehicle_destruction,
                                 RichText::new("Show vehicle destruction")
                                     .color(Color32::from_rgb(210, 210, 210)),
-                            );
+                            )
+                            .changed();
+                            filter_changed |= ui
+                                .checkbox(
+                                &mut self.filter.show_custom,
+                                RichText::new("Show custom events")
+                                    .color(Color32::from_rgb(210, 210, 210)),
+                            )
+                            .changed();
                         });
 
                         ui.horizontal_wrapped(|ui| {
@@ -514,10 +1722,10 @@ impl eframe::App for LogApp {
                                     .color(Color32::from_rgb(210, 210, 210)),
                             );
                             let response =
-                                ui.add(egui::TextEdit::singleline(&mut self.ignored_player));
+                                ui.add(egui::TextEdit::singleline(&mut self.filter.ignored_player));
                             if response.changed() {
                                 self.ignored_player_user_override = true;
-                                self.persist_ignored_player();
+                                filter_changed = true;
                             }
                             if ui
                                 .add(
@@ -526,9 +1734,85 @@ impl eframe::App for LogApp {
                                 )
                                 .clicked()
                             {
-                                self.ignored_player.clear();
+                                self.filter.ignored_player.clear();
                                 self.ignored_player_user_override = true;
-                                self.persist_ignored_player();
+                                filter_changed = true;
+                            }
+                            if ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new("Roster…").color(Color32::WHITE),
+                                    )
+                                    .fill(Color32::from_rgb(70, 70, 70)),
+                                )
+                                .on_hover_text(
+                                    "Friendly, hostile and ignored players. Right-click a player name to sort it in.",
+                                )
+                                .clicked()
+                            {
+                                self.roster_window.open = true;
+                            }
+                            if ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new("Alerts…").color(Color32::WHITE),
+                                    )
+                                    .fill(Color32::from_rgb(70, 70, 70)),
+                                )
+                                .on_hover_text(
+                                    "Desktop notifications and sounds for new events while the log is followed",
+                                )
+                                .clicked()
+                            {
+                                self.alerts_window.open = true;
+                            }
+                            if ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new("Webhook…").color(Color32::WHITE),
+                                    )
+                                    .fill(Color32::from_rgb(70, 70, 70)),
+                                )
+                                .on_hover_text("Post new kills and deaths to a chat webhook")
+                                .clicked()
+                            {
+                                self.webhook_window.open = true;
+                            }
+                            if ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new("Custom events…").color(Color32::WHITE),
+                                    )
+                                    .fill(Color32::from_rgb(70, 70, 70)),
+                                )
+                                .on_hover_text("Define event types of your own with regular expressions")
+                                .clicked()
+                            {
+                                self.patterns_window.open = true;
+                            }
+                            if ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new("Parser health…").color(Color32::WHITE),
+                                    )
+                                    .fill(Color32::from_rgb(70, 70, 70)),
+                                )
+                                .on_hover_text("Lines per parser and lines that look like events but did not parse")
+                                .clicked()
+                            {
+                                self.health_window.open = true;
+                            }
+                            if ui
+                                .add(
+                                    egui::Button::new(
+                                        RichText::new("Log info…").color(Color32::WHITE),
+                                    )
+                                    .fill(Color32::from_rgb(70, 70, 70)),
+                                )
+                                .on_hover_text("Game build, system and shards from the log header")
+                                .clicked()
+                            {
+                                self.log_info_window.open = true;
                             }
                         });
 
@@ -536,849 +1820,187 @@ impl eframe::App for LogApp {
                             ui.label(
                                 RichText::new("Search:").color(Color32::from_rgb(210, 210, 210)),
                             );
-                            ui.add(egui::TextEdit::singleline(&mut self.search_text));
+                            filter_changed |= ui
+                                .add(
+                                    egui::TextEdit::singleline(&mut self.filter.search_text)
+                                        .hint_text("text, killer:Foo, zone:OOC_*, type:kill OR …"),
+                                )
+                                .on_hover_text(SEARCH_HELP)
+                                .changed();
+                            filter_changed |= ui
+                                .checkbox(&mut self.filter.search_regex, "Regex")
+                                .on_hover_text(
+                                    "Search the summary, details and raw line with a case-insensitive regular expression and highlight the matches",
+                                )
+                                .changed();
+                            if let Some(err) = self.search().error() {
+                                ui.colored_label(
+                                    Color32::from_rgb(240, 170, 80),
+                                    format!("{}, searching the plain text", err),
+                                );
+                            }
                         });
-                    });
-                });
-        });
-
-        egui::CentralPanel::default().show(ctx, |ui| {
-            let filtered = self.filtered_events();
-
-            let header_text = format!(
-                "Showing {} events ({} total parsed)",
-                filtered.len(),
-                self.events.len()
-            );
-            ui.label(RichText::new(header_text).color(Color32::from_rgb(200, 200, 200)));
 
-            if filtered.is_empty() {
-                ui.label(
-                    RichText::new("No events match the current filters.")
-                        .color(Color32::from_rgb(200, 200, 200)),
-                );
-                return;
-            }
-
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                for event in &filtered {
-                    let summary = event.summary_line();
-                    let (fill, text_color, border) = match &event.kind {
-                        EventKind::Kill(_) => (
-                            Color32::from_rgb(50, 25, 30),
-                            Color32::from_rgb(235, 130, 130),
-                            Color32::from_rgb(120, 45, 55),
-                        ),
-                        EventKind::SpawnReservation(_) => (
-                            Color32::from_rgb(24, 36, 52),
-                            Color32::from_rgb(130, 185, 245),
-                            Color32::from_rgb(55, 95, 150),
-                        ),
-                        EventKind::CorpseStatus(_) => (
-                            Color32::from_rgb(32, 38, 24),
-                            Color32::from_rgb(200, 220, 150),
-                            Color32::from_rgb(80, 110, 40),
-                        ),
-                        EventKind::ZoneTransfer(_) => (
-                            Color32::from_rgb(36, 30, 48),
-                            Color32::from_rgb(190, 160, 235),
-                            Color32::from_rgb(90, 70, 150),
-                        ),
-                        EventKind::StatusEffect(_) => (
-                            Color32::from_rgb(44, 28, 24),
-                            Color32::from_rgb(245, 180, 140),
-                            Color32::from_rgb(130, 70, 40),
-                        ),
-                        EventKind::Hit(_) => (
-                            Color32::from_rgb(25, 45, 30),
-                            Color32::from_rgb(160, 240, 160),
-                            Color32::from_rgb(60, 120, 70),
-                        ),
-                        EventKind::VehicleDestruction(_) => (
-                            Color32::from_rgb(48, 30, 30),
-                            Color32::from_rgb(245, 150, 150),
-                            Color32::from_rgb(120, 60, 60),
-                        ),
-                    };
-                    egui::Frame::none()
-                        .fill(fill)
-                        .stroke(egui::Stroke::new(1.0, border))
-                        .rounding(egui::Rounding::same(6.0))
-                        .inner_margin(egui::Margin::symmetric(10.0, 6.0))
-                        .show(ui, |ui| {
-                            ui.label(RichText::new(summary).color(text_color));
-                            let detail_color = Color32::from_rgb(220, 220, 220);
-                            for detail in event.detail_lines() {
-                                ui.label(RichText::new(detail).color(detail_color));
-                            }
-                            let players = event.involved_players();
-                            if !players.is_empty() {
-                                ui.add_space(6.0);
-                                ui.horizontal_wrapped(|ui| {
-                                    ui.label(
-                                        RichText::new("Players:")
-                                            .color(Color32::from_rgb(190, 190, 190)),
-                                    );
-                                    for (index, player) in players.iter().enumerate() {
-                                        let response = ui.add(
-                                            egui::Label::new(
-                                                RichText::new(player.clone())
-                                                    .underline()
-                                                    .color(Color32::from_rgb(140, 200, 255)),
+                        ui.horizontal_wrapped(|ui| {
+                            self.render_session_picker(ui);
+                            ui.label(
+                                RichText::new("Players:").color(Color32::from_rgb(210, 210, 210)),
+                            );
+                            egui::ComboBox::from_id_source("roster_scope")
+                                .selected_text(roster_view::scope_label(self.filter.roster_scope))
+                                .show_ui(ui, |ui| {
+                                    for scope in
+                                        [RosterScope::All, RosterScope::Friendly, RosterScope::Hostile]
+                                    {
+                                        filter_changed |= ui
+                                            .selectable_value(
+                                                &mut self.filter.roster_scope,
+                                                scope,
+                                                roster_view::scope_label(scope),
                                             )
-                                            .sense(Sense::click()),
-                                        );
-                                        if response.clicked() {
-                                            self.open_player_info(player);
-                                        }
-                                        if index + 1 < players.len() {
-                                            ui.label(
-                                                RichText::new("•")
-                                                    .color(Color32::from_rgb(120, 120, 120)),
-                                            );
-                                        }
+                                            .changed();
                                     }
                                 });
-                            }
                         });
-                    ui.add_space(8.0);
-                }
-            });
+                        if filter_changed {
+                            self.filter_generation += 1;
+                        }
+                    });
+                });
         });
-        self.render_player_info_window(ctx);
-    }
-}
-
-struct ParsedLog {
-    events: Vec<PlayerEvent>,
-    primary_nickname: Option<String>,
-}
-
-fn parse_log(path: &Path) -> Result<ParsedLog, String> {
-    let file =
-        File::open(path).map_err(|err| format!("Failed to open {}: {}", path.display(), err))?;
-    let reader = BufReader::new(file);
-    let mut events: Vec<PlayerEvent> = Vec::new();
-    let mut primary_nickname = None;
-    let mut reader = reader;
-    let mut buffer = Vec::new();
-
-    loop {
-        buffer.clear();
-        match reader.read_until(b'\n', &mut buffer) {
-            Ok(0) => break,
-            Ok(_) => {
-                if buffer.ends_with(&[b'\n']) {
-                    buffer.pop();
-                    if buffer.ends_with(&[b'\r']) {
-                        buffer.pop();
-                    }
-                }
-                let line = String::from_utf8_lossy(&buffer).to_string();
-                if primary_nickname.is_none() {
-                    if let Some(name) = extract_nickname(&line) {
-                        primary_nickname = Some(name);
-                    }
-                }
-                if let Some(event) = parse_line(&line) {
-                    let is_duplicate = events
-                        .last()
-                        .map(|previous| previous.raw == event.raw)
-                        .unwrap_or(false);
-                    if !is_duplicate {
-                        events.push(event);
-                    }
-                }
-            }
-            Err(err) => {
-                return Err(format!(
-                    "Failed to read line from {}: {}",
-                    path.display(),
-                    err
-                ));
-            }
-        }
-    }
-
-    events.sort_by_key(|event| event.timestamp);
-    events.reverse();
-
-    Ok(ParsedLog {
-        events,
-        primary_nickname,
-    })
-}
-
-fn extract_nickname(line: &str) -> Option<String> {
-    let marker = "nickname=\"";
-    let start = line.find(marker)? + marker.len();
-    let rest = &line[start..];
-    let end = rest.find('"')?;
-    Some(rest[..end].to_string())
-}
-
-fn parse_line(line: &str) -> Option<PlayerEvent> {
-    parse_actor_death(line)
-        .or_else(|| parse_spawn_reservation(line))
-        .or_else(|| parse_corpse_status(line))
-        .or_else(|| parse_zone_transfer(line))
-        .or_else(|| parse_status_effect(line))
-        .or_else(|| parse_hit_event(line))
-        .or_else(|| parse_vehicle_destruction(line))
-}
-
-fn parse_actor_death(line: &str) -> Option<PlayerEvent> {
-    static RE: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?<Actor Death> CActor::Kill: ["'](?P<victim>[^"']+)["']\s\[(?P<victim_id>[^\]]+)\](?: in zone ["'](?P<zone>[^"']+)["'])? killed by ["'](?P<killer>[^"']+)["']\s\[(?P<killer_id>[^\]]+)\](?: using ["'](?P<weapon>[^"']*)["'](?: \[(?P<weapon_class>[^\]]+)\])?)?\s+with damage type ["'](?P<damage>[^"']+)["'].*"#)
-            .unwrap()
-    });
-
-    let caps = RE.captures(line)?;
-    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
-    let victim_name = caps.name("victim")?.as_str().to_string();
-    let victim_id = caps.name("victim_id")?.as_str().to_string();
-    let zone = caps
-        .name("zone")
-        .map(|m| m.as_str().to_string())
-        .unwrap_or_default();
-    let killer_name = caps.name("killer")?.as_str().to_string();
-    let killer_id = caps.name("killer_id")?.as_str().to_string();
-    let weapon = caps
-        .name("weapon")
-        .map(|m| m.as_str().to_string())
-        .unwrap_or_default();
-    let weapon_class = caps
-        .name("weapon_class")
-        .map(|m| m.as_str().to_string())
-        .unwrap_or_default();
-    let damage_type = caps
-        .name("damage")
-        .map(|m| m.as_str().to_string())
-        .unwrap_or_default();
-
-    Some(PlayerEvent {
-        timestamp,
-        kind: EventKind::Kill(KillEvent {
-            victim_name,
-            victim_id,
-            killer_name,
-            killer_id,
-            weapon,
-            weapon_class,
-            damage_type,
-            zone,
-        }),
-        raw: line.to_string(),
-    })
-}
-
-fn parse_spawn_reservation(line: &str) -> Option<PlayerEvent> {
-    static RE: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?<Spawn Flow>.*?Player ["'](?P<player>[^"']+)["']\s\[(?P<player_id>[^\]]+)\] lost reservation for spawnpoint (?P<spawnpoint>[^\[]+)\s\[(?P<spawn_id>[^\]]+)\] at location (?P<location>[-\d]+)"#)
-            .unwrap()
-    });
-
-    let caps = RE.captures(line)?;
-    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
-    let player_name = caps.name("player")?.as_str().trim().to_string();
-    let player_id = caps.name("player_id")?.as_str().to_string();
-    let spawn_point = caps
-        .name("spawnpoint")
-        .map(|m| m.as_str().trim().to_string())
-        .unwrap_or_default();
-    let spawn_id = caps
-        .name("spawn_id")
-        .map(|m| m.as_str().to_string())
-        .unwrap_or_default();
-    let location = caps
-        .name("location")
-        .map(|m| m.as_str().to_string())
-        .unwrap_or_default();
-
-    Some(PlayerEvent {
-        timestamp,
-        kind: EventKind::SpawnReservation(SpawnReservationEvent {
-            player_name,
-            player_id,
-            spawn_point,
-            spawn_id,
-            location,
-        }),
-        raw: line.to_string(),
-    })
-}
-
-fn parse_corpse_status(line: &str) -> Option<PlayerEvent> {
-    static RE: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?\bPlayer ["'](?P<player>[^"'<>]+)["']\s*(?P<context><[^>]+>)?:\s*IsCorpseEnabled:\s*(?P<enabled>Yes|No)\.?"#)
-            .unwrap()
-    });
-
-    let caps = RE.captures(line)?;
-    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
-    let player_name = caps.name("player")?.as_str().trim().to_string();
-    let enabled_raw = caps.name("enabled")?.as_str();
-    let corpse_enabled = matches_ignore_case(enabled_raw, "Yes");
-    if corpse_enabled {
-        return None;
-    }
-    let context = caps.name("context").and_then(|m| {
-        let trimmed = m
-            .as_str()
-            .trim()
-            .trim_start_matches('<')
-            .trim_end_matches('>')
-            .trim();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed.to_string())
-        }
-    });
-
-    Some(PlayerEvent {
-        timestamp,
-        kind: EventKind::CorpseStatus(CorpseStatusEvent {
-            player_name,
-            context,
-            corpse_enabled,
-        }),
-        raw: line.to_string(),
-    })
-}
-
-fn parse_zone_transfer(line: &str) -> Option<PlayerEvent> {
-    static RE: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?moving zone hosted child id = (?P<child_id>\d+)\s+name\s*=\s*"(?P<player>[^"]+)"\s+to unblock removal of parent id = (?P<parent_id>\d+)\s+name\s*=\s*"(?P<parent_name>[^"]+)"\s+into zone host id = (?P<host_id>\d+)\s+name\s*=\s*"(?P<host_name>[^"]+)""#)
-            .unwrap()
-    });
-
-    let caps = RE.captures(line)?;
-    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
-    let player_name = caps.name("player")?.as_str().to_string();
-
-    if player_name.is_empty() {
-        return None;
-    }
-
-    Some(PlayerEvent {
-        timestamp,
-        kind: EventKind::ZoneTransfer(ZoneTransferEvent {
-            player_name,
-            child_id: caps.name("child_id").map(|m| m.as_str().to_string()),
-            parent_id: caps.name("parent_id").map(|m| m.as_str().to_string()),
-            parent_name: caps.name("parent_name").map(|m| m.as_str().to_string()),
-            host_id: caps.name("host_id").map(|m| m.as_str().to_string()),
-            host_name: caps.name("host_name").map(|m| m.as_str().to_string()),
-        }),
-        raw: line.to_string(),
-    })
-}
-
-fn parse_status_effect(line: &str) -> Option<PlayerEvent> {
-    static RE: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?Logged (?P<article>a|an) (?P<stage>start|end) of a status effect!\s*nickname:\s*(?P<nickname>[^,]+),\s*status effect:\s*(?P<effect>.+)"#)
-            .unwrap()
-    });
-
-    let caps = RE.captures(line)?;
-    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
-    let nickname = caps.name("nickname")?.as_str().trim().to_string();
-
-    Some(PlayerEvent {
-        timestamp,
-        kind: EventKind::StatusEffect(StatusEffectEvent {
-            player_name: nickname,
-            effect: caps
-                .name("effect")
-                .map(|m| m.as_str().trim().to_string())
-                .unwrap_or_default(),
-            stage: caps
-                .name("stage")
-                .map(|m| m.as_str().trim().to_ascii_lowercase())
-                .unwrap_or_else(|| "start".to_string()),
-        }),
-        raw: line.to_string(),
-    })
-}
-
-fn parse_hit_event(line: &str) -> Option<PlayerEvent> {
-    static RE: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?<Debug Hostility Events>.*?Fake hit FROM (?P<attacker>[^\s]+) TO (?P<target>[^\.]+)\.\s*Being sent to child (?P<child>[\w_-]+)"#)
-            .unwrap()
-    });
 
-    let caps = RE.captures(line)?;
-    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
-    let attacker = caps.name("attacker")?.as_str().to_string();
-    let target = caps.name("target")?.as_str().trim().to_string();
-    let child = caps
-        .name("child")
-        .map(|m| m.as_str().trim().to_string())
-        .filter(|s| !s.is_empty());
-
-    Some(PlayerEvent {
-        timestamp,
-        kind: EventKind::Hit(HitEvent {
-            attacker,
-            target,
-            child,
-        }),
-        raw: line.to_string(),
-    })
-}
-
-fn parse_vehicle_destruction(line: &str) -> Option<PlayerEvent> {
-    static RE: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?<Vehicle Destruction>.*?Vehicle '(?P<vehicle>[^']+)'\s*\[(?P<vehicle_id>[^\]]+)\].*?zone '(?P<zone>[^']+)'.*?driven by '(?P<driver>[^']+)'\s*\[(?P<driver_id>[^\]]+)\].*?advanced from destroy level (?P<from>\d+) to (?P<to>\d+) caused by '(?P<attacker>[^']+)'\s*\[(?P<attacker_id>[^\]]+)\]\s*with '(?P<cause>[^']+)'"#)
-            .unwrap()
-    });
-
-    let caps = RE.captures(line)?;
-    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
-    Some(PlayerEvent {
-        timestamp,
-        kind: EventKind::VehicleDestruction(VehicleDestructionEvent {
-            vehicle_name: caps
-                .name("vehicle")
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default(),
-            vehicle_id: caps
-                .name("vehicle_id")
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default(),
-            zone: caps
-                .name("zone")
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default(),
-            driver_name: caps
-                .name("driver")
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default(),
-            driver_id: caps
-                .name("driver_id")
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default(),
-            from_level: caps
-                .name("from")
-                .and_then(|m| m.as_str().parse::<u32>().ok())
-                .unwrap_or_default(),
-            to_level: caps
-                .name("to")
-                .and_then(|m| m.as_str().parse::<u32>().ok())
-                .unwrap_or_default(),
-            attacker_name: caps
-                .name("attacker")
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default(),
-            attacker_id: caps
-                .name("attacker_id")
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default(),
-            cause: caps
-                .name("cause")
-                .map(|m| m.as_str().to_string())
-                .unwrap_or_default(),
-        }),
-        raw: line.to_string(),
-    })
-}
-
-fn matches_ignore_case(value: &str, expected: &str) -> bool {
-    value.eq_ignore_ascii_case(expected)
-}
-
-fn format_status_stage(stage: &str, effect: &str) -> String {
-    if matches_ignore_case(stage, "start") {
-        format!("started {}", effect)
-    } else if matches_ignore_case(stage, "end") {
-        format!("ended {}", effect)
-    } else {
-        format!("{} {}", stage, effect)
-    }
-}
-
-fn describe_destroy_levels(from: u32, to: u32) -> &'static str {
-    match (from, to) {
-        (0, 1) => "soft kill",
-        (1, 2) => "hard kill",
-        (start, end) if end > start => "destroyed",
-        _ => "changed",
-    }
-}
-
-fn parse_timestamp(raw: &str) -> Option<DateTime<Utc>> {
-    chrono::DateTime::parse_from_rfc3339(raw)
-        .map(|dt| dt.with_timezone(&Utc))
-        .ok()
-}
-
-#[derive(Clone)]
-struct PlayerEvent {
-    timestamp: DateTime<Utc>,
-    kind: EventKind,
-    raw: String,
-}
-
-impl PlayerEvent {
-    fn summary_line(&self) -> String {
-        let ts = self.timestamp.format("%Y-%m-%d %H:%M:%S");
-        match &self.kind {
-            EventKind::Kill(event) => {
-                let weapon_display = if event.weapon.is_empty() {
-                    "unknown weapon".to_string()
-                } else if event.weapon_class.is_empty() {
-                    event.weapon.clone()
-                } else {
-                    format!("{} ({})", event.weapon, event.weapon_class)
-                };
-                format!(
-                    "{} | Kill | {} → {} with {}",
-                    ts, event.killer_name, event.victim_name, weapon_display
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.central_view, CentralView::Timeline, "Timeline");
+                ui.selectable_value(
+                    &mut self.central_view,
+                    CentralView::Statistics,
+                    "Statistics",
+                );
+                ui.selectable_value(
+                    &mut self.central_view,
+                    CentralView::DeathRecap,
+                    "Death recap",
                 )
-            }
-            EventKind::SpawnReservation(event) => format!(
-                "{} | Spawn | {} lost {}",
-                ts, event.player_name, event.spawn_point
-            ),
-            EventKind::CorpseStatus(event) => format!(
-                "{} | Corpse | {} corpse {}",
-                ts,
-                event.player_name,
-                if event.corpse_enabled {
-                    "enabled"
-                } else {
-                    "disabled"
-                }
-            ),
-            EventKind::ZoneTransfer(event) => format!(
-                "{} | Zone | {} → {}",
-                ts,
-                event.player_name,
-                event
-                    .host_name
-                    .as_deref()
-                    .filter(|name| !name.is_empty())
-                    .unwrap_or("unknown destination")
-            ),
-            EventKind::StatusEffect(event) => format!(
-                "{} | Status | {} {}",
-                ts,
-                event.player_name,
-                format_status_stage(&event.stage, &event.effect)
-            ),
-            EventKind::Hit(event) => {
-                format!("{} | Hit | {} → {}", ts, event.attacker, event.target)
-            }
-            EventKind::VehicleDestruction(event) => format!(
-                "{} | Vehicle | {} {} ({})",
-                ts,
-                event.attacker_name,
-                describe_destroy_levels(event.from_level, event.to_level),
-                event.vehicle_name
-            ),
-        }
-    }
-
-    fn detail_lines(&self) -> Vec<String> {
-        match &self.kind {
-            EventKind::Kill(event) => {
-                let mut lines = Vec::new();
-                lines.push(format!(
-                    "Victim: {} [{}] in {}",
-                    event.victim_name, event.victim_id, event.zone
-                ));
-                lines.push(format!(
-                    "Killer: {} [{}]",
-                    event.killer_name, event.killer_id
-                ));
-                if !event.damage_type.is_empty() {
-                    lines.push(format!("Damage type: {}", event.damage_type));
-                }
-                lines
-            }
-            EventKind::SpawnReservation(event) => vec![
-                format!("Player: {} [{}]", event.player_name, event.player_id),
-                format!("Spawn point: {}", event.spawn_point),
-                format!("Spawn ID: {}", event.spawn_id),
-                format!("Location: {}", event.location),
-            ],
-            EventKind::CorpseStatus(event) => {
-                let mut lines = vec![format!(
-                    "Corpse state: {}",
-                    if event.corpse_enabled {
-                        "Enabled"
-                    } else {
-                        "Disabled"
-                    }
-                )];
-                if let Some(context) = event.context.as_ref() {
-                    if !context.is_empty() {
-                        lines.push(format!("Context: {}", context));
-                    }
-                }
-                lines
-            }
-            EventKind::ZoneTransfer(event) => {
-                let mut lines = vec![format!("Player: {}", event.player_name)];
-                if let Some(child_id) = event.child_id.as_ref() {
-                    lines.push(format!("Child ID: {}", child_id));
-                }
-                if let Some(parent_name) = event.parent_name.as_ref() {
-                    if !parent_name.is_empty() {
-                        lines.push(format!("Parent: {}", parent_name));
-                    }
+                .on_hover_text("What led up to each of your deaths");
+                ui.selectable_value(
+                    &mut self.central_view,
+                    CentralView::Engagements,
+                    "Engagements",
+                )
+                .on_hover_text("Fights grouped by the players involved and time");
+                if self.central_view == CentralView::Timeline {
+                    ui.separator();
+                    ui.checkbox(&mut self.show_chart, "Chart")
+                        .on_hover_text("Plot the events over time and select a time range to list");
                 }
-                if let Some(parent_id) = event.parent_id.as_ref() {
-                    lines.push(format!("Parent ID: {}", parent_id));
+            });
+            ui.separator();
+            match self.central_view {
+                CentralView::Timeline => self.render_timeline(ui),
+                CentralView::Statistics => stats_view::show(ui, self.combat_stats()),
+                CentralView::DeathRecap => {
+                    let player = self.primary_nickname.clone();
+                    let mut window_secs = self.recap_window_secs;
+                    recap_view::show(ui, self.death_recaps(), player.as_deref(), &mut window_secs);
+                    self.recap_window_secs = window_secs;
                 }
-                if let Some(host_name) = event.host_name.as_ref() {
-                    if !host_name.is_empty() {
-                        lines.push(format!("Zone host: {}", host_name));
+                CentralView::Engagements => {
+                    let mut gap_secs = self.engagement_gap_secs;
+                    let export = engagements_view::show(ui, self.engagements(), &mut gap_secs);
+                    self.engagement_gap_secs = gap_secs;
+                    if export {
+                        self.export_engagements();
                     }
                 }
-                if let Some(host_id) = event.host_id.as_ref() {
-                    lines.push(format!("Zone host ID: {}", host_id));
-                }
-                lines
             }
-            EventKind::StatusEffect(event) => vec![
-                format!("Player: {}", event.player_name),
-                format!("Status effect: {}", event.effect),
-                format!(
-                    "Stage: {}",
-                    if matches_ignore_case(&event.stage, "start") {
-                        "Start".to_string()
-                    } else if matches_ignore_case(&event.stage, "end") {
-                        "End".to_string()
-                    } else {
-                        event.stage.clone()
-                    }
-                ),
-            ],
-            EventKind::Hit(event) => {
-                let mut lines = vec![format!("Attacker: {}", event.attacker)];
-                lines.push(format!("Target: {}", event.target));
-                if let Some(child) = event.child.as_ref() {
-                    lines.push(format!("Child channel: {}", child));
-                }
-                lines
-            }
-            EventKind::VehicleDestruction(event) => {
-                let mut lines = Vec::new();
-                lines.push(format!(
-                    "Vehicle: {} [{}]",
-                    event.vehicle_name, event.vehicle_id
-                ));
-                lines.push(format!(
-                    "Destroy level: {} → {} ({})",
-                    event.from_level,
-                    event.to_level,
-                    describe_destroy_levels(event.from_level, event.to_level)
-                ));
-                lines.push(format!(
-                    "Attacker: {} [{}] via {}",
-                    event.attacker_name, event.attacker_id, event.cause
-                ));
-                if !event.zone.is_empty() {
-                    lines.push(format!("Zone: {}", event.zone));
-                }
-                if !event.driver_name.is_empty() {
-                    lines.push(format!(
-                        "Driver: {} [{}]",
-                        event.driver_name, event.driver_id
-                    ));
-                }
-                lines
-            }
-        }
-    }
-
-    fn matches_search(&self, needle: &str) -> bool {
-        let needle = needle.trim();
-        if needle.is_empty() {
-            return true;
-        }
-        self.search_blob().contains(needle)
-    }
-
-    fn should_ignore(&self, ignored: &str) -> bool {
-        let trimmed = ignored.trim();
-        if trimmed.is_empty() {
-            return false;
+        });
+        self.render_player_info_window(ctx);
+        if self.roster_window.show(ctx, &mut self.filter.roster) {
+            self.roster_changed();
         }
-        match &self.kind {
-            EventKind::Kill(event) => {
-                event.killer_name.eq_ignore_ascii_case(trimmed)
-                    && !event.victim_name.eq_ignore_ascii_case(trimmed)
-            }
-            EventKind::SpawnReservation(event) => event.player_name.eq_ignore_ascii_case(trimmed),
-            EventKind::CorpseStatus(event) => event.player_name.eq_ignore_ascii_case(trimmed),
-            EventKind::ZoneTransfer(event) => event.player_name.eq_ignore_ascii_case(trimmed),
-            EventKind::StatusEffect(event) => event.player_name.eq_ignore_ascii_case(trimmed),
-            EventKind::Hit(event) => event.attacker.eq_ignore_ascii_case(trimmed),
-            EventKind::VehicleDestruction(event) => {
-                event.attacker_name.eq_ignore_ascii_case(trimmed)
-                    || (!event.driver_name.is_empty()
-                        && event.driver_name.eq_ignore_ascii_case(trimmed))
+        if self.webhook_window.open {
+            let preview_event = self
+                .webhook_preview()
+                .and_then(|index| self.events.get(index));
+            if let Some(payload) = self
+                .webhook_window
+                .show(ctx, &mut self.webhook, preview_event)
+            {
+                self.webhook_status = self
+                    .webhook_sender
+                    .send(&self.webhook, payload)
+                    .err()
+                    .map(Err);
             }
         }
-    }
-
-    fn search_blob(&self) -> String {
-        let mut blob = self.summary_line().to_lowercase();
-        for line in self.detail_lines() {
-            blob.push('\n');
-            blob.push_str(&line.to_lowercase());
+        if let Some(alert) = self.alerts_window.show(ctx, &mut self.alerts) {
+            self.alert_error = None;
+            self.alert_notifier.send(alert);
         }
-        blob.push('\n');
-        blob.push_str(&self.raw.to_lowercase());
-        blob
-    }
-
-    fn participants(&self) -> Vec<String> {
-        match &self.kind {
-            EventKind::Kill(event) => vec![
-                event.killer_name.to_lowercase(),
-                event.victim_name.to_lowercase(),
-            ],
-            EventKind::SpawnReservation(event) => vec![event.player_name.to_lowercase()],
-            EventKind::CorpseStatus(event) => vec![event.player_name.to_lowercase()],
-            EventKind::ZoneTransfer(event) => vec![event.player_name.to_lowercase()],
-            EventKind::StatusEffect(event) => vec![event.player_name.to_lowercase()],
-            EventKind::Hit(event) => vec![event.attacker.to_lowercase()],
-            EventKind::VehicleDestruction(event) => {
-                let mut names = vec![event.attacker_name.to_lowercase()];
-                if !event.driver_name.is_empty() {
-                    names.push(event.driver_name.to_lowercase());
-                }
-                names
+        self.health_window.show(ctx, &self.parser_health);
+        self.log_info_window.show(ctx, self.log_metadata.as_ref());
+        match self.patterns_window.show(
+            ctx,
+            self.patterns_path.as_deref(),
+            &self.patterns,
+            self.patterns_error.as_deref(),
+        ) {
+            Some(PatternsAction::Reload) => {
+                self.load_patterns();
+                self.reload();
             }
+            Some(PatternsAction::CreateExample) => self.create_example_patterns(),
+            None => {}
         }
-    }
-
-    fn involved_players(&self) -> Vec<String> {
-        let mut seen: HashSet<String> = HashSet::new();
-        let mut names = Vec::new();
-        let mut push_name = |name: &str| {
-            let trimmed = name.trim();
-            if trimmed.is_empty() {
-                return;
-            }
-            if trimmed.eq_ignore_ascii_case("unknown") {
-                return;
-            }
-            let key = trimmed.to_ascii_lowercase();
-            if seen.insert(key) {
-                names.push(trimmed.to_string());
-            }
-        };
-
-        match &self.kind {
-            EventKind::Kill(event) => {
-                push_name(&event.killer_name);
-                push_name(&event.victim_name);
-            }
-            EventKind::SpawnReservation(event) => {
-                push_name(&event.player_name);
-            }
-            EventKind::CorpseStatus(event) => {
-                push_name(&event.player_name);
-            }
-            EventKind::ZoneTransfer(event) => {
-                push_name(&event.player_name);
-            }
-            EventKind::StatusEffect(event) => {
-                push_name(&event.player_name);
-            }
-            EventKind::Hit(event) => {
-                push_name(&event.attacker);
-            }
-            EventKind::VehicleDestruction(event) => {
-                push_name(&event.attacker_name);
-                push_name(&event.driver_name);
+        match self.library.show(ctx, &self.patterns) {
+            Some(LibraryAction::Open(path)) => self.set_selected_file(&path),
+            Some(LibraryAction::Merge(paths)) => {
+                self.source = LogSource::Merged(paths);
+                self.reload();
             }
+            None => {}
         }
-
-        names
+        self.persist_settings(false);
     }
-}
-
-#[derive(Clone)]
-enum EventKind {
-    Kill(KillEvent),
-    SpawnReservation(SpawnReservationEvent),
-    CorpseStatus(CorpseStatusEvent),
-    ZoneTransfer(ZoneTransferEvent),
-    StatusEffect(StatusEffectEvent),
-    Hit(HitEvent),
-    VehicleDestruction(VehicleDestructionEvent),
-}
-
-#[derive(Clone)]
-struct KillEvent {
-    victim_name: String,
-    victim_id: String,
-    killer_name: String,
-    killer_id: String,
-    weapon: String,
-    weapon_class: String,
-    damage_type: String,
-    zone: String,
-}
-
-#[derive(Clone)]
-struct SpawnReservationEvent {
-    player_name: String,
-    player_id: String,
-    spawn_point: String,
-    spawn_id: String,
-    location: String,
-}
-
-#[derive(Clone)]
-struct CorpseStatusEvent {
-    player_name: String,
-    context: Option<String>,
-    corpse_enabled: bool,
-}
 
-#[derive(Clone)]
-struct ZoneTransferEvent {
-    player_name: String,
-    child_id: Option<String>,
-    parent_id: Option<String>,
-    parent_name: Option<String>,
-    host_id: Option<String>,
-    host_name: Option<String>,
-}
-
-#[derive(Clone)]
-struct StatusEffectEvent {
-    player_name: String,
-    effect: String,
-    stage: String,
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.persist_settings(true);
+    }
 }
 
-#[derive(Clone)]
-struct HitEvent {
-    attacker: String,
-    target: String,
-    child: Option<String>,
+const SEARCH_HELP: &str = "Words and \"quoted phrases\" match anywhere in an event.\n\
+field:value matches a field exactly, * and ? are wildcards: zone:OOC_*\n\
+field:~value matches a field containing the value: weapon:~\"klwe\"\n\
+Fields: killer, victim, player, weapon, damage, zone, vehicle, effect, type\n\
+type: takes a category (kill, spawn, …, custom) or the name of a custom pattern\n\
+after:12:30 / before:2024-09-14T18:00 limit the time (UTC)\n\
+Combine with AND (default), OR, NOT or -term and parentheses.";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CentralView {
+    Timeline,
+    Statistics,
+    DeathRecap,
+    Engagements,
 }
 
-#[derive(Clone)]
-struct VehicleDestructionEvent {
-    vehicle_name: String,
-    vehicle_id: String,
-    zone: String,
-    driver_name: String,
-    driver_id: String,
-    from_level: u32,
-    to_level: u32,
-    attacker_name: String,
-    attacker_id: String,
-    cause: String,
+type TimeRange = RangeInclusive<DateTime<Utc>>;
+
+/// Events revision, time range, gap and our side the engagements were detected for.
+type EngagementCache = (u64, Option<TimeRange>, u32, Vec<Engagement>);
+/// Events revision, the webhook settings, and the index into `events` of the webhook preview.
+type WebhookPreviewCache = (u64, WebhookConfig, Option<usize>);
+/// Events revision, filter generation, and the indices into `events` shown in the timeline
+/// and in the chart, which ignores the brushed range.
+type FilteredCache = (u64, u64, Vec<usize>, Vec<usize>);
+
+/// Where the timeline comes from. Only a single file is followed for new lines.
+#[derive(Clone, PartialEq, Eq)]
+enum LogSource {
+    File,
+    Merged(Vec<PathBuf>),
+    History,
 }
 
 struct PlayerInfoEntry {
@@ -1404,6 +2026,53 @@ enum PlayerInfoResult {
     Error(String),
 }
 
+/// A label with the spans matched by a regex search highlighted.
+fn highlighted(
+    ui: &egui::Ui,
+    text: &str,
+    search: &CompiledSearch,
+    color: Color32,
+) -> egui::WidgetText {
+    let ranges = search.highlights(text);
+    if ranges.is_empty() {
+        return RichText::new(text).color(color).into();
+    }
+    let plain = egui::TextFormat {
+        font_id: egui::TextStyle::Body.resolve(ui.style()),
+        color,
+        ..Default::default()
+    };
+    let matched = egui::TextFormat {
+        color: Color32::BLACK,
+        background: Color32::from_rgb(240, 200, 90),
+        ..plain.clone()
+    };
+    let mut job = egui::text::LayoutJob::default();
+    let mut end = 0;
+    for range in ranges {
+        job.append(&text[end..range.start], 0.0, plain.clone());
+        job.append(&text[range.clone()], 0.0, matched.clone());
+        end = range.end;
+    }
+    job.append(&text[end..], 0.0, plain);
+    job.into()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
 fn canonical_player_key(name: &str) -> String {
     name.trim().to_ascii_lowercase()
 }
@@ -1426,18 +2095,18 @@ fn resolve_input_path(raw: &str) -> PathBuf {
         return PathBuf::new();
     }
 
-    if trimmed == "~" {
-        if let Some(home) = env::var_os("HOME") {
-            return PathBuf::from(home);
-        }
+    if trimmed == "~"
+        && let Some(home) = env::var_os("HOME")
+    {
+        return PathBuf::from(home);
     }
 
-    if trimmed.starts_with("~/") || trimmed.starts_with("~\\") {
-        if let Some(mut home) = env::var_os("HOME").map(PathBuf::from) {
-            let remainder = trimmed[2..].replace('\\', "/");
-            push_path_components(&mut home, &remainder);
-            return home;
-        }
+    if (trimmed.starts_with("~/") || trimmed.starts_with("~\\"))
+        && let Some(mut home) = env::var_os("HOME").map(PathBuf::from)
+    {
+        let remainder = trimmed[2..].replace('\\', "/");
+        push_path_components(&mut home, &remainder);
+        return home;
     }
 
     if trimmed.len() >= 3
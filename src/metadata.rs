@@ -0,0 +1,181 @@
+use crate::game_build::GameBuild;
+use crate::session::{SessionMarker, SessionMarkerKind};
+use chrono::{DateTime, SecondsFormat, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+/// What the header of a `Game.log` tells about the game and the machine it ran on, plus the
+/// shards joined later. Meant to go along with bug reports and exports.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LogMetadata {
+    /// When the log was started.
+    pub started: Option<DateTime<Utc>>,
+    pub build: GameBuild,
+    pub built_on: Option<String>,
+    pub command_line: Option<String>,
+    /// The launcher environment, e.g. `PUB`, from `Environment:` or the command line.
+    pub environment: Option<String>,
+    pub os: Option<String>,
+    pub cpu: Option<String>,
+    pub cpu_cores: Option<u32>,
+    pub memory: Option<String>,
+    pub gpu: Option<String>,
+    /// Shards joined, in order, without repeats.
+    pub shards: Vec<String>,
+}
+
+impl LogMetadata {
+    /// Takes what a header line tells. Returns whether it was one.
+    pub fn read_header_line(&mut self, line: &str) -> bool {
+        static RE: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(
+                r"^(?:<[^>]+>\s*)?(?P<key>[A-Za-z][A-Za-z0-9 ()/.-]*?)\s*:\s*(?P<value>\S.*?)\s*$",
+            )
+            .unwrap()
+        });
+        static BUILT_ON: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"^(?:<[^>]+>\s*)?Built on (?P<value>\S.*?)\s*$").unwrap());
+
+        if self.build.read_header_line(line) {
+            return true;
+        }
+        if let Some(caps) = BUILT_ON.captures(line) {
+            self.built_on = Some(caps["value"].to_string());
+            return true;
+        }
+        let Some(caps) = RE.captures(line) else {
+            return false;
+        };
+        let value = caps["value"].to_string();
+        match caps["key"].to_ascii_lowercase().as_str() {
+            "command line" => {
+                self.environment = self
+                    .environment
+                    .take()
+                    .or_else(|| environment_from_command_line(&value));
+                self.command_line = Some(value);
+            }
+            "environment" => self.environment = Some(value),
+            "os" | "operating system" | "windows version" => self.os = Some(value),
+            "host cpu" | "cpu" | "processor" => self.cpu = Some(value),
+            "logical cpu count" | "cpu cores" | "logical processors" => {
+                self.cpu_cores = value.parse().ok();
+            }
+            "physical memory" | "total physical memory" | "system memory" => {
+                self.memory = Some(value);
+            }
+            "gpu" | "d3d adapter" | "video card" | "graphics card" | "adapter description" => {
+                self.gpu = Some(value);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Takes the log start time and the shards from session markers.
+    pub fn record_marker(&mut self, marker: &SessionMarker) {
+        match &marker.kind {
+            SessionMarkerKind::LogStart if self.started.is_none() => {
+                self.started = Some(marker.timestamp);
+            }
+            SessionMarkerKind::ServerJoin { shard, .. } if !self.shards.contains(shard) => {
+                self.shards.push(shard.clone());
+            }
+            _ => {}
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        *self != LogMetadata::default()
+    }
+
+    /// The server regions of the joined shards, e.g. `euw1` for `pub_euw1b_9100`.
+    pub fn regions(&self) -> Vec<String> {
+        let mut regions: Vec<String> = Vec::new();
+        for shard in &self.shards {
+            let Some(region) = shard_region(shard) else {
+                continue;
+            };
+            if !regions.contains(&region) {
+                regions.push(region);
+            }
+        }
+        regions
+    }
+
+    /// Label and value of everything that is known, in display order.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = Vec::new();
+        if let Some(started) = self.started {
+            fields.push((
+                "Started",
+                started.to_rfc3339_opts(SecondsFormat::Secs, true),
+            ));
+        }
+        if self.build.is_known() {
+            fields.push(("Build", self.build.to_string()));
+        }
+        let text = [
+            ("Branch", &self.build.branch),
+            ("Built on", &self.built_on),
+            ("Environment", &self.environment),
+            ("OS", &self.os),
+            ("CPU", &self.cpu),
+        ];
+        for (label, value) in text {
+            if let Some(value) = value {
+                fields.push((label, value.clone()));
+            }
+        }
+        if let Some(cores) = self.cpu_cores {
+            fields.push(("CPU cores", cores.to_string()));
+        }
+        for (label, value) in [("Memory", &self.memory), ("GPU", &self.gpu)] {
+            if let Some(value) = value {
+                fields.push((label, value.clone()));
+            }
+        }
+        if !self.shards.is_empty() {
+            fields.push(("Shards", self.shards.join(", ")));
+            let regions = self.regions();
+            if !regions.is_empty() {
+                fields.push(("Regions", regions.join(", ")));
+            }
+        }
+        if let Some(command_line) = &self.command_line {
+            fields.push(("Command line", command_line.clone()));
+        }
+        fields
+    }
+
+    /// `Label: value` lines, e.g. to paste into a bug report.
+    pub fn to_text(&self) -> String {
+        self.fields()
+            .into_iter()
+            .map(|(label, value)| format!("{}: {}\n", label, value))
+            .collect()
+    }
+}
+
+fn environment_from_command_line(command_line: &str) -> Option<String> {
+    static ENVTAG: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"-envtag[= ]+["']?(?P<env>[^\s"']+)"#).unwrap());
+    static TRACE_ENV: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"--system-trace-env-id[= ]+["']?(?P<env>[^\s"']+)"#).unwrap());
+    ENVTAG
+        .captures(command_line)
+        .or_else(|| TRACE_ENV.captures(command_line))
+        .map(|caps| caps["env"].to_string())
+}
+
+/// The second part of a shard name without the availability zone letter.
+fn shard_region(shard: &str) -> Option<String> {
+    let part = shard.split('_').nth(1)?;
+    let region = part
+        .strip_suffix(|c: char| c.is_ascii_lowercase())
+        .unwrap_or(part);
+    let valid = region.chars().any(|c| c.is_ascii_digit())
+        && region.chars().all(|c| c.is_ascii_alphanumeric());
+    valid.then(|| region.to_string())
+}
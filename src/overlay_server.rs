@@ -0,0 +1,241 @@
+use sc_log_analyzer::{PlayerEvent, merge_newest_first};
+use std::{
+    cmp::Reverse,
+    io::Write,
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, RecvTimeoutError, Sender},
+    },
+    time::Duration,
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+pub const DEFAULT_OVERLAY_PORT: u16 = 8765;
+
+const OVERLAY_PAGE: &str =
+    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/overlay.html"));
+
+/// Comment lines sent to idle streams, so dead connections are noticed and proxies keep them
+/// open.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Newest events kept for `/events`, so a long history is not held a second time.
+const MAX_BUFFERED_EVENTS: usize = 1000;
+/// Events returned by `/events` without a `limit`.
+const DEFAULT_EVENTS_LIMIT: usize = 50;
+
+/// The events and stream subscribers shared with the request threads.
+#[derive(Default)]
+struct Shared {
+    events: Mutex<Vec<PlayerEvent>>,
+    subscribers: Mutex<Vec<Sender<String>>>,
+}
+
+/// Local HTTP server for stream overlays. It only listens on 127.0.0.1 and only answers
+/// requests addressed to `127.0.0.1:<port>` or `localhost:<port>`, so other websites open in
+/// the browser cannot read the events, not even through DNS rebinding.
+///
+/// - `GET /` serves the bundled kill feed overlay page.
+/// - `GET /events` returns the newest loaded events as JSON, newest first, like the export.
+///   `?limit=N` asks for `N` of them instead of the default 50, up to the 1000 kept.
+/// - `GET /events/stream` is a Server-Sent Events stream with an `event` message for every
+///   line appended to the followed log and a `reset` message when the log is reloaded.
+pub struct OverlayServer {
+    server: Arc<Server>,
+    shared: Arc<Shared>,
+    port: u16,
+}
+
+impl OverlayServer {
+    pub fn start(port: u16) -> Result<Self, String> {
+        let server = Server::http(("127.0.0.1", port)).map_err(|err| {
+            format!(
+                "Failed to start the overlay server on port {}: {}",
+                port, err
+            )
+        })?;
+        let server = Arc::new(server);
+        let shared = Arc::new(Shared::default());
+
+        let accept_server = Arc::clone(&server);
+        let accept_shared = Arc::clone(&shared);
+        std::thread::spawn(move || {
+            for request in accept_server.incoming_requests() {
+                handle_request(request, &accept_shared, port);
+            }
+        });
+        Ok(Self {
+            server,
+            shared,
+            port,
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}/", self.port)
+    }
+
+    /// Forgets all events and tells stream clients to start over.
+    pub fn reset(&self) {
+        self.shared
+            .events
+            .lock()
+            .expect("overlay events lock")
+            .clear();
+        self.send("event: reset\ndata: {}\n\n".to_string());
+    }
+
+    /// Adds events to the list served at `/events`, which keeps the newest
+    /// `MAX_BUFFERED_EVENTS`.
+    pub fn extend(&self, events: &[PlayerEvent]) {
+        let mut newest: Vec<&PlayerEvent> = events.iter().collect();
+        if newest.len() > MAX_BUFFERED_EVENTS {
+            newest.sort_by_key(|event| Reverse(event.timestamp));
+            newest.truncate(MAX_BUFFERED_EVENTS);
+        }
+        let mut list = self.shared.events.lock().expect("overlay events lock");
+        merge_newest_first(&mut list, newest.into_iter().cloned().collect());
+        list.truncate(MAX_BUFFERED_EVENTS);
+    }
+
+    /// Pushes live events to the stream clients, oldest first.
+    pub fn broadcast(&self, events: &[PlayerEvent]) {
+        let mut ordered: Vec<&PlayerEvent> = events.iter().collect();
+        ordered.sort_by_key(|event| event.timestamp);
+        for event in ordered {
+            match serde_json::to_string(event) {
+                Ok(json) => self.send(format!("event: event\ndata: {}\n\n", json)),
+                Err(err) => eprintln!("Failed to encode event for the overlay: {}", err),
+            }
+        }
+    }
+
+    fn send(&self, message: String) {
+        self.shared
+            .subscribers
+            .lock()
+            .expect("overlay subscribers lock")
+            .retain(|subscriber| subscriber.send(message.clone()).is_ok());
+    }
+}
+
+impl Drop for OverlayServer {
+    fn drop(&mut self) {
+        // Ends the accept loop, and dropping the senders ends every stream.
+        self.server.unblock();
+        self.shared
+            .subscribers
+            .lock()
+            .expect("overlay subscribers lock")
+            .clear();
+    }
+}
+
+fn handle_request(request: Request, shared: &Arc<Shared>, port: u16) {
+    if !is_local_host(&request, port) {
+        let _ = request.respond(Response::from_string("Forbidden").with_status_code(403));
+        return;
+    }
+    if *request.method() != Method::Get {
+        let _ = request.respond(Response::from_string("Method not allowed").with_status_code(405));
+        return;
+    }
+    let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+    let result = match path {
+        "/" | "/overlay" | "/overlay.html" => request.respond(
+            Response::from_string(OVERLAY_PAGE)
+                .with_header(header("Content-Type", "text/html; charset=utf-8")),
+        ),
+        "/events" => {
+            let limit = query_param(query, "limit").and_then(|value| value.parse::<usize>().ok());
+            let body = {
+                let events = shared.events.lock().expect("overlay events lock");
+                let shown = &events[..limit.unwrap_or(DEFAULT_EVENTS_LIMIT).min(events.len())];
+                serde_json::to_string(shown)
+            };
+            match body {
+                Ok(body) => request.respond(
+                    Response::from_string(body)
+                        .with_header(header("Content-Type", "application/json")),
+                ),
+                Err(err) => request.respond(
+                    Response::from_string(format!("Failed to encode events: {}", err))
+                        .with_status_code(500),
+                ),
+            }
+        }
+        "/events/stream" => {
+            stream_events(request, shared);
+            Ok(())
+        }
+        _ => request.respond(Response::from_string("Not found").with_status_code(404)),
+    };
+    if let Err(err) = result {
+        eprintln!("Overlay server failed to respond: {}", err);
+    }
+}
+
+/// Holds the connection open on its own thread and writes every broadcast message to it.
+fn stream_events(request: Request, shared: &Arc<Shared>) {
+    let (tx, rx) = mpsc::channel::<String>();
+    shared
+        .subscribers
+        .lock()
+        .expect("overlay subscribers lock")
+        .push(tx);
+    let mut writer = request.into_writer();
+    std::thread::spawn(move || {
+        let head = "HTTP/1.1 200 OK\r\n\
+            Content-Type: text/event-stream\r\n\
+            Cache-Control: no-cache\r\n\
+            Connection: keep-alive\r\n\r\n";
+        if writer
+            .write_all(head.as_bytes())
+            .and_then(|()| writer.flush())
+            .is_err()
+        {
+            return;
+        }
+        loop {
+            let message = match rx.recv_timeout(KEEPALIVE_INTERVAL) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => ": keepalive\n\n".to_string(),
+                Err(RecvTimeoutError::Disconnected) => return,
+            };
+            if writer
+                .write_all(message.as_bytes())
+                .and_then(|()| writer.flush())
+                .is_err()
+            {
+                return;
+            }
+        }
+    });
+}
+
+/// Whether the `Host` header names this server, and not some other site that resolves to
+/// 127.0.0.1.
+fn is_local_host(request: &Request, port: u16) -> bool {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Host"))
+        .is_some_and(|header| {
+            let host = header.value.as_str();
+            [format!("127.0.0.1:{}", port), format!("localhost:{}", port)]
+                .iter()
+                .any(|allowed| host.eq_ignore_ascii_case(allowed))
+        })
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid header")
+}
@@ -0,0 +1,444 @@
+use crate::event::{
+    CorpseStatusEvent, EventKind, HitEvent, KillEvent, PlayerEvent, SpawnReservationEvent,
+    StatusEffectEvent, VehicleDestructionEvent, ZoneTransferEvent, matches_ignore_case,
+};
+use crate::filter::EventCategory;
+use crate::health::ParserHealth;
+use crate::metadata::LogMetadata;
+use crate::patterns::PatternSet;
+use crate::profile::ParserProfile;
+use crate::session::{SessionMarker, parse_session_marker};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+pub struct ParsedLog {
+    /// Events sorted newest first.
+    pub events: Vec<PlayerEvent>,
+    pub primary_nickname: Option<String>,
+    /// Login, server join and disconnect lines in file order, see `segment_sessions`.
+    pub markers: Vec<SessionMarker>,
+    pub health: ParserHealth,
+    /// The log header and the shards joined. Its game build picked the parser profile.
+    pub metadata: LogMetadata,
+}
+
+/// Parses a complete log from any buffered reader, with `patterns` for user-defined events.
+pub fn parse_reader<R: BufRead>(mut reader: R, patterns: &PatternSet) -> io::Result<ParsedLog> {
+    let mut parser = LineParser::new(patterns.clone());
+    let mut events = Vec::new();
+    let mut buffer = Vec::new();
+
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        if buffer.ends_with(b"\n") {
+            buffer.pop();
+        }
+        if let Some(event) = parser.push_line(&buffer) {
+            events.push(event);
+        }
+    }
+
+    events.sort_by_key(|event| event.timestamp);
+    events.reverse();
+
+    Ok(ParsedLog {
+        events,
+        markers: parser.take_markers(),
+        health: parser.take_health(),
+        metadata: parser.metadata,
+        primary_nickname: parser.primary_nickname,
+    })
+}
+
+pub fn parse_log(path: &Path, patterns: &PatternSet) -> Result<ParsedLog, String> {
+    let file =
+        File::open(path).map_err(|err| format!("Failed to open {}: {}", path.display(), err))?;
+    parse_reader(BufReader::new(file), patterns)
+        .map_err(|err| format!("Failed to read line from {}: {}", path.display(), err))
+}
+
+/// Turns raw log lines into events while carrying the state that spans lines.
+#[derive(Default)]
+pub struct LineParser {
+    primary_nickname: Option<String>,
+    last_raw: Option<String>,
+    markers: Vec<SessionMarker>,
+    health: ParserHealth,
+    metadata: LogMetadata,
+    /// Header lines are only looked for before the first event.
+    header_done: bool,
+    /// Chosen once the header names the game version; the latest profile until then.
+    profile: Option<&'static ParserProfile>,
+    /// User-defined events, tried after the profile's parsers.
+    patterns: PatternSet,
+}
+
+impl LineParser {
+    pub fn new(patterns: PatternSet) -> Self {
+        Self {
+            patterns,
+            ..Self::default()
+        }
+    }
+
+    /// The first `nickname="…"` seen so far, usually the player who owns the log.
+    pub fn primary_nickname(&self) -> Option<&str> {
+        self.primary_nickname.as_deref()
+    }
+
+    pub fn metadata(&self) -> &LogMetadata {
+        &self.metadata
+    }
+
+    pub fn profile(&self) -> &'static ParserProfile {
+        self.profile.unwrap_or_else(ParserProfile::latest)
+    }
+
+    /// Session markers collected since the last call.
+    pub fn take_markers(&mut self) -> Vec<SessionMarker> {
+        std::mem::take(&mut self.markers)
+    }
+
+    /// Parser counts and unmatched lines since the last call.
+    pub fn take_health(&mut self) -> ParserHealth {
+        std::mem::take(&mut self.health)
+    }
+
+    /// Parses one line (without its trailing newline). Consecutive duplicates are dropped.
+    /// Login, join and disconnect lines are kept as session markers and only the user-defined
+    /// patterns can also turn them into an event.
+    pub fn push_line(&mut self, bytes: &[u8]) -> Option<PlayerEvent> {
+        let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+        let line = String::from_utf8_lossy(bytes);
+        if self.primary_nickname.is_none() {
+            self.primary_nickname = extract_nickname(&line);
+        }
+        if !self.header_done && self.metadata.read_header_line(&line) {
+            self.profile = Some(ParserProfile::select(&self.metadata.build));
+            return None;
+        }
+        let event = match parse_session_marker(&line) {
+            Some(marker) => {
+                self.metadata.record_marker(&marker);
+                self.markers.push(marker);
+                self.patterns.parse_line(&line)?
+            }
+            None => {
+                let event = self
+                    .profile()
+                    .parse_line(&line)
+                    .or_else(|| self.patterns.parse_line(&line));
+                let Some(event) = event else {
+                    self.health.record_unparsed(&line);
+                    return None;
+                };
+                event
+            }
+        };
+        self.health.record_parsed(EventCategory::of(&event.kind));
+        self.header_done = true;
+        if self.last_raw.as_deref() == Some(event.raw.as_str()) {
+            return None;
+        }
+        self.last_raw = Some(event.raw.clone());
+        Some(event)
+    }
+}
+
+pub fn extract_nickname(line: &str) -> Option<String> {
+    let marker = "nickname=\"";
+    let start = line.find(marker)? + marker.len();
+    let rest = &line[start..];
+    let end = rest.find('"')?;
+    Some(rest[..end].to_string())
+}
+
+/// Tries the parsers of the latest profile on a single line. `LineParser` picks the profile
+/// from the log header instead and also tries the user-defined patterns.
+pub fn parse_line(line: &str) -> Option<PlayerEvent> {
+    ParserProfile::latest().parse_line(line)
+}
+
+/// Kill lines. Since 4.0 the zone and the weapon are left out for some deaths.
+pub fn parse_actor_death(line: &str) -> Option<PlayerEvent> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?<Actor Death> CActor::Kill: ["'](?P<victim>[^"']+)["']\s\[(?P<victim_id>[^\]]+)\](?: in zone ["'](?P<zone>[^"']+)["'])? killed by ["'](?P<killer>[^"']+)["']\s\[(?P<killer_id>[^\]]+)\](?: using ["'](?P<weapon>[^"']*)["'](?: \[(?P<weapon_class>[^\]]+)\])?)?\s+with damage type ["'](?P<damage>[^"']+)["'].*"#)
+            .unwrap()
+    });
+    kill_from_captures(RE.captures(line)?, line)
+}
+
+/// Kill lines of 3.x builds, which always name the zone and the weapon with its class.
+pub fn parse_actor_death_v3(line: &str) -> Option<PlayerEvent> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?<Actor Death> CActor::Kill: ["'](?P<victim>[^"']+)["']\s\[(?P<victim_id>[^\]]+)\] in zone ["'](?P<zone>[^"']+)["'] killed by ["'](?P<killer>[^"']+)["']\s\[(?P<killer_id>[^\]]+)\] using ["'](?P<weapon>[^"']*)["'] \[(?P<weapon_class>[^\]]+)\]\s+with damage type ["'](?P<damage>[^"']+)["'].*"#)
+            .unwrap()
+    });
+    kill_from_captures(RE.captures(line)?, line)
+}
+
+fn kill_from_captures(caps: Captures, line: &str) -> Option<PlayerEvent> {
+    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
+    let victim_name = caps.name("victim")?.as_str().to_string();
+    let victim_id = caps.name("victim_id")?.as_str().to_string();
+    let zone = caps
+        .name("zone")
+        .map(|m| m.as_str().to_string())
+        .unwrap_or_default();
+    let killer_name = caps.name("killer")?.as_str().to_string();
+    let killer_id = caps.name("killer_id")?.as_str().to_string();
+    let weapon = caps
+        .name("weapon")
+        .map(|m| m.as_str().to_string())
+        .unwrap_or_default();
+    let weapon_class = caps
+        .name("weapon_class")
+        .map(|m| m.as_str().to_string())
+        .unwrap_or_default();
+    let damage_type = caps
+        .name("damage")
+        .map(|m| m.as_str().to_string())
+        .unwrap_or_default();
+
+    Some(PlayerEvent {
+        timestamp,
+        kind: EventKind::Kill(KillEvent {
+            victim_name,
+            victim_id,
+            killer_name,
+            killer_id,
+            weapon,
+            weapon_class,
+            damage_type,
+            zone,
+        }),
+        raw: line.to_string(),
+    })
+}
+
+pub fn parse_spawn_reservation(line: &str) -> Option<PlayerEvent> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?<Spawn Flow>.*?Player ["'](?P<player>[^"']+)["']\s\[(?P<player_id>[^\]]+)\] lost reservation for spawnpoint (?P<spawnpoint>[^\[]+)\s\[(?P<spawn_id>[^\]]+)\] at location (?P<location>[-\d]+)"#)
+            .unwrap()
+    });
+
+    let caps = RE.captures(line)?;
+    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
+    let player_name = caps.name("player")?.as_str().trim().to_string();
+    let player_id = caps.name("player_id")?.as_str().to_string();
+    let spawn_point = caps
+        .name("spawnpoint")
+        .map(|m| m.as_str().trim().to_string())
+        .unwrap_or_default();
+    let spawn_id = caps
+        .name("spawn_id")
+        .map(|m| m.as_str().to_string())
+        .unwrap_or_default();
+    let location = caps
+        .name("location")
+        .map(|m| m.as_str().to_string())
+        .unwrap_or_default();
+
+    Some(PlayerEvent {
+        timestamp,
+        kind: EventKind::SpawnReservation(SpawnReservationEvent {
+            player_name,
+            player_id,
+            spawn_point,
+            spawn_id,
+            location,
+        }),
+        raw: line.to_string(),
+    })
+}
+
+pub fn parse_corpse_status(line: &str) -> Option<PlayerEvent> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?\bPlayer ["'](?P<player>[^"'<>]+)["']\s*(?P<context><[^>]+>)?:\s*IsCorpseEnabled:\s*(?P<enabled>Yes|No)\.?"#)
+            .unwrap()
+    });
+
+    let caps = RE.captures(line)?;
+    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
+    let player_name = caps.name("player")?.as_str().trim().to_string();
+    let enabled_raw = caps.name("enabled")?.as_str();
+    let corpse_enabled = matches_ignore_case(enabled_raw, "Yes");
+    if corpse_enabled {
+        return None;
+    }
+    let context = caps.name("context").and_then(|m| {
+        let trimmed = m
+            .as_str()
+            .trim()
+            .trim_start_matches('<')
+            .trim_end_matches('>')
+            .trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed.to_string())
+        }
+    });
+
+    Some(PlayerEvent {
+        timestamp,
+        kind: EventKind::CorpseStatus(CorpseStatusEvent {
+            player_name,
+            context,
+            corpse_enabled,
+        }),
+        raw: line.to_string(),
+    })
+}
+
+pub fn parse_zone_transfer(line: &str) -> Option<PlayerEvent> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?moving zone hosted child id = (?P<child_id>\d+)\s+name\s*=\s*"(?P<player>[^"]+)"\s+to unblock removal of parent id = (?P<parent_id>\d+)\s+name\s*=\s*"(?P<parent_name>[^"]+)"\s+into zone host id = (?P<host_id>\d+)\s+name\s*=\s*"(?P<host_name>[^"]+)""#)
+            .unwrap()
+    });
+
+    let caps = RE.captures(line)?;
+    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
+    let player_name = caps.name("player")?.as_str().to_string();
+
+    if player_name.is_empty() {
+        return None;
+    }
+
+    Some(PlayerEvent {
+        timestamp,
+        kind: EventKind::ZoneTransfer(ZoneTransferEvent {
+            player_name,
+            child_id: caps.name("child_id").map(|m| m.as_str().to_string()),
+            parent_id: caps.name("parent_id").map(|m| m.as_str().to_string()),
+            parent_name: caps.name("parent_name").map(|m| m.as_str().to_string()),
+            host_id: caps.name("host_id").map(|m| m.as_str().to_string()),
+            host_name: caps.name("host_name").map(|m| m.as_str().to_string()),
+        }),
+        raw: line.to_string(),
+    })
+}
+
+pub fn parse_status_effect(line: &str) -> Option<PlayerEvent> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?Logged (?P<article>a|an) (?P<stage>start|end) of a status effect!\s*nickname:\s*(?P<nickname>[^,]+),\s*status effect:\s*(?P<effect>.+)"#)
+            .unwrap()
+    });
+
+    let caps = RE.captures(line)?;
+    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
+    let nickname = caps.name("nickname")?.as_str().trim().to_string();
+
+    Some(PlayerEvent {
+        timestamp,
+        kind: EventKind::StatusEffect(StatusEffectEvent {
+            player_name: nickname,
+            effect: caps
+                .name("effect")
+                .map(|m| m.as_str().trim().to_string())
+                .unwrap_or_default(),
+            stage: caps
+                .name("stage")
+                .map(|m| m.as_str().trim().to_ascii_lowercase())
+                .unwrap_or_else(|| "start".to_string()),
+        }),
+        raw: line.to_string(),
+    })
+}
+
+pub fn parse_hit_event(line: &str) -> Option<PlayerEvent> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?<Debug Hostility Events>.*?Fake hit FROM (?P<attacker>[^\s]+) TO (?P<target>[^\.]+)\.\s*Being sent to child (?P<child>[\w_-]+)"#)
+            .unwrap()
+    });
+
+    let caps = RE.captures(line)?;
+    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
+    let attacker = caps.name("attacker")?.as_str().to_string();
+    let target = caps.name("target")?.as_str().trim().to_string();
+    let child = caps
+        .name("child")
+        .map(|m| m.as_str().trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    Some(PlayerEvent {
+        timestamp,
+        kind: EventKind::Hit(HitEvent {
+            attacker,
+            target,
+            child,
+        }),
+        raw: line.to_string(),
+    })
+}
+
+pub fn parse_vehicle_destruction(line: &str) -> Option<PlayerEvent> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?<Vehicle Destruction>.*?Vehicle '(?P<vehicle>[^']+)'\s*\[(?P<vehicle_id>[^\]]+)\].*?zone '(?P<zone>[^']+)'.*?driven by '(?P<driver>[^']+)'\s*\[(?P<driver_id>[^\]]+)\].*?advanced from destroy level (?P<from>\d+) to (?P<to>\d+) caused by '(?P<attacker>[^']+)'\s*\[(?P<attacker_id>[^\]]+)\]\s*with '(?P<cause>[^']+)'"#)
+            .unwrap()
+    });
+
+    let caps = RE.captures(line)?;
+    let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
+    Some(PlayerEvent {
+        timestamp,
+        kind: EventKind::VehicleDestruction(VehicleDestructionEvent {
+            vehicle_name: caps
+                .name("vehicle")
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default(),
+            vehicle_id: caps
+                .name("vehicle_id")
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default(),
+            zone: caps
+                .name("zone")
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default(),
+            driver_name: caps
+                .name("driver")
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default(),
+            driver_id: caps
+                .name("driver_id")
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default(),
+            from_level: caps
+                .name("from")
+                .and_then(|m| m.as_str().parse::<u32>().ok())
+                .unwrap_or_default(),
+            to_level: caps
+                .name("to")
+                .and_then(|m| m.as_str().parse::<u32>().ok())
+                .unwrap_or_default(),
+            attacker_name: caps
+                .name("attacker")
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default(),
+            attacker_id: caps
+                .name("attacker_id")
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default(),
+            cause: caps
+                .name("cause")
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default(),
+        }),
+        raw: line.to_string(),
+    })
+}
+
+pub(crate) fn parse_timestamp(raw: &str) -> Option<DateTime<Utc>> {
+    chrono::DateTime::parse_from_rfc3339(raw)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+}
@@ -0,0 +1,318 @@
+use crate::event::{CustomEvent, EventKind, PlayerEvent};
+use crate::filter::EventCategory;
+use crate::parser::parse_timestamp;
+use regex::Regex;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    path::Path,
+    sync::Arc,
+};
+
+/// Color of custom events whose pattern does not set one.
+pub const DEFAULT_CUSTOM_COLOR: [u8; 3] = [225, 205, 120];
+
+/// Written by "Create example file", with every key a pattern can have.
+pub const EXAMPLE_PATTERNS: &str = r##"# Custom event patterns. Every [[pattern]] turns matching log lines into an event that can be
+# filtered, searched, exported and charted like the built-in ones.
+#
+# name          Shown in the event list and matched by `type:<name>` searches.
+# regex         Matched against the whole line. Named captures become the event fields. The
+#               time comes from a `timestamp` capture, or else from the `<…>` the line starts with.
+# summary       Text after the time, with `{capture}` placeholders. Defaults to the name.
+# color         Optional "#rrggbb" for the event list and chart.
+# participants  Captures that hold player names. They can be clicked and are matched by
+#               `player:` searches and the roster. The ignored player hides an event when they
+#               are the first participant.
+
+[[pattern]]
+name = "control"
+regex = '<Vehicle Control Flow>.*?control token for .(?P<vehicle>[A-Za-z0-9_]+?)_(?P<vehicle_id>\d+)'
+summary = "Took control of {vehicle}"
+color = "#78c8e6"
+participants = []
+"##;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PatternFile {
+    #[serde(default)]
+    pattern: Vec<PatternSpec>,
+}
+
+/// One `[[pattern]]` table as written in the file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PatternSpec {
+    name: String,
+    regex: String,
+    summary: Option<String>,
+    color: Option<String>,
+    #[serde(default)]
+    participants: Vec<String>,
+}
+
+/// A user-defined event type.
+#[derive(Clone, Debug)]
+pub struct EventPattern {
+    pub name: String,
+    pub regex: Regex,
+    /// Summary template with `{capture}` placeholders.
+    pub summary: String,
+    pub color: Option<[u8; 3]>,
+    /// Captures that hold player names.
+    pub participants: Vec<String>,
+}
+
+impl EventPattern {
+    /// Builds the event for `line` if the regex matches and a time can be found.
+    pub fn parse_line(&self, line: &str) -> Option<PlayerEvent> {
+        let caps = self.regex.captures(line)?;
+        let timestamp = match caps.name("timestamp") {
+            Some(value) => parse_timestamp(value.as_str())?,
+            None => parse_timestamp(line.strip_prefix('<')?.split_once('>')?.0)?,
+        };
+        let fields: BTreeMap<String, String> = self
+            .regex
+            .capture_names()
+            .flatten()
+            .filter(|name| *name != "timestamp")
+            .filter_map(|name| {
+                caps.name(name)
+                    .map(|value| (name.to_string(), value.as_str().trim().to_string()))
+            })
+            .collect();
+        let participants = self
+            .participants
+            .iter()
+            .filter_map(|name| fields.get(name))
+            .filter(|value| !value.is_empty())
+            .cloned()
+            .collect();
+        let summary = fill_template(&self.summary, |name| {
+            fields.get(name).map(String::as_str).unwrap_or_default()
+        });
+
+        Some(PlayerEvent {
+            timestamp,
+            kind: EventKind::Custom(CustomEvent {
+                name: self.name.clone(),
+                summary,
+                fields,
+                participants,
+            }),
+            raw: line.to_string(),
+        })
+    }
+}
+
+/// The patterns of one pattern file, tried in file order after the built-in parsers. Handed to
+/// `LineParser`, `parse_reader` and `Query::parse`; cloning it is cheap.
+#[derive(Clone, Debug, Default)]
+pub struct PatternSet {
+    patterns: Arc<[EventPattern]>,
+}
+
+impl PatternSet {
+    /// Parses and checks a TOML pattern file. Errors name the pattern they were found in.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let file: PatternFile =
+            toml::from_str(text).map_err(|err| format!("Invalid pattern file: {}", err))?;
+        let mut names = HashSet::new();
+        let mut patterns = Vec::with_capacity(file.pattern.len());
+        for (index, spec) in file.pattern.into_iter().enumerate() {
+            let name = spec.name.trim().to_string();
+            let error =
+                |message: String| format!("Pattern {} ('{}'): {}", index + 1, name, message);
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+            {
+                return Err(error(
+                    "names may only contain letters, digits, '_' and '-'".to_string(),
+                ));
+            }
+            if EventCategory::ALL
+                .iter()
+                .any(|category| category.key().eq_ignore_ascii_case(&name))
+            {
+                return Err(error(
+                    "the name is already used by a built-in category".to_string(),
+                ));
+            }
+            if !names.insert(name.to_ascii_lowercase()) {
+                return Err(error("the name is used by an earlier pattern".to_string()));
+            }
+            let regex = Regex::new(&spec.regex).map_err(|err| error(err.to_string()))?;
+            let captures: HashSet<&str> = regex.capture_names().flatten().collect();
+            for participant in &spec.participants {
+                if !captures.contains(participant.as_str()) {
+                    return Err(error(format!(
+                        "participant '{}' is not a named capture",
+                        participant
+                    )));
+                }
+            }
+            let summary = spec.summary.unwrap_or_else(|| name.clone());
+            let mut missing = None;
+            fill_template(&summary, |placeholder| {
+                if !captures.contains(placeholder) && missing.is_none() {
+                    missing = Some(placeholder.to_string());
+                }
+                ""
+            });
+            if let Some(placeholder) = missing {
+                return Err(error(format!(
+                    "summary placeholder '{{{}}}' is not a named capture",
+                    placeholder
+                )));
+            }
+            let color = spec
+                .color
+                .as_deref()
+                .map(|color| {
+                    parse_color(color)
+                        .ok_or_else(|| error(format!("invalid color '{}' (use #rrggbb)", color)))
+                })
+                .transpose()?;
+            patterns.push(EventPattern {
+                name,
+                regex,
+                summary,
+                color,
+                participants: spec.participants,
+            });
+        }
+        Ok(Self {
+            patterns: patterns.into(),
+        })
+    }
+
+    /// Reads a pattern file. A file that does not exist gives an empty set.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|err| format!("{}: {}", path.display(), err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("Failed to read {}: {}", path.display(), err)),
+        }
+    }
+
+    pub fn patterns(&self) -> &[EventPattern] {
+        &self.patterns
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&EventPattern> {
+        self.patterns
+            .iter()
+            .find(|pattern| pattern.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The color of the named pattern, or [`DEFAULT_CUSTOM_COLOR`].
+    pub fn color(&self, name: &str) -> [u8; 3] {
+        self.get(name)
+            .and_then(|pattern| pattern.color)
+            .unwrap_or(DEFAULT_CUSTOM_COLOR)
+    }
+
+    /// The event of the first pattern that matches `line`.
+    pub fn parse_line(&self, line: &str) -> Option<PlayerEvent> {
+        self.patterns
+            .iter()
+            .find_map(|pattern| pattern.parse_line(line))
+    }
+}
+
+/// Replaces every `{name}` in `template` with `value(name)`; `{{` and `}}` are literal braces.
+fn fill_template<'a>(template: &str, mut value: impl FnMut(&str) -> &'a str) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        filled.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        if rest[start..].starts_with("{{") || rest[start..].starts_with("}}") {
+            filled.push_str(&rest[start..start + 1]);
+            rest = &after[1..];
+            continue;
+        }
+        match (rest.as_bytes()[start], after.find('}')) {
+            (b'{', Some(end)) => {
+                filled.push_str(value(after[..end].trim()));
+                rest = &after[end + 1..];
+            }
+            _ => {
+                filled.push_str(&rest[start..start + 1]);
+                rest = after;
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+fn parse_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.trim().strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_reader;
+
+    const LOG: &str = "<2024-09-14T18:38:00.000Z> [Notice] <Join PU> address[10.0.0.1] port[64300] shard[pub_euw1b_9100] locationId[-1]\n\
+        <2024-09-14T18:39:00.000Z> [Notice] <Vehicle Control Flow> granted control token for 'AEGS_Gladius_1234' [Team_VehicleFeatures]\n";
+
+    const PATTERNS: &str = r#"
+[[pattern]]
+name = "join"
+regex = 'shard\[(?P<shard>[^\]]+)\]'
+summary = "Joined {shard}"
+
+[[pattern]]
+name = "control"
+regex = 'control token for .(?P<vehicle>[A-Za-z0-9_]+?)_(?P<vehicle_id>\d+)'
+"#;
+
+    fn custom_names(patterns: &PatternSet) -> Vec<String> {
+        let parsed = parse_reader(LOG.as_bytes(), patterns).expect("reads");
+        assert_eq!(
+            parsed.markers.len(),
+            1,
+            "the join line stays a session marker"
+        );
+        parsed
+            .events
+            .iter()
+            .filter_map(|event| match &event.kind {
+                EventKind::Custom(custom) => Some(custom.name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn logs_parse_with_the_patterns_they_are_given() {
+        assert!(custom_names(&PatternSet::default()).is_empty());
+        let patterns = PatternSet::parse(PATTERNS).expect("valid patterns");
+        assert_eq!(custom_names(&patterns), ["control", "join"]);
+        assert!(custom_names(&PatternSet::default()).is_empty());
+    }
+
+    #[test]
+    fn example_patterns_parse() {
+        let patterns = PatternSet::parse(EXAMPLE_PATTERNS).expect("valid example");
+        assert!(patterns.get("CONTROL").is_some());
+        assert_eq!(patterns.color("control"), [0x78, 0xc8, 0xe6]);
+        assert_eq!(patterns.color("missing"), DEFAULT_CUSTOM_COLOR);
+    }
+}
@@ -0,0 +1,101 @@
+use eframe::egui::{self, Color32, RichText};
+use sc_log_analyzer::PatternSet;
+use std::path::Path;
+
+pub enum PatternsAction {
+    /// Read the pattern file again and parse the log with it.
+    Reload,
+    /// Write the example file to the pattern file path, which does not exist yet.
+    CreateExample,
+}
+
+/// The custom event patterns that are loaded and the errors of the pattern file.
+#[derive(Default)]
+pub struct PatternsWindow {
+    pub open: bool,
+}
+
+impl PatternsWindow {
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        path: Option<&Path>,
+        patterns: &PatternSet,
+        error: Option<&str>,
+    ) -> Option<PatternsAction> {
+        if !self.open {
+            return None;
+        }
+        let mut action = None;
+        let mut open = self.open;
+        egui::Window::new("Custom events")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(
+                        "Patterns turn other log lines into events. Edit the file, then reload.",
+                    )
+                    .color(Color32::from_rgb(160, 160, 160)),
+                );
+                match path {
+                    Some(path) => {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("Pattern file:");
+                            ui.label(RichText::new(path.display().to_string()).monospace());
+                        });
+                        ui.horizontal(|ui| {
+                            if ui.button("Reload").clicked() {
+                                action = Some(PatternsAction::Reload);
+                            }
+                            if !path.exists() && ui.button("Create example file").clicked() {
+                                action = Some(PatternsAction::CreateExample);
+                            }
+                        });
+                    }
+                    None => {
+                        ui.colored_label(
+                            Color32::from_rgb(240, 90, 80),
+                            "Failed to resolve settings directory",
+                        );
+                    }
+                }
+                if let Some(error) = error {
+                    ui.colored_label(Color32::from_rgb(240, 90, 80), error);
+                }
+
+                ui.separator();
+                if patterns.is_empty() {
+                    ui.label(
+                        RichText::new("No custom patterns are loaded.")
+                            .color(Color32::from_rgb(160, 160, 160)),
+                    );
+                    return;
+                }
+                egui::Grid::new("custom_patterns")
+                    .num_columns(3)
+                    .spacing([12.0, 6.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for pattern in patterns.patterns() {
+                            let [r, g, b] = patterns.color(&pattern.name);
+                            ui.label(
+                                RichText::new(&pattern.name)
+                                    .strong()
+                                    .color(Color32::from_rgb(r, g, b)),
+                            );
+                            ui.label(RichText::new(pattern.regex.as_str()).monospace())
+                                .on_hover_text(format!("Summary: {}", pattern.summary));
+                            ui.label(if pattern.participants.is_empty() {
+                                String::new()
+                            } else {
+                                format!("Players: {}", pattern.participants.join(", "))
+                            });
+                            ui.end_row();
+                        }
+                    });
+            });
+        self.open = open;
+        action
+    }
+}
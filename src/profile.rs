@@ -0,0 +1,79 @@
+use crate::event::PlayerEvent;
+use crate::game_build::{GameBuild, GameVersion};
+use crate::parser::{
+    parse_actor_death, parse_actor_death_v3, parse_corpse_status, parse_hit_event,
+    parse_spawn_reservation, parse_status_effect, parse_vehicle_destruction, parse_zone_transfer,
+};
+
+pub type EventParser = fn(&str) -> Option<PlayerEvent>;
+
+/// The kill line parser for the log format of a range of game builds. Kill lines are the only
+/// format that changed between patches; the other parsers are shared by all profiles.
+pub struct ParserProfile {
+    pub name: &'static str,
+    /// The first version the profile applies to.
+    pub since: Option<GameVersion>,
+    /// The first version it no longer applies to.
+    pub until: Option<GameVersion>,
+    /// A kill line parser for the exact layout of these builds, tried before the lenient
+    /// `parse_actor_death`, which still reads any kill line it rejects.
+    pub actor_death: Option<EventParser>,
+}
+
+/// Newest first. The first matching profile wins, and the first one is used for logs without
+/// a version in their header.
+static PROFILES: [ParserProfile; 2] = [
+    ParserProfile {
+        name: "alpha-4",
+        since: Some(GameVersion::new(4, 0, 0)),
+        until: None,
+        actor_death: None,
+    },
+    // Kill lines always name the zone and the weapon before 4.0.
+    ParserProfile {
+        name: "alpha-3",
+        since: None,
+        until: Some(GameVersion::new(4, 0, 0)),
+        actor_death: Some(parse_actor_death_v3),
+    },
+];
+
+impl ParserProfile {
+    pub fn all() -> &'static [ParserProfile] {
+        &PROFILES
+    }
+
+    /// The profile for the newest game builds.
+    pub fn latest() -> &'static ParserProfile {
+        &PROFILES[0]
+    }
+
+    /// The profile for the logs of `build`, or the latest one if its version is unknown.
+    pub fn select(build: &GameBuild) -> &'static ParserProfile {
+        let Some(version) = build.version else {
+            return Self::latest();
+        };
+        PROFILES
+            .iter()
+            .find(|profile| profile.applies_to(version))
+            .unwrap_or_else(Self::latest)
+    }
+
+    pub fn applies_to(&self, version: GameVersion) -> bool {
+        self.since.is_none_or(|since| version >= since)
+            && self.until.is_none_or(|until| version < until)
+    }
+
+    /// Tries the parsers of the profile on a single line.
+    pub fn parse_line(&self, line: &str) -> Option<PlayerEvent> {
+        self.actor_death
+            .and_then(|parse| parse(line))
+            .or_else(|| parse_actor_death(line))
+            .or_else(|| parse_spawn_reservation(line))
+            .or_else(|| parse_corpse_status(line))
+            .or_else(|| parse_zone_transfer(line))
+            .or_else(|| parse_status_effect(line))
+            .or_else(|| parse_hit_event(line))
+            .or_else(|| parse_vehicle_destruction(line))
+    }
+}
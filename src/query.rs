@@ -0,0 +1,784 @@
+use crate::event::{EventKind, PlayerEvent};
+use crate::filter::EventCategory;
+use crate::patterns::PatternSet;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use regex::Regex;
+use std::fmt;
+
+/// Names accepted before a colon, e.g. `killer:Foo`.
+pub const FIELDS: [&str; 11] = [
+    "killer", "victim", "player", "weapon", "damage", "zone", "vehicle", "effect", "type", "after",
+    "before",
+];
+
+/// A parsed search query.
+///
+/// Bare words and quoted phrases match anywhere in the summary, details or raw line, like the
+/// plain search did. `field:value` compares a typed field (case-insensitive, `*` and `?` are
+/// wildcards), `field:~value` checks that the field contains the value. Terms next to each
+/// other must all match; `OR`, `NOT` (or a leading `-`) and parentheses combine them.
+/// `type:` takes a category or the name of a user-defined pattern in the set given to `parse`.
+/// `after:`/`before:` take a UTC time of day (`12:30`), a date (`2024-09-14`) or both
+/// (`2024-09-14T12:30`).
+#[derive(Clone, Debug)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Text(String),
+    Field(Field, Matcher),
+    Type(EventCategory),
+    /// Events of the user-defined pattern with this name.
+    Pattern(String),
+    After(TimeBound),
+    Before(TimeBound),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Killer,
+    Victim,
+    Player,
+    Weapon,
+    Damage,
+    Zone,
+    Vehicle,
+    Effect,
+}
+
+impl Field {
+    pub fn key(self) -> &'static str {
+        match self {
+            Field::Killer => "killer",
+            Field::Victim => "victim",
+            Field::Player => "player",
+            Field::Weapon => "weapon",
+            Field::Damage => "damage",
+            Field::Zone => "zone",
+            Field::Vehicle => "vehicle",
+            Field::Effect => "effect",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Matcher {
+    Exact(String),
+    Contains(String),
+    Wildcard(Regex),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum TimeBound {
+    TimeOfDay(NaiveTime),
+    Instant(DateTime<Utc>),
+}
+
+/// A syntax error with the character column (starting at 1) it was found at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    pub column: usize,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (column {})", self.message, self.column)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl Query {
+    /// Parses `input`; an empty or blank query gives `None`.
+    pub fn parse(input: &str, patterns: &PatternSet) -> Result<Option<Query>, QueryError> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Ok(None);
+        }
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            input_len: input.chars().count(),
+            patterns,
+        };
+        let query = parser.parse_or()?;
+        if let Some((token, column)) = parser.tokens.get(parser.position) {
+            let message = match token {
+                Token::RParen => "Unbalanced ')'".to_string(),
+                _ => "Unexpected input".to_string(),
+            };
+            return Err(QueryError {
+                message,
+                column: *column,
+            });
+        }
+        Ok(Some(query))
+    }
+
+    pub fn matches(&self, event: &PlayerEvent) -> bool {
+        let mut blob = None;
+        self.matches_with_blob(event, &mut blob)
+    }
+
+    /// `blob` caches the lowercased search text, which only bare words need.
+    fn matches_with_blob(&self, event: &PlayerEvent, blob: &mut Option<String>) -> bool {
+        match self {
+            Query::And(left, right) => {
+                left.matches_with_blob(event, blob) && right.matches_with_blob(event, blob)
+            }
+            Query::Or(left, right) => {
+                left.matches_with_blob(event, blob) || right.matches_with_blob(event, blob)
+            }
+            Query::Not(inner) => !inner.matches_with_blob(event, blob),
+            Query::Text(needle) => blob
+                .get_or_insert_with(|| event.search_blob())
+                .contains(needle.as_str()),
+            Query::Field(field, matcher) => field_values(event, *field)
+                .into_iter()
+                .any(|value| matcher.matches(value)),
+            Query::Type(category) => EventCategory::of(&event.kind) == *category,
+            Query::Pattern(name) => {
+                matches!(&event.kind, EventKind::Custom(custom) if custom.name.eq_ignore_ascii_case(name))
+            }
+            Query::After(bound) => bound.compare(event.timestamp).is_ge(),
+            Query::Before(bound) => bound.compare(event.timestamp).is_lt(),
+        }
+    }
+}
+
+impl Matcher {
+    fn new(value: &str, contains: bool) -> Self {
+        let lowered = value.to_lowercase();
+        if contains {
+            Matcher::Contains(lowered)
+        } else if value.contains(['*', '?']) {
+            let pattern: String = value
+                .chars()
+                .map(|ch| match ch {
+                    '*' => ".*".to_string(),
+                    '?' => ".".to_string(),
+                    other => regex::escape(&other.to_string()),
+                })
+                .collect();
+            let regex = Regex::new(&format!("(?i)^{}$", pattern))
+                .expect("escaped wildcard patterns are valid regexes");
+            Matcher::Wildcard(regex)
+        } else {
+            Matcher::Exact(lowered)
+        }
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            Matcher::Exact(expected) => value.to_lowercase() == *expected,
+            Matcher::Contains(needle) => value.to_lowercase().contains(needle.as_str()),
+            Matcher::Wildcard(regex) => regex.is_match(value),
+        }
+    }
+}
+
+impl TimeBound {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        for format in ["%H:%M", "%H:%M:%S"] {
+            if let Ok(time) = NaiveTime::parse_from_str(value, format) {
+                return Some(TimeBound::TimeOfDay(time));
+            }
+        }
+        for format in ["%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S"] {
+            if let Ok(instant) = NaiveDateTime::parse_from_str(value, format) {
+                return Some(TimeBound::Instant(instant.and_utc()));
+            }
+        }
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .map(|date| TimeBound::Instant(date.and_time(NaiveTime::MIN).and_utc()))
+    }
+
+    /// How `timestamp` relates to the bound.
+    fn compare(&self, timestamp: DateTime<Utc>) -> std::cmp::Ordering {
+        match self {
+            TimeBound::TimeOfDay(time) => timestamp.time().cmp(time),
+            TimeBound::Instant(instant) => timestamp.cmp(instant),
+        }
+    }
+}
+
+/// The typed values a field refers to for this kind of event. Events without the field give
+/// an empty list and never match. Custom events use their participants for `player:` and the
+/// capture with the field's name for the other fields.
+fn field_values(event: &PlayerEvent, field: Field) -> Vec<&str> {
+    match (&event.kind, field) {
+        (EventKind::Custom(custom), Field::Player) => {
+            custom.participants.iter().map(String::as_str).collect()
+        }
+        (EventKind::Custom(custom), field) => custom
+            .fields
+            .get(field.key())
+            .map(String::as_str)
+            .into_iter()
+            .collect(),
+        (EventKind::Kill(kill), Field::Killer) => vec![&kill.killer_name],
+        (EventKind::Kill(kill), Field::Victim) => vec![&kill.victim_name],
+        (EventKind::Kill(kill), Field::Player) => vec![&kill.killer_name, &kill.victim_name],
+        (EventKind::Kill(kill), Field::Weapon) => vec![&kill.weapon, &kill.weapon_class],
+        (EventKind::Kill(kill), Field::Damage) => vec![&kill.damage_type],
+        (EventKind::Kill(kill), Field::Zone) => vec![&kill.zone],
+        (EventKind::SpawnReservation(spawn), Field::Player) => vec![&spawn.player_name],
+        (EventKind::SpawnReservation(spawn), Field::Zone) => vec![&spawn.location],
+        (EventKind::CorpseStatus(corpse), Field::Player) => vec![&corpse.player_name],
+        (EventKind::ZoneTransfer(zone), Field::Player) => vec![&zone.player_name],
+        (EventKind::ZoneTransfer(zone), Field::Zone) => zone
+            .parent_name
+            .iter()
+            .chain(zone.host_name.iter())
+            .map(String::as_str)
+            .collect(),
+        (EventKind::StatusEffect(status), Field::Player) => vec![&status.player_name],
+        (EventKind::StatusEffect(status), Field::Effect) => vec![&status.effect],
+        (EventKind::Hit(hit), Field::Killer) => vec![&hit.attacker],
+        (EventKind::Hit(hit), Field::Victim) => vec![&hit.target],
+        (EventKind::Hit(hit), Field::Player) => vec![&hit.attacker, &hit.target],
+        (EventKind::VehicleDestruction(vehicle), Field::Killer) => vec![&vehicle.attacker_name],
+        (EventKind::VehicleDestruction(vehicle), Field::Victim) => vec![&vehicle.driver_name],
+        (EventKind::VehicleDestruction(vehicle), Field::Player) => {
+            vec![&vehicle.attacker_name, &vehicle.driver_name]
+        }
+        (EventKind::VehicleDestruction(vehicle), Field::Damage) => vec![&vehicle.cause],
+        (EventKind::VehicleDestruction(vehicle), Field::Zone) => vec![&vehicle.zone],
+        (EventKind::VehicleDestruction(vehicle), Field::Vehicle) => vec![&vehicle.vehicle_name],
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    /// A bare word or quoted phrase.
+    Text(String),
+    Term {
+        field: String,
+        contains: bool,
+        value: String,
+    },
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, QueryError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        let column = index + 1;
+        if ch.is_whitespace() {
+            index += 1;
+            continue;
+        }
+        match ch {
+            '(' => {
+                tokens.push((Token::LParen, column));
+                index += 1;
+            }
+            ')' => {
+                tokens.push((Token::RParen, column));
+                index += 1;
+            }
+            '-' if chars
+                .get(index + 1)
+                .is_some_and(|next| !next.is_whitespace()) =>
+            {
+                tokens.push((Token::Not, column));
+                index += 1;
+            }
+            '"' => {
+                let (phrase, next) = read_quoted(&chars, index)?;
+                tokens.push((Token::Text(phrase), column));
+                index = next;
+            }
+            _ => {
+                let start = index;
+                while index < chars.len()
+                    && !chars[index].is_whitespace()
+                    && !matches!(chars[index], '(' | ')' | '"')
+                {
+                    index += 1;
+                }
+                let word: String = chars[start..index].iter().collect();
+                let token = match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => match word.split_once(':') {
+                        Some((field, rest)) => {
+                            let field = field.to_ascii_lowercase();
+                            if !FIELDS.contains(&field.as_str()) {
+                                return Err(QueryError {
+                                    message: format!(
+                                        "Unknown field '{}' (expected one of {}; quote text that contains ':')",
+                                        field,
+                                        FIELDS.join(", ")
+                                    ),
+                                    column,
+                                });
+                            }
+                            let (contains, rest) = match rest.strip_prefix('~') {
+                                Some(rest) => (true, rest),
+                                None => (false, rest),
+                            };
+                            let value = if rest.is_empty() && chars.get(index) == Some(&'"') {
+                                let (value, next) = read_quoted(&chars, index)?;
+                                index = next;
+                                value
+                            } else {
+                                rest.to_string()
+                            };
+                            if value.is_empty() {
+                                return Err(QueryError {
+                                    message: format!("Missing value after '{}:'", field),
+                                    column,
+                                });
+                            }
+                            Token::Term {
+                                field,
+                                contains,
+                                value,
+                            }
+                        }
+                        None => Token::Text(word),
+                    },
+                };
+                tokens.push((token, column));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Reads a `"…"` phrase starting at the opening quote. Returns the phrase and the index after
+/// the closing quote.
+fn read_quoted(chars: &[char], open: usize) -> Result<(String, usize), QueryError> {
+    let start = open + 1;
+    match chars[start..].iter().position(|ch| *ch == '"') {
+        Some(length) => Ok((
+            chars[start..start + length].iter().collect(),
+            start + length + 1,
+        )),
+        None => Err(QueryError {
+            message: "Missing closing quote".to_string(),
+            column: open + 1,
+        }),
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    input_len: usize,
+    patterns: &'a PatternSet,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map_or(self.input_len + 1, |(_, column)| *column)
+    }
+
+    fn parse_or(&mut self) -> Result<Query, QueryError> {
+        let mut query = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            let right = self.parse_and()?;
+            query = Query::Or(Box::new(query), Box::new(right));
+        }
+        Ok(query)
+    }
+
+    fn parse_and(&mut self) -> Result<Query, QueryError> {
+        let mut query = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => self.position += 1,
+                Some(Token::Or | Token::RParen) | None => return Ok(query),
+                Some(_) => {}
+            }
+            let right = self.parse_unary()?;
+            query = Query::And(Box::new(query), Box::new(right));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Query, QueryError> {
+        if self.peek() == Some(&Token::Not) {
+            self.position += 1;
+            let inner = self.parse_unary()?;
+            return Ok(Query::Not(Box::new(inner)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Query, QueryError> {
+        let column = self.column();
+        let Some((token, _)) = self.tokens.get(self.position) else {
+            return Err(QueryError {
+                message: "Expected a search term".to_string(),
+                column,
+            });
+        };
+        match token {
+            Token::LParen => {
+                self.position += 1;
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err(QueryError {
+                        message: format!("Missing ')' to close the '(' at column {}", column),
+                        column: self.column(),
+                    });
+                }
+                self.position += 1;
+                Ok(inner)
+            }
+            Token::Text(text) => {
+                let query = Query::Text(text.to_lowercase());
+                self.position += 1;
+                Ok(query)
+            }
+            Token::Term {
+                field,
+                contains,
+                value,
+            } => {
+                let query = term_query(field, *contains, value, column, self.patterns)?;
+                self.position += 1;
+                Ok(query)
+            }
+            Token::RParen | Token::And | Token::Or | Token::Not => Err(QueryError {
+                message: "Expected a search term".to_string(),
+                column,
+            }),
+        }
+    }
+}
+
+fn term_query(
+    field: &str,
+    contains: bool,
+    value: &str,
+    column: usize,
+    patterns: &PatternSet,
+) -> Result<Query, QueryError> {
+    let error = |message: String| QueryError { message, column };
+    let field = match field {
+        "killer" => Field::Killer,
+        "victim" => Field::Victim,
+        "player" => Field::Player,
+        "weapon" => Field::Weapon,
+        "damage" => Field::Damage,
+        "zone" => Field::Zone,
+        "vehicle" => Field::Vehicle,
+        "effect" => Field::Effect,
+        "type" => {
+            return match value.parse() {
+                Ok(category) => Ok(Query::Type(category)),
+                Err(err) => match patterns.get(value) {
+                    Some(pattern) => Ok(Query::Pattern(pattern.name.clone())),
+                    None => Err(error(err)),
+                },
+            };
+        }
+        "after" | "before" => {
+            let bound = TimeBound::parse(value).ok_or_else(|| {
+                error(format!(
+                    "Invalid time '{}' (use 12:30, 2024-09-14 or 2024-09-14T12:30)",
+                    value
+                ))
+            })?;
+            return Ok(if field == "after" {
+                Query::After(bound)
+            } else {
+                Query::Before(bound)
+            });
+        }
+        other => return Err(error(format!("Unknown field '{}'", other))),
+    };
+    Ok(Query::Field(field, Matcher::new(value, contains)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_line;
+
+    const KILL: &str = "<2025-01-18T21:03:44.019Z> [Notice] <Actor Death> CActor::Kill: 'Player_D' [200000000004] in zone 'OOC_Pyro_1' killed by 'Player_A' [200000000001] using 'BEHR_LaserCannon_S2_3000000011' [Class BEHR_LaserCannon_S2] with damage type 'VehicleDestruction' from direction x: 0.000000, y: 0.000000, z: 0.000000 [Team_ActorTech][Actor]";
+
+    fn parse(input: &str) -> Result<Option<Query>, QueryError> {
+        Query::parse(input, &PatternSet::default())
+    }
+
+    /// The query as a fully parenthesized string.
+    fn show(query: &Query) -> String {
+        match query {
+            Query::And(left, right) => format!("({} AND {})", show(left), show(right)),
+            Query::Or(left, right) => format!("({} OR {})", show(left), show(right)),
+            Query::Not(inner) => format!("NOT {}", show(inner)),
+            Query::Text(text) => format!("{:?}", text),
+            Query::Field(field, Matcher::Exact(value)) => format!("{}={}", field.key(), value),
+            Query::Field(field, Matcher::Contains(value)) => format!("{}~{}", field.key(), value),
+            Query::Field(field, Matcher::Wildcard(regex)) => {
+                format!("{}/{}/", field.key(), regex.as_str())
+            }
+            Query::Type(category) => format!("type={}", category),
+            Query::Pattern(name) => format!("pattern={}", name),
+            Query::After(bound) => format!("after {}", show_bound(bound)),
+            Query::Before(bound) => format!("before {}", show_bound(bound)),
+        }
+    }
+
+    fn show_bound(bound: &TimeBound) -> String {
+        match bound {
+            TimeBound::TimeOfDay(time) => time.to_string(),
+            TimeBound::Instant(instant) => instant.to_rfc3339(),
+        }
+    }
+
+    fn parsed(input: &str) -> String {
+        let query = parse(input)
+            .unwrap_or_else(|err| panic!("{:?} did not parse: {}", input, err))
+            .unwrap_or_else(|| panic!("{:?} parsed to nothing", input));
+        show(&query)
+    }
+
+    fn error(input: &str) -> QueryError {
+        match parse(input) {
+            Ok(query) => panic!("{:?} parsed to {:?}", input, query.as_ref().map(show)),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn blank_queries_parse_to_nothing() {
+        assert!(parse("").unwrap().is_none());
+        assert!(parse("   ").unwrap().is_none());
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(parsed("a b OR c"), r#"(("a" AND "b") OR "c")"#);
+        assert_eq!(parsed("a OR b c"), r#"("a" OR ("b" AND "c"))"#);
+        assert_eq!(parsed("a AND b OR c"), r#"(("a" AND "b") OR "c")"#);
+        assert_eq!(parsed("a OR b OR c"), r#"(("a" OR "b") OR "c")"#);
+    }
+
+    #[test]
+    fn not_binds_tighter_than_and() {
+        assert_eq!(parsed("NOT a b"), r#"(NOT "a" AND "b")"#);
+        assert_eq!(parsed("NOT NOT a"), r#"NOT NOT "a""#);
+        assert_eq!(parsed("a OR NOT b"), r#"("a" OR NOT "b")"#);
+    }
+
+    #[test]
+    fn parentheses_group() {
+        assert_eq!(parsed("a (b OR c)"), r#"("a" AND ("b" OR "c"))"#);
+        assert_eq!(parsed("NOT (a OR b)"), r#"NOT ("a" OR "b")"#);
+        assert_eq!(parsed("((a))"), r#""a""#);
+    }
+
+    #[test]
+    fn leading_dash_negates() {
+        assert_eq!(parsed("-a"), r#"NOT "a""#);
+        assert_eq!(parsed("a -killer:Foo"), r#"("a" AND NOT killer=foo)"#);
+        assert_eq!(parsed("-(a OR b)"), r#"NOT ("a" OR "b")"#);
+        assert_eq!(parsed("a - b"), r#"(("a" AND "-") AND "b")"#);
+    }
+
+    #[test]
+    fn words_and_phrases_are_lowercased_text() {
+        assert_eq!(parsed("Foo"), r#""foo""#);
+        assert_eq!(parsed(r#""Killed By" x"#), r#"("killed by" AND "x")"#);
+        assert_eq!(parsed("and or not"), r#"(("and" AND "or") AND "not")"#);
+    }
+
+    #[test]
+    fn fields_parse_with_quoted_values() {
+        assert_eq!(parsed("killer:Foo"), "killer=foo");
+        assert_eq!(parsed("VICTIM:Foo"), "victim=foo");
+        assert_eq!(parsed(r#"zone:"Stanton 1""#), "zone=stanton 1");
+        assert_eq!(parsed(r#"zone:~"Pyro 1""#), "zone~pyro 1");
+        assert_eq!(parsed(r#"zone:"a b" c"#), r#"(zone=a b AND "c")"#);
+    }
+
+    #[test]
+    fn tilde_means_contains() {
+        assert_eq!(parsed("weapon:~Behr"), "weapon~behr");
+        let Some(Query::Field(_, matcher)) = parse("weapon:~laser").unwrap() else {
+            panic!("expected a field query");
+        };
+        assert!(matcher.matches("BEHR_LaserCannon_S2"));
+        assert!(!matcher.matches("behr_rifle"));
+    }
+
+    #[test]
+    fn stars_and_question_marks_are_wildcards() {
+        assert_eq!(parsed("weapon:behr*"), "weapon/(?i)^behr.*$/");
+        assert_eq!(parsed("weapon:a?c"), "weapon/(?i)^a.c$/");
+        assert_eq!(parsed("weapon:a.b*"), r"weapon/(?i)^a\.b.*$/");
+
+        let star = Matcher::new("behr*s2", false);
+        assert!(star.matches("BEHR_LaserCannon_S2"));
+        assert!(star.matches("behrs2"));
+        assert!(!star.matches("BEHR_LaserCannon_S2_3000000011"));
+        let question = Matcher::new("s?", false);
+        assert!(question.matches("S2"));
+        assert!(!question.matches("S"));
+        assert!(!question.matches("S22"));
+    }
+
+    #[test]
+    fn exact_matches_ignore_case_only() {
+        let exact = Matcher::new("Player_A", false);
+        assert!(exact.matches("player_a"));
+        assert!(!exact.matches("Player_AB"));
+    }
+
+    #[test]
+    fn type_accepts_every_category_and_its_plural() {
+        for category in EventCategory::ALL {
+            let expected = format!("type={}", category);
+            assert_eq!(parsed(&format!("type:{}", category.key())), expected);
+        }
+        assert_eq!(parsed("type:status"), "type=status");
+        assert_eq!(parsed("type:statuses"), "type=status");
+        assert_eq!(parsed("type:kills"), "type=kill");
+        let err = error("x type:weapon");
+        assert_eq!(err.column, 3);
+        assert!(err.message.starts_with("Unknown event category 'weapon'"));
+    }
+
+    #[test]
+    fn type_accepts_the_given_pattern_names() {
+        let patterns =
+            PatternSet::parse("[[pattern]]\nname = \"Control\"\nregex = 'control token'\n")
+                .expect("valid patterns");
+        let query = Query::parse("type:control", &patterns).unwrap().unwrap();
+        assert_eq!(show(&query), "pattern=Control");
+        assert!(parse("type:control").is_err());
+    }
+
+    #[test]
+    fn after_and_before_accept_each_time_format() {
+        assert_eq!(parsed("after:12:30"), "after 12:30:00");
+        assert_eq!(parsed("after:12:30:15"), "after 12:30:15");
+        assert_eq!(
+            parsed("before:2024-09-14"),
+            "before 2024-09-14T00:00:00+00:00"
+        );
+        assert_eq!(
+            parsed("before:2024-09-14T12:30"),
+            "before 2024-09-14T12:30:00+00:00"
+        );
+        assert_eq!(
+            parsed("after:2024-09-14T12:30:15"),
+            "after 2024-09-14T12:30:15+00:00"
+        );
+        for input in [
+            "after:noon",
+            "before:2024-13-01",
+            "after:25:00",
+            "before:12",
+        ] {
+            let err = error(input);
+            assert_eq!(err.column, 1, "{}", input);
+            assert!(err.message.starts_with("Invalid time"), "{}", input);
+        }
+    }
+
+    #[test]
+    fn time_bounds_compare_against_the_event_time() {
+        let event = parse_line(KILL).expect("kill line parses");
+        let matches = |input: &str| parse(input).unwrap().unwrap().matches(&event);
+        assert!(matches("after:21:03"));
+        assert!(matches("after:21:03:44"));
+        assert!(!matches("after:21:04"));
+        assert!(matches("before:21:04"));
+        assert!(!matches("before:21:03:44"));
+        assert!(matches("after:2025-01-18 before:2025-01-19"));
+        assert!(!matches("before:2025-01-18T21:03"));
+    }
+
+    #[test]
+    fn queries_match_kill_fields() {
+        let event = parse_line(KILL).expect("kill line parses");
+        let matches = |input: &str| parse(input).unwrap().unwrap().matches(&event);
+        assert!(matches("killer:player_a victim:Player_D"));
+        assert!(matches("player:Player_D"));
+        assert!(!matches("killer:Player_D"));
+        assert!(matches("weapon:behr_lasercannon_s2_3000000011"));
+        assert!(matches("zone:~pyro type:kill"));
+        assert!(matches("killer:nobody OR damage:VehicleDestruction"));
+        assert!(matches("-type:hit"));
+        assert!(!matches("NOT (type:kill)"));
+        assert!(matches("lasercannon"));
+        assert!(!matches("effect:*"));
+    }
+
+    #[test]
+    fn unbalanced_parentheses_report_their_column() {
+        let err = error("(a OR b");
+        assert_eq!(err.column, 8);
+        assert_eq!(err.message, "Missing ')' to close the '(' at column 1");
+
+        let err = error("a (b (c)");
+        assert_eq!(err.column, 9);
+        assert_eq!(err.message, "Missing ')' to close the '(' at column 3");
+
+        let err = error("a b)");
+        assert_eq!(err.column, 4);
+        assert_eq!(err.message, "Unbalanced ')'");
+
+        let err = error(")");
+        assert_eq!(err.column, 1);
+        assert_eq!(err.message, "Expected a search term");
+    }
+
+    #[test]
+    fn missing_closing_quotes_report_the_opening_quote() {
+        let err = error(r#"a "b c"#);
+        assert_eq!(err.column, 3);
+        assert_eq!(err.message, "Missing closing quote");
+
+        let err = error(r#"killer:"Foo"#);
+        assert_eq!(err.column, 8);
+        assert_eq!(err.message, "Missing closing quote");
+    }
+
+    #[test]
+    fn other_syntax_errors_report_their_column() {
+        let err = error("a OR");
+        assert_eq!(
+            (err.column, err.message.as_str()),
+            (5, "Expected a search term")
+        );
+
+        let err = error("a NOT");
+        assert_eq!(
+            (err.column, err.message.as_str()),
+            (6, "Expected a search term")
+        );
+
+        let err = error("a killer:");
+        assert_eq!(err.column, 3);
+        assert_eq!(err.message, "Missing value after 'killer:'");
+
+        let err = error("a ship:Foo");
+        assert_eq!(err.column, 3);
+        assert!(err.message.starts_with("Unknown field 'ship'"));
+    }
+}
@@ -0,0 +1,282 @@
+use crate::event::{
+    EventKind, KillEvent, PlayerEvent, describe_destroy_levels, format_status_stage,
+};
+use crate::stats::weapon_base_name;
+use chrono::{DateTime, Duration, Utc};
+
+/// How far back before a death the recap looks by default.
+pub const DEFAULT_RECAP_WINDOW_SECS: u32 = 30;
+
+/// A death of the player with the hits, status effects and vehicle damage involving them in
+/// the moments before it.
+#[derive(Clone, Debug)]
+pub struct DeathRecap {
+    pub death: PlayerEvent,
+    /// Oldest first. Never reaches back past the previous death.
+    pub lead_up: Vec<PlayerEvent>,
+}
+
+impl DeathRecap {
+    /// The recaps of every death of `player` in `events`, newest first. `events` may be in any
+    /// order.
+    pub fn collect<'a>(
+        events: impl IntoIterator<Item = &'a PlayerEvent>,
+        player: &str,
+        window: Duration,
+    ) -> Vec<DeathRecap> {
+        let player = player.trim();
+        if player.is_empty() {
+            return Vec::new();
+        }
+        let mut events: Vec<&PlayerEvent> = events.into_iter().collect();
+        events.sort_by_key(|event| event.timestamp);
+
+        let mut recaps = Vec::new();
+        let mut previous_death: Option<DateTime<Utc>> = None;
+        for (index, event) in events.iter().enumerate() {
+            let EventKind::Kill(kill) = &event.kind else {
+                continue;
+            };
+            if !kill.victim_name.eq_ignore_ascii_case(player) {
+                continue;
+            }
+            let start = event.timestamp - window;
+            let mut lead_up: Vec<PlayerEvent> = events[..index]
+                .iter()
+                .rev()
+                .take_while(|earlier| {
+                    earlier.timestamp >= start
+                        && previous_death.is_none_or(|death| earlier.timestamp > death)
+                })
+                .filter(|earlier| leads_to_death(earlier, player))
+                .map(|earlier| (*earlier).clone())
+                .collect();
+            lead_up.reverse();
+            recaps.push(DeathRecap {
+                death: (*event).clone(),
+                lead_up,
+            });
+            previous_death = Some(event.timestamp);
+        }
+        recaps.reverse();
+        recaps
+    }
+
+    pub fn kill(&self) -> &KillEvent {
+        match &self.death.kind {
+            EventKind::Kill(kill) => kill,
+            _ => unreachable!("recaps are only built from kill events"),
+        }
+    }
+
+    /// How the player died, e.g. `Killed by Foo with behr_rifle_ballistic_01 (Bullet)`.
+    pub fn headline(&self) -> String {
+        let kill = self.kill();
+        let killer = if kill.killer_name.eq_ignore_ascii_case(&kill.victim_name) {
+            "Suicide".to_string()
+        } else {
+            format!("Killed by {}", kill.killer_name)
+        };
+        let weapon = weapon_base_name(&kill.weapon);
+        let mut headline = if weapon.is_empty() || weapon.eq_ignore_ascii_case("unknown") {
+            killer
+        } else {
+            format!("{} with {}", killer, weapon)
+        };
+        if !kill.damage_type.is_empty() {
+            headline.push_str(&format!(" ({})", kill.damage_type));
+        }
+        headline
+    }
+
+    /// Players who hit the victim before the death, with the number of hits, most first.
+    pub fn attackers(&self) -> Vec<(String, usize)> {
+        let victim = &self.kill().victim_name;
+        let mut attackers: Vec<(String, usize)> = Vec::new();
+        for event in &self.lead_up {
+            let EventKind::Hit(hit) = &event.kind else {
+                continue;
+            };
+            if !is_target(hit.target.as_str(), hit.child.as_deref(), victim) {
+                continue;
+            }
+            match attackers
+                .iter_mut()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&hit.attacker))
+            {
+                Some((_, count)) => *count += 1,
+                None => attackers.push((hit.attacker.clone(), 1)),
+            }
+        }
+        attackers.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+        attackers
+    }
+
+    /// Counts of the lead-up, e.g. `3 hits · 1 status effect · 2 vehicle damage steps`.
+    pub fn overview(&self) -> String {
+        let mut hits = 0;
+        let mut effects = 0;
+        let mut vehicle = 0;
+        for event in &self.lead_up {
+            match &event.kind {
+                EventKind::Hit(_) => hits += 1,
+                EventKind::StatusEffect(_) => effects += 1,
+                EventKind::VehicleDestruction(_) => vehicle += 1,
+                _ => {}
+            }
+        }
+        if hits + effects + vehicle == 0 {
+            return "Nothing logged before the death".to_string();
+        }
+        let mut parts = Vec::new();
+        if hits > 0 {
+            parts.push(plural(hits, "hit", "hits"));
+        }
+        if effects > 0 {
+            parts.push(plural(effects, "status effect", "status effects"));
+        }
+        if vehicle > 0 {
+            parts.push(plural(
+                vehicle,
+                "vehicle damage step",
+                "vehicle damage steps",
+            ));
+        }
+        parts.join(" · ")
+    }
+
+    /// One line per lead-up event, with the seconds before the death, oldest first.
+    pub fn timeline(&self) -> Vec<String> {
+        self.lead_up
+            .iter()
+            .map(|event| {
+                let before = (self.death.timestamp - event.timestamp).num_milliseconds() as f64;
+                format!("-{:.1}s  {}", before / 1000.0, describe(event))
+            })
+            .collect()
+    }
+}
+
+/// Hits, status effects and vehicle damage that involve `player`.
+fn leads_to_death(event: &PlayerEvent, player: &str) -> bool {
+    let is_player = |name: &str| name.eq_ignore_ascii_case(player);
+    match &event.kind {
+        EventKind::Hit(hit) => {
+            is_player(&hit.attacker) || is_target(&hit.target, hit.child.as_deref(), player)
+        }
+        EventKind::StatusEffect(effect) => is_player(&effect.player_name),
+        EventKind::VehicleDestruction(vehicle) => {
+            is_player(&vehicle.driver_name) || is_player(&vehicle.attacker_name)
+        }
+        _ => false,
+    }
+}
+
+fn is_target(target: &str, child: Option<&str>, player: &str) -> bool {
+    target.trim().eq_ignore_ascii_case(player)
+        || child.is_some_and(|child| child.eq_ignore_ascii_case(player))
+}
+
+fn describe(event: &PlayerEvent) -> String {
+    match &event.kind {
+        EventKind::Hit(hit) => format!("{} hit {}", hit.attacker, hit.target.trim()),
+        EventKind::StatusEffect(effect) => format_status_stage(&effect.stage, &effect.effect),
+        EventKind::VehicleDestruction(vehicle) => format!(
+            "{} {} by {} ({})",
+            vehicle.vehicle_name,
+            describe_destroy_levels(vehicle.from_level, vehicle.to_level),
+            vehicle.attacker_name,
+            vehicle.cause
+        ),
+        _ => event.summary_line(),
+    }
+}
+
+fn plural(count: usize, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_line;
+
+    fn death_at(time: &str, killer: &str) -> PlayerEvent {
+        parse_line(&format!(
+            "<2024-09-14T{}.000Z> [Notice] <Actor Death> CActor::Kill: 'Me' [1] in zone 'Z' killed by '{}' [2] using 'rifle_1234' [Class W] with damage type 'Bullet' from direction x: 0, y: 0, z: 0 [Team_ActorTech][Actor]",
+            time, killer
+        ))
+        .expect("kill line")
+    }
+
+    fn hit_at(time: &str, attacker: &str, target: &str) -> PlayerEvent {
+        parse_line(&format!(
+            "<2024-09-14T{}.000Z> [Notice] <Debug Hostility Events> [OnHandleHit] Fake hit FROM {} TO {}. Being sent to child {} [Team_CoreGameplayFeatures][Hostility]",
+            time, attacker, target, target
+        ))
+        .expect("hit line")
+    }
+
+    fn effect_at(time: &str, nickname: &str) -> PlayerEvent {
+        parse_line(&format!(
+            "<2024-09-14T{}.000Z> Logged a start of a status effect! nickname: {}, status effect: Bleeding",
+            time, nickname
+        ))
+        .expect("status effect line")
+    }
+
+    fn recaps(events: &[PlayerEvent]) -> Vec<DeathRecap> {
+        DeathRecap::collect(events, "Me", Duration::seconds(30))
+    }
+
+    #[test]
+    fn lead_ups_stay_inside_the_window_and_after_the_previous_death() {
+        let recaps = recaps(&[
+            hit_at("18:00:00", "Foe", "Me"),
+            death_at("18:00:40", "Foe"),
+            hit_at("18:00:15", "Foe", "Me"),
+            effect_at("18:00:30", "Me"),
+            hit_at("18:00:35", "Foe", "Someone"),
+            hit_at("18:00:41", "Foe", "Me"),
+            death_at("18:00:50", "Foe"),
+        ]);
+
+        assert_eq!(recaps.len(), 2);
+        assert_eq!(recaps[0].lead_up.len(), 1);
+        assert_eq!(recaps[0].timeline(), ["-9.0s  Foe hit Me"]);
+        assert_eq!(recaps[1].overview(), "1 hit · 1 status effect");
+        assert_eq!(
+            recaps[1].timeline(),
+            ["-25.0s  Foe hit Me", "-10.0s  started Bleeding"]
+        );
+    }
+
+    #[test]
+    fn attackers_are_counted_by_hits_on_the_victim() {
+        let recaps = recaps(&[
+            hit_at("18:00:01", "Foe", "Me"),
+            hit_at("18:00:02", "Other", "Me"),
+            hit_at("18:00:03", "foe", "Me"),
+            hit_at("18:00:04", "Me", "Foe"),
+            death_at("18:00:05", "Foe"),
+        ]);
+        let recap = &recaps[0];
+        assert_eq!(
+            recap.attackers(),
+            [("Foe".to_string(), 2), ("Other".to_string(), 1)]
+        );
+        assert_eq!(recap.overview(), "4 hits");
+        assert_eq!(recap.headline(), "Killed by Foe with rifle (Bullet)");
+    }
+
+    #[test]
+    fn quiet_deaths_and_suicides_read_plainly() {
+        let recaps = recaps(&[death_at("18:00:00", "me")]);
+        assert_eq!(recaps[0].overview(), "Nothing logged before the death");
+        assert_eq!(recaps[0].headline(), "Suicide with rifle (Bullet)");
+        assert!(
+            DeathRecap::collect(&[death_at("18:00:00", "Foe")], " ", Duration::seconds(30))
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use eframe::egui::{self, Color32, RichText};
+use sc_log_analyzer::DeathRecap;
+
+/// Longest look-back the window slider offers, in seconds.
+const MAX_WINDOW_SECS: u32 = 300;
+
+/// One "what killed me" card per death of the player, newest first.
+pub fn show(ui: &mut egui::Ui, recaps: &[DeathRecap], player: Option<&str>, window_secs: &mut u32) {
+    let Some(player) = player else {
+        ui.label(
+            RichText::new("No player nickname found in this log, so deaths cannot be recapped.")
+                .color(Color32::from_rgb(200, 200, 200)),
+        );
+        return;
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new(format!("Deaths of {}", player))
+                .strong()
+                .color(Color32::from_rgb(220, 220, 220)),
+        );
+        ui.separator();
+        ui.label("Look back");
+        ui.add(
+            egui::DragValue::new(window_secs)
+                .clamp_range(1..=MAX_WINDOW_SECS)
+                .suffix(" s"),
+        )
+        .on_hover_text("Hits, status effects and vehicle damage this long before each death");
+    });
+    ui.add_space(6.0);
+
+    if recaps.is_empty() {
+        ui.label(RichText::new("No deaths").color(Color32::from_rgb(140, 140, 140)));
+        return;
+    }
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for recap in recaps {
+            card(ui, recap);
+            ui.add_space(6.0);
+        }
+    });
+}
+
+fn card(ui: &mut egui::Ui, recap: &DeathRecap) {
+    let kill = recap.kill();
+    egui::Frame::none()
+        .fill(Color32::from_rgb(28, 32, 40))
+        .rounding(egui::Rounding::same(6.0))
+        .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal_wrapped(|ui| {
+                ui.label(
+                    RichText::new(
+                        recap
+                            .death
+                            .timestamp
+                            .format("%Y-%m-%d %H:%M:%S")
+                            .to_string(),
+                    )
+                    .color(Color32::from_rgb(160, 160, 160)),
+                );
+                ui.label(
+                    RichText::new(recap.headline())
+                        .strong()
+                        .color(Color32::from_rgb(130, 185, 245)),
+                );
+                if !kill.zone.is_empty() {
+                    ui.label(
+                        RichText::new(format!("in {}", kill.zone))
+                            .color(Color32::from_rgb(190, 190, 190)),
+                    );
+                }
+            });
+            ui.label(RichText::new(recap.overview()).color(Color32::from_rgb(190, 190, 190)));
+            let attackers = recap.attackers();
+            if !attackers.is_empty() {
+                let list: Vec<String> = attackers
+                    .iter()
+                    .map(|(name, hits)| format!("{} ({})", name, hits))
+                    .collect();
+                ui.label(
+                    RichText::new(format!("Hit by {}", list.join(", ")))
+                        .color(Color32::from_rgb(235, 130, 130)),
+                );
+            }
+            if recap.lead_up.is_empty() {
+                return;
+            }
+            egui::CollapsingHeader::new("What happened")
+                .id_source(("recap", recap.death.timestamp, &recap.death.raw))
+                .show(ui, |ui| {
+                    for line in recap.timeline() {
+                        ui.label(RichText::new(line).monospace());
+                    }
+                    ui.label(
+                        RichText::new(format!("-0.0s  {}", recap.headline()))
+                            .monospace()
+                            .color(Color32::from_rgb(130, 185, 245)),
+                    );
+                });
+        });
+}
@@ -0,0 +1,186 @@
+use crate::event::PlayerEvent;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Handles sorted into lists. Names compare case-insensitively, and a handle is either
+/// friendly or hostile, never both.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Roster {
+    /// Players whose routine events are hidden, like the single ignored player.
+    pub ignored: Vec<String>,
+    /// Squadmates and org members.
+    pub friendly: Vec<String>,
+    /// Players to watch out for.
+    pub hostile: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RosterList {
+    Ignored,
+    Friendly,
+    Hostile,
+}
+
+impl RosterList {
+    pub const ALL: [RosterList; 3] = [
+        RosterList::Friendly,
+        RosterList::Hostile,
+        RosterList::Ignored,
+    ];
+}
+
+impl fmt::Display for RosterList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RosterList::Ignored => "ignored",
+            RosterList::Friendly => "friendly",
+            RosterList::Hostile => "hostile",
+        })
+    }
+}
+
+/// Which events to keep based on who is involved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RosterScope {
+    #[default]
+    All,
+    /// Events involving at least one friendly player.
+    Friendly,
+    /// Events involving at least one hostile player.
+    Hostile,
+}
+
+impl Roster {
+    pub fn list(&self, list: RosterList) -> &[String] {
+        match list {
+            RosterList::Ignored => &self.ignored,
+            RosterList::Friendly => &self.friendly,
+            RosterList::Hostile => &self.hostile,
+        }
+    }
+
+    fn list_mut(&mut self, list: RosterList) -> &mut Vec<String> {
+        match list {
+            RosterList::Ignored => &mut self.ignored,
+            RosterList::Friendly => &mut self.friendly,
+            RosterList::Hostile => &mut self.hostile,
+        }
+    }
+
+    pub fn contains(&self, list: RosterList, name: &str) -> bool {
+        let name = name.trim();
+        self.list(list)
+            .iter()
+            .any(|entry| entry.eq_ignore_ascii_case(name))
+    }
+
+    /// Adds `name` to `list`. Adding to friendly removes it from hostile and the other way
+    /// round. Returns `false` for blank names and names already on the list.
+    pub fn add(&mut self, list: RosterList, name: &str) -> bool {
+        let name = name.trim();
+        if name.is_empty() || self.contains(list, name) {
+            return false;
+        }
+        match list {
+            RosterList::Friendly => self.remove(RosterList::Hostile, name),
+            RosterList::Hostile => self.remove(RosterList::Friendly, name),
+            RosterList::Ignored => false,
+        };
+        let entries = self.list_mut(list);
+        entries.push(name.to_string());
+        entries.sort_by_key(|entry| entry.to_lowercase());
+        true
+    }
+
+    pub fn remove(&mut self, list: RosterList, name: &str) -> bool {
+        let name = name.trim();
+        let entries = self.list_mut(list);
+        let before = entries.len();
+        entries.retain(|entry| !entry.eq_ignore_ascii_case(name));
+        entries.len() != before
+    }
+
+    /// The lists a player is on, friendly or hostile first.
+    pub fn lists_of(&self, name: &str) -> Vec<RosterList> {
+        RosterList::ALL
+            .into_iter()
+            .filter(|list| self.contains(*list, name))
+            .collect()
+    }
+
+    pub fn should_ignore(&self, event: &PlayerEvent) -> bool {
+        self.ignored.iter().any(|name| event.should_ignore(name))
+    }
+
+    pub fn in_scope(&self, event: &PlayerEvent, scope: RosterScope) -> bool {
+        let list = match scope {
+            RosterScope::All => return true,
+            RosterScope::Friendly => RosterList::Friendly,
+            RosterScope::Hostile => RosterList::Hostile,
+        };
+        event
+            .involved_players()
+            .iter()
+            .any(|name| self.contains(list, name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_line;
+
+    fn kill(victim: &str, killer: &str) -> PlayerEvent {
+        parse_line(&format!(
+            "<2024-09-14T18:00:00.000Z> [Notice] <Actor Death> CActor::Kill: '{}' [1] in zone 'Z' killed by '{}' [2] using 'W_1' [Class W] with damage type 'Bullet' from direction x: 0, y: 0, z: 0 [Team_ActorTech][Actor]",
+            victim, killer
+        ))
+        .expect("kill line")
+    }
+
+    fn roster() -> Roster {
+        let mut roster = Roster::default();
+        roster.add(RosterList::Ignored, "Spammer");
+        roster.add(RosterList::Friendly, "Buddy");
+        roster.add(RosterList::Hostile, "Pirate");
+        roster
+    }
+
+    #[test]
+    fn friendly_and_hostile_exclude_each_other() {
+        let mut roster = roster();
+        assert!(!roster.add(RosterList::Friendly, " buddy "));
+        assert!(!roster.add(RosterList::Hostile, "  "));
+        assert!(roster.add(RosterList::Friendly, "PIRATE"));
+        assert_eq!(roster.friendly, ["Buddy", "PIRATE"]);
+        assert!(roster.hostile.is_empty());
+        assert!(roster.add(RosterList::Ignored, "pirate"));
+        assert_eq!(
+            roster.lists_of("Pirate"),
+            [RosterList::Friendly, RosterList::Ignored]
+        );
+    }
+
+    #[test]
+    fn ignored_players_hide_only_their_own_routine_events() {
+        let roster = roster();
+        assert!(roster.should_ignore(&kill("Someone", "spammer")));
+        assert!(!roster.should_ignore(&kill("Spammer", "Someone")));
+        assert!(!roster.should_ignore(&kill("Someone", "Buddy")));
+    }
+
+    #[test]
+    fn scopes_keep_events_with_a_player_of_the_list() {
+        let roster = roster();
+        let buddy_kill = kill("Pirate", "Buddy");
+        let stranger_kill = kill("Someone", "Stranger");
+        assert!(roster.in_scope(&stranger_kill, RosterScope::All));
+        assert!(roster.in_scope(&buddy_kill, RosterScope::Friendly));
+        assert!(roster.in_scope(&buddy_kill, RosterScope::Hostile));
+        assert!(!roster.in_scope(&stranger_kill, RosterScope::Friendly));
+        assert!(!roster.in_scope(&kill("Buddy", "Someone"), RosterScope::Hostile));
+    }
+}
@@ -0,0 +1,134 @@
+use eframe::egui::{self, Color32, RichText};
+use sc_log_analyzer::{Roster, RosterList, RosterScope};
+
+/// The roster editor and the handles typed into its "add" fields, one per list.
+#[derive(Default)]
+pub struct RosterWindow {
+    pub open: bool,
+    new_friendly: String,
+    new_hostile: String,
+    new_ignored: String,
+}
+
+impl RosterWindow {
+    /// Shows the window. Returns `true` when the roster was edited.
+    pub fn show(&mut self, ctx: &egui::Context, roster: &mut Roster) -> bool {
+        if !self.open {
+            return false;
+        }
+        let mut changed = false;
+        let mut open = self.open;
+        egui::Window::new("Roster")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.columns(3, |columns| {
+                    for (column, list) in columns.iter_mut().zip(RosterList::ALL) {
+                        let input = match list {
+                            RosterList::Friendly => &mut self.new_friendly,
+                            RosterList::Hostile => &mut self.new_hostile,
+                            RosterList::Ignored => &mut self.new_ignored,
+                        };
+                        changed |= list_column(column, roster, list, input);
+                    }
+                });
+            });
+        self.open = open;
+        changed
+    }
+}
+
+fn list_column(
+    ui: &mut egui::Ui,
+    roster: &mut Roster,
+    list: RosterList,
+    input: &mut String,
+) -> bool {
+    let mut changed = false;
+    ui.label(
+        RichText::new(list_title(list))
+            .strong()
+            .color(list_color(list)),
+    );
+    ui.horizontal(|ui| {
+        let response = ui.add(
+            egui::TextEdit::singleline(input)
+                .hint_text("Handle")
+                .desired_width(120.0),
+        );
+        let submitted =
+            response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+        if (ui.button("Add").clicked() || submitted) && roster.add(list, input) {
+            input.clear();
+            changed = true;
+        }
+    });
+    let mut removed = None;
+    for name in roster.list(list) {
+        ui.horizontal(|ui| {
+            if ui.small_button("✕").on_hover_text("Remove").clicked() {
+                removed = Some(name.clone());
+            }
+            ui.label(RichText::new(name).color(list_color(list)));
+        });
+    }
+    if let Some(name) = removed {
+        changed |= roster.remove(list, &name);
+    }
+    if roster.list(list).is_empty() {
+        ui.label(RichText::new("Nobody yet").color(Color32::from_rgb(140, 140, 140)));
+    }
+    changed
+}
+
+fn list_title(list: RosterList) -> &'static str {
+    match list {
+        RosterList::Friendly => "Friendly",
+        RosterList::Hostile => "Hostile watch list",
+        RosterList::Ignored => "Ignored",
+    }
+}
+
+fn list_color(list: RosterList) -> Color32 {
+    match list {
+        RosterList::Friendly => Color32::from_rgb(130, 220, 150),
+        RosterList::Hostile => Color32::from_rgb(245, 120, 100),
+        RosterList::Ignored => Color32::from_rgb(150, 150, 150),
+    }
+}
+
+/// Color for a player name, taken from the first list the player is on.
+pub fn player_color(roster: &Roster, name: &str) -> Color32 {
+    roster
+        .lists_of(name)
+        .first()
+        .map(|list| list_color(*list))
+        .unwrap_or(Color32::from_rgb(140, 200, 255))
+}
+
+/// Right-click menu to move a player between lists. Returns `true` when the roster changed.
+pub fn player_context_menu(response: &egui::Response, roster: &mut Roster, name: &str) -> bool {
+    let mut changed = false;
+    response.context_menu(|ui| {
+        for list in RosterList::ALL {
+            if roster.contains(list, name) {
+                if ui.button(format!("Remove from {}", list)).clicked() {
+                    changed |= roster.remove(list, name);
+                    ui.close_menu();
+                }
+            } else if ui.button(format!("Add to {}", list)).clicked() {
+                changed |= roster.add(list, name);
+                ui.close_menu();
+            }
+        }
+    });
+    changed
+}
+
+pub fn scope_label(scope: RosterScope) -> &'static str {
+    match scope {
+        RosterScope::All => "Everyone",
+        RosterScope::Friendly => "With friendly players",
+        RosterScope::Hostile => "With hostile players",
+    }
+}
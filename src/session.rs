@@ -0,0 +1,366 @@
+use crate::event::PlayerEvent;
+use crate::parser::parse_timestamp;
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// A line that marks the client starting, logging in, joining a server or dropping its
+/// connection.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SessionMarker {
+    pub timestamp: DateTime<Utc>,
+    pub kind: SessionMarkerKind,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum SessionMarkerKind {
+    /// The first line of every `Game.log`, so it also separates merged backups.
+    LogStart,
+    Login {
+        handle: Option<String>,
+    },
+    ServerJoin {
+        shard: String,
+        address: Option<String>,
+    },
+    Disconnect {
+        reason: Option<String>,
+    },
+}
+
+/// A stretch of the log spent on one server shard.
+#[derive(Clone, Debug, Serialize)]
+pub struct GameSession {
+    pub start: DateTime<Utc>,
+    /// When the client disconnected, or the last event seen if it did not (yet).
+    /// `None` only for a session without any events that is still running.
+    pub end: Option<DateTime<Utc>>,
+    pub shard: Option<String>,
+    pub address: Option<String>,
+    pub handle: Option<String>,
+    /// Only set when a disconnect line ended the session, not a new login or shard.
+    pub disconnected: bool,
+    pub disconnect_reason: Option<String>,
+    pub event_count: usize,
+    /// When the following session starts, if there is one.
+    pub next_start: Option<DateTime<Utc>>,
+}
+
+impl GameSession {
+    /// The time span to scope filters to. A session without an end reaches into the future.
+    /// It stops just before the next session starts, so an event at the boundary only counts
+    /// for the next one, like `session_index_at` decides.
+    pub fn time_range(&self) -> RangeInclusive<DateTime<Utc>> {
+        let end = self.end.unwrap_or(DateTime::<Utc>::MAX_UTC);
+        let end = match self.next_start {
+            Some(next) if next <= end => next - Duration::nanoseconds(1),
+            _ => end,
+        };
+        self.start..=end
+    }
+
+    /// One line description for pickers and listings.
+    pub fn label(&self, index: usize) -> String {
+        let start = self.start.format("%Y-%m-%d %H:%M");
+        let end = self
+            .end
+            .map(|end| {
+                if end.date_naive() == self.start.date_naive() {
+                    end.format("%H:%M").to_string()
+                } else {
+                    end.format("%Y-%m-%d %H:%M").to_string()
+                }
+            })
+            .unwrap_or_else(|| "…".to_string());
+        let shard = self.shard.as_deref().unwrap_or("menu");
+        format!(
+            "#{} {} – {} · {} · {} events",
+            index + 1,
+            start,
+            end,
+            shard,
+            self.event_count
+        )
+    }
+}
+
+pub fn parse_session_marker(line: &str) -> Option<SessionMarker> {
+    if !(line.contains("Log started on")
+        || line.contains("<Join PU>")
+        || line.contains("User Login Success")
+        || line.contains("Disconnect"))
+    {
+        return None;
+    }
+
+    static LOG_START_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"^<(?P<timestamp>[^>]+)>\s*Log started on"#).unwrap());
+    static LOGIN_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?User Login Success - Handle\[(?P<handle>[^\]]*)\]"#)
+            .unwrap()
+    });
+    static JOIN_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?<Join PU>(?:.*?address\[(?P<address>[^\]]*)\])?(?:.*?port\[(?P<port>[^\]]*)\])?.*?shard\[(?P<shard>[^\]]+)\]"#)
+            .unwrap()
+    });
+    static DISCONNECT_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"^<(?P<timestamp>[^>]+)>.*?<(?:Channel Disconnected|Disconnect)>(?:.*?reason="(?P<reason>[^"]*)")?"#)
+            .unwrap()
+    });
+
+    if let Some(caps) = LOG_START_RE.captures(line) {
+        let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
+        return Some(SessionMarker {
+            timestamp,
+            kind: SessionMarkerKind::LogStart,
+        });
+    }
+    if let Some(caps) = JOIN_RE.captures(line) {
+        let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
+        let address = caps.name("address").map(|m| match caps.name("port") {
+            Some(port) => format!("{}:{}", m.as_str(), port.as_str()),
+            None => m.as_str().to_string(),
+        });
+        return Some(SessionMarker {
+            timestamp,
+            kind: SessionMarkerKind::ServerJoin {
+                shard: caps.name("shard")?.as_str().to_string(),
+                address,
+            },
+        });
+    }
+    if let Some(caps) = LOGIN_RE.captures(line) {
+        let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
+        let handle = caps
+            .name("handle")
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|handle| !handle.is_empty());
+        return Some(SessionMarker {
+            timestamp,
+            kind: SessionMarkerKind::Login { handle },
+        });
+    }
+    if let Some(caps) = DISCONNECT_RE.captures(line) {
+        let timestamp = parse_timestamp(caps.name("timestamp")?.as_str())?;
+        let reason = caps
+            .name("reason")
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|reason| !reason.is_empty());
+        return Some(SessionMarker {
+            timestamp,
+            kind: SessionMarkerKind::Disconnect { reason },
+        });
+    }
+    None
+}
+
+/// Splits the log into sessions. A new log, a login or a join to a different shard starts a new
+/// session, a disconnect ends the current one. A login right after the log started stays in
+/// that session. Events before the first marker get a session of their
+/// own, events after a disconnect stay with the session they follow.
+pub fn segment_sessions(markers: &[SessionMarker], events: &[PlayerEvent]) -> Vec<GameSession> {
+    let mut markers: Vec<&SessionMarker> = markers.iter().collect();
+    markers.sort_by_key(|marker| marker.timestamp);
+
+    let mut sessions: Vec<GameSession> = Vec::new();
+    let mut open = false;
+    for marker in markers {
+        match &marker.kind {
+            SessionMarkerKind::LogStart => {
+                // The previous log ends with its last event, not when the next one starts, so
+                // the open session is left as it is.
+                sessions.push(new_session(marker.timestamp, None, None, None));
+                open = true;
+            }
+            SessionMarkerKind::Login { handle } => {
+                let current = sessions.last_mut().filter(|_| open);
+                match current {
+                    Some(session) if session.shard.is_none() && session.handle.is_none() => {
+                        session.handle = handle.clone();
+                    }
+                    _ => {
+                        close_open(&mut sessions, &mut open, marker.timestamp);
+                        sessions.push(new_session(marker.timestamp, None, None, handle.clone()));
+                        open = true;
+                    }
+                }
+            }
+            SessionMarkerKind::ServerJoin { shard, address } => {
+                let current = sessions.last_mut().filter(|_| open);
+                match current {
+                    Some(session) if session.shard.is_none() => {
+                        session.shard = Some(shard.clone());
+                        session.address = address.clone();
+                    }
+                    Some(session) if session.shard.as_deref() == Some(shard.as_str()) => {}
+                    _ => {
+                        let handle = sessions.last().and_then(|s| s.handle.clone());
+                        close_open(&mut sessions, &mut open, marker.timestamp);
+                        sessions.push(new_session(
+                            marker.timestamp,
+                            Some(shard.clone()),
+                            address.clone(),
+                            handle,
+                        ));
+                        open = true;
+                    }
+                }
+            }
+            SessionMarkerKind::Disconnect { reason } => {
+                if open && let Some(session) = sessions.last_mut() {
+                    session.disconnected = true;
+                    session.disconnect_reason = reason.clone();
+                }
+                close_open(&mut sessions, &mut open, marker.timestamp);
+            }
+        }
+    }
+
+    let earliest = events.iter().map(|event| event.timestamp).min();
+    if let Some(earliest) = earliest
+        && sessions.first().is_none_or(|first| earliest < first.start)
+    {
+        sessions.insert(0, new_session(earliest, None, None, None));
+    }
+
+    let mut last_seen: Vec<Option<DateTime<Utc>>> = vec![None; sessions.len()];
+    for event in events {
+        if let Some(index) = session_index_at(&sessions, event.timestamp) {
+            sessions[index].event_count += 1;
+            let seen = &mut last_seen[index];
+            *seen = Some(seen.map_or(event.timestamp, |seen| seen.max(event.timestamp)));
+        }
+    }
+    for (session, seen) in sessions.iter_mut().zip(last_seen) {
+        session.end = match (session.end, seen) {
+            (Some(end), Some(seen)) => Some(end.max(seen)),
+            (end, seen) => end.or(seen),
+        };
+    }
+    let starts: Vec<DateTime<Utc>> = sessions.iter().map(|session| session.start).collect();
+    for (session, next) in sessions.iter_mut().zip(starts.into_iter().skip(1)) {
+        session.next_start = Some(next);
+    }
+    sessions
+}
+
+/// Index of the session an event at `timestamp` belongs to: the last one that started before it.
+pub fn session_index_at(sessions: &[GameSession], timestamp: DateTime<Utc>) -> Option<usize> {
+    let after = sessions.partition_point(|session| session.start <= timestamp);
+    after.checked_sub(1)
+}
+
+fn new_session(
+    start: DateTime<Utc>,
+    shard: Option<String>,
+    address: Option<String>,
+    handle: Option<String>,
+) -> GameSession {
+    GameSession {
+        start,
+        end: None,
+        shard,
+        address,
+        handle,
+        disconnected: false,
+        disconnect_reason: None,
+        event_count: 0,
+        next_start: None,
+    }
+}
+
+fn close_open(sessions: &mut [GameSession], open: &mut bool, at: DateTime<Utc>) {
+    if !*open {
+        return;
+    }
+    if let Some(session) = sessions.last_mut() {
+        session.end = Some(at);
+    }
+    *open = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_line;
+
+    fn markers(lines: &[&str]) -> Vec<SessionMarker> {
+        lines
+            .iter()
+            .map(|line| parse_session_marker(line).expect("marker line"))
+            .collect()
+    }
+
+    fn kill_at(time: &str) -> PlayerEvent {
+        parse_line(&format!(
+            "<2024-09-14T{}.000Z> [Notice] <Actor Death> CActor::Kill: 'A' [1] in zone 'Z' killed by 'B' [2] using 'W_1' [Class W] with damage type 'Bullet' from direction x: 0, y: 0, z: 0 [Team_ActorTech][Actor]",
+            time
+        ))
+        .expect("kill line")
+    }
+
+    #[test]
+    fn only_disconnect_lines_mark_a_disconnect() {
+        let markers = markers(&[
+            "<2024-09-14T18:00:00.000Z> Log started on Sat Sep 14 20:00:00 2024",
+            "<2024-09-14T18:01:00.000Z> [Notice] <Join PU> shard[pub_euw1b_1]",
+            "<2024-09-14T18:10:00.000Z> [Notice] <Join PU> shard[pub_euw1b_2]",
+            "<2024-09-14T18:20:00.000Z> [Notice] User Login Success - Handle[Someone]",
+            "<2024-09-14T18:25:00.000Z> [Notice] <Join PU> shard[pub_euw1b_3]",
+            "<2024-09-14T18:30:00.000Z> [Notice] <Disconnect> reason=\"Timeout\"",
+        ]);
+        let sessions = segment_sessions(&markers, &[]);
+        let disconnected: Vec<bool> = sessions
+            .iter()
+            .map(|session| session.disconnected)
+            .collect();
+        assert_eq!(disconnected, [false, false, true]);
+        assert_eq!(sessions[2].disconnect_reason.as_deref(), Some("Timeout"));
+        assert!(
+            sessions[..2]
+                .iter()
+                .all(|session| session.disconnect_reason.is_none())
+        );
+    }
+
+    #[test]
+    fn boundary_events_belong_to_the_next_session_only() {
+        let markers = markers(&[
+            "<2024-09-14T18:00:00.000Z> Log started on Sat Sep 14 20:00:00 2024",
+            "<2024-09-14T18:01:00.000Z> [Notice] <Join PU> shard[pub_euw1b_1]",
+            "<2024-09-14T18:10:00.000Z> [Notice] <Join PU> shard[pub_euw1b_2]",
+        ]);
+        let events = [
+            kill_at("18:05:00"),
+            kill_at("18:10:00"),
+            kill_at("18:15:00"),
+        ];
+        let sessions = segment_sessions(&markers, &events);
+        assert_eq!(sessions.len(), 2);
+        let counts: Vec<usize> = sessions
+            .iter()
+            .map(|session| {
+                let range = session.time_range();
+                events
+                    .iter()
+                    .filter(|event| range.contains(&event.timestamp))
+                    .count()
+            })
+            .collect();
+        assert_eq!(counts, [1, 2]);
+        assert_eq!(
+            counts,
+            sessions
+                .iter()
+                .map(|session| session.event_count)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            sessions[0].time_range().end(),
+            &(events[1].timestamp - Duration::nanoseconds(1))
+        );
+        assert_eq!(sessions[1].time_range().end(), &events[2].timestamp);
+    }
+}
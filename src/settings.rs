@@ -1,60 +1,234 @@
+use crate::overlay_server::DEFAULT_OVERLAY_PORT;
 use directories::ProjectDirs;
+use sc_log_analyzer::{
+    AlertRule, DEFAULT_ENGAGEMENT_GAP_SECS, DEFAULT_RECAP_WINDOW_SECS, EventCategory, Roster,
+    RosterScope, WebhookConfig,
+};
+use serde::{Deserialize, Serialize};
 use std::{
     env, fs,
     io::{self, Write},
     path::{Path, PathBuf},
 };
 
-const LAST_PATH_FILE: &str = "last_log_path.txt";
-const IGNORED_PLAYER_FILE: &str = "ignored_player.txt";
+const SETTINGS_FILE: &str = "settings.json";
+const HISTORY_DATABASE_FILE: &str = "history.sqlite3";
+const PATTERNS_FILE: &str = "patterns.toml";
+
+/// Version of the settings document written by this build. Older documents are migrated in
+/// [`Settings::load`].
+const SETTINGS_VERSION: u32 = 1;
+
+// Files written by versions before the settings document existed.
+const LEGACY_LAST_PATH_FILE: &str = "last_log_path.txt";
+const LEGACY_IGNORED_PLAYER_FILE: &str = "ignored_player.txt";
+
+/// Everything the GUI remembers between runs, stored as one JSON document.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    /// The last log that was opened successfully.
+    pub last_log_path: Option<String>,
+    /// Set once the user edits the ignored player; until then it follows the nickname found
+    /// in the log.
+    pub ignored_player: Option<String>,
+    pub hidden_categories: Vec<EventCategory>,
+    pub search_text: String,
+    pub search_regex: bool,
+    pub roster: Roster,
+    pub roster_scope: RosterScope,
+    pub auto_refresh_secs: f32,
+    pub window: Option<WindowSize>,
+    pub history_enabled: bool,
+    pub library_dir: Option<String>,
+    pub alert_rules: Vec<AlertRule>,
+    pub overlay_enabled: bool,
+    pub overlay_port: u16,
+    pub webhook: WebhookConfig,
+    pub show_chart: bool,
+    /// How far the death recap looks back before each death.
+    pub recap_window_secs: u32,
+    /// Longest quiet stretch inside one engagement.
+    pub engagement_gap_secs: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowSize {
+    pub width: f32,
+    pub height: f32,
+}
 
-pub fn load_last_log_path() -> Option<String> {
-    read_setting(LAST_PATH_FILE).and_then(|contents| {
-        let trimmed = contents.trim();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed.to_string())
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            last_log_path: None,
+            ignored_player: None,
+            hidden_categories: Vec::new(),
+            search_text: String::new(),
+            search_regex: false,
+            roster: Roster::default(),
+            roster_scope: RosterScope::All,
+            auto_refresh_secs: 2.0,
+            window: None,
+            history_enabled: false,
+            library_dir: None,
+            alert_rules: Vec::new(),
+            overlay_enabled: false,
+            overlay_port: DEFAULT_OVERLAY_PORT,
+            webhook: WebhookConfig::default(),
+            show_chart: false,
+            recap_window_secs: DEFAULT_RECAP_WINDOW_SECS,
+            engagement_gap_secs: DEFAULT_ENGAGEMENT_GAP_SECS,
         }
-    })
+    }
 }
 
-pub fn save_last_log_path(path: &Path) -> io::Result<()> {
-    let as_str = path.to_string_lossy();
-    write_setting(LAST_PATH_FILE, as_str.as_ref())
+impl Settings {
+    /// Reads the settings document, or builds one from the files older versions wrote.
+    pub fn load() -> Self {
+        project_dirs()
+            .map(|dir| Self::load_from(&dir))
+            .unwrap_or_default()
+    }
+
+    /// Reads the settings without touching the settings directory, for the command line. An
+    /// unreadable document gives the defaults and is left where it is; the files of older
+    /// versions are read but not migrated.
+    pub fn read() -> Self {
+        project_dirs()
+            .map(|dir| Self::read_from(&dir))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(dir) = project_dirs() else {
+            return Err(io::Error::other("Failed to resolve settings directory"));
+        };
+        self.save_to(&dir)
+    }
+
+    fn load_from(dir: &Path) -> Self {
+        if let Some(contents) = read_setting(dir, SETTINGS_FILE) {
+            return match serde_json::from_str::<serde_json::Value>(&contents)
+                .map_err(|err| err.to_string())
+                .and_then(migrate)
+            {
+                Ok(settings) => settings,
+                Err(err) => {
+                    eprintln!("Failed to read settings, using defaults: {}", err);
+                    // Keep the file, or saving the defaults would overwrite it.
+                    match backup_setting(dir, SETTINGS_FILE) {
+                        Ok(path) => {
+                            eprintln!("Moved the unreadable settings to {}", path.display())
+                        }
+                        Err(err) => eprintln!("Failed to back up the settings: {}", err),
+                    }
+                    Settings::default()
+                }
+            };
+        }
+
+        let settings = load_legacy(dir);
+        if settings != Settings::default() {
+            match settings.save_to(dir) {
+                Ok(()) => remove_legacy_files(dir),
+                Err(err) => eprintln!("Failed to migrate settings: {}", err),
+            }
+        }
+        settings
+    }
+
+    fn read_from(dir: &Path) -> Self {
+        match read_setting(dir, SETTINGS_FILE) {
+            Some(contents) => serde_json::from_str::<serde_json::Value>(&contents)
+                .map_err(|err| err.to_string())
+                .and_then(migrate)
+                .unwrap_or_default(),
+            None => load_legacy(dir),
+        }
+    }
+
+    fn save_to(&self, dir: &Path) -> io::Result<()> {
+        let contents = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        write_setting(dir, SETTINGS_FILE, &contents)
+    }
 }
 
-pub fn load_ignored_player() -> Option<String> {
-    read_setting(IGNORED_PLAYER_FILE)
+/// Upgrades a parsed document to the current version. Fields missing from older versions
+/// take their defaults.
+fn migrate(value: serde_json::Value) -> Result<Settings, String> {
+    let version = value
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0);
+    if version > u64::from(SETTINGS_VERSION) {
+        return Err(format!(
+            "settings version {} is newer than this build supports ({})",
+            version, SETTINGS_VERSION
+        ));
+    }
+    let mut settings: Settings = serde_json::from_value(value).map_err(|err| err.to_string())?;
+    settings.version = SETTINGS_VERSION;
+    Ok(settings)
 }
 
-pub fn save_ignored_player(value: &str) -> io::Result<()> {
-    write_setting(IGNORED_PLAYER_FILE, value)
+fn load_legacy(dir: &Path) -> Settings {
+    Settings {
+        last_log_path: read_setting(dir, LEGACY_LAST_PATH_FILE)
+            .map(|contents| contents.trim().to_string())
+            .filter(|path| !path.is_empty()),
+        ignored_player: read_setting(dir, LEGACY_IGNORED_PLAYER_FILE),
+        ..Settings::default()
+    }
 }
 
-fn read_setting(file_name: &str) -> Option<String> {
-    let path = storage_file_path(file_name)?;
-    let mut contents = fs::read_to_string(path).ok()?;
+fn remove_legacy_files(dir: &Path) {
+    for file_name in [LEGACY_LAST_PATH_FILE, LEGACY_IGNORED_PLAYER_FILE] {
+        let path = dir.join(file_name);
+        if path.exists()
+            && let Err(err) = fs::remove_file(&path)
+        {
+            eprintln!("Failed to remove {}: {}", path.display(), err);
+        }
+    }
+}
+
+pub fn history_database_path() -> Option<PathBuf> {
+    storage_file_path(HISTORY_DATABASE_FILE)
+}
+
+/// The custom event pattern file, see `PatternSet`.
+pub fn patterns_path() -> Option<PathBuf> {
+    storage_file_path(PATTERNS_FILE)
+}
+
+fn read_setting(dir: &Path, file_name: &str) -> Option<String> {
+    let mut contents = fs::read_to_string(dir.join(file_name)).ok()?;
     while contents.ends_with('\n') || contents.ends_with('\r') {
         contents.pop();
     }
     Some(contents)
 }
 
-fn write_setting(file_name: &str, contents: &str) -> io::Result<()> {
-    let Some(storage_path) = storage_file_path(file_name) else {
-        return Err(io::Error::new(
-            io::ErrorKind::Other,
-            "Failed to resolve settings directory",
-        ));
-    };
+fn write_setting(dir: &Path, file_name: &str, contents: &str) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
 
-    if let Some(dir) = storage_path.parent() {
-        fs::create_dir_all(dir)?;
-    }
+    // Write next to the target and rename, so a crash never leaves a half written file.
+    let storage_path = dir.join(file_name);
+    let temp_path = storage_path.with_extension("tmp");
+    let mut file = fs::File::create(&temp_path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(temp_path, storage_path)
+}
 
-    let mut file = fs::File::create(storage_path)?;
-    file.write_all(contents.as_bytes())
+/// Renames a settings file to `<name>.bak`, replacing an older backup.
+fn backup_setting(dir: &Path, file_name: &str) -> io::Result<PathBuf> {
+    let backup_path = dir.join(format!("{}.bak", file_name));
+    fs::rename(dir.join(file_name), &backup_path)?;
+    Ok(backup_path)
 }
 
 fn storage_file_path(file_name: &str) -> Option<PathBuf> {
@@ -79,3 +253,69 @@ fn fallback_config_dir() -> Option<PathBuf> {
                 .map(|dir| dir.join(".config").join("sc_log_analyzer"))
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "sc_log_analyzer-settings-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("temp dir is writable");
+        dir
+    }
+
+    #[test]
+    fn unversioned_documents_are_migrated() {
+        let dir = temp_dir("migrate");
+        fs::write(
+            dir.join(SETTINGS_FILE),
+            r#"{"last_log_path": "Game.log", "show_chart": true}"#,
+        )
+        .expect("settings are writable");
+
+        let settings = Settings::load_from(&dir);
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.last_log_path.as_deref(), Some("Game.log"));
+        assert!(settings.show_chart);
+        assert_eq!(
+            settings.auto_refresh_secs,
+            Settings::default().auto_refresh_secs
+        );
+        assert!(migrate(serde_json::json!({ "version": SETTINGS_VERSION + 1 })).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unreadable_documents_are_backed_up() {
+        let dir = temp_dir("corrupt");
+        fs::write(dir.join(SETTINGS_FILE), "{ not json").expect("settings are writable");
+
+        assert_eq!(Settings::load_from(&dir), Settings::default());
+        assert!(!dir.join(SETTINGS_FILE).exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("settings.json.bak")).expect("backup exists"),
+            "{ not json"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn legacy_files_are_moved_into_the_document() {
+        let dir = temp_dir("legacy");
+        fs::write(dir.join(LEGACY_LAST_PATH_FILE), "Game.log\n").expect("writable");
+        fs::write(dir.join(LEGACY_IGNORED_PLAYER_FILE), "Me").expect("writable");
+
+        let settings = Settings::load_from(&dir);
+        assert_eq!(settings.last_log_path.as_deref(), Some("Game.log"));
+        assert_eq!(settings.ignored_player.as_deref(), Some("Me"));
+        assert!(!dir.join(LEGACY_LAST_PATH_FILE).exists());
+        assert!(!dir.join(LEGACY_IGNORED_PLAYER_FILE).exists());
+        assert_eq!(Settings::read_from(&dir), settings);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
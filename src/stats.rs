@@ -0,0 +1,231 @@
+use crate::event::{EventKind, PlayerEvent, describe_destroy_levels};
+use std::collections::HashMap;
+
+/// A labelled count, sorted by descending count in every breakdown.
+pub type Breakdown = Vec<(String, usize)>;
+
+/// Combat numbers for one player, computed from kill and vehicle destruction events.
+#[derive(Clone, Debug, Default)]
+pub struct CombatStats {
+    pub player: Option<String>,
+    pub kills: usize,
+    pub deaths: usize,
+    pub suicides: usize,
+    pub kills_by_weapon: Breakdown,
+    pub kills_by_weapon_class: Breakdown,
+    pub kills_by_zone: Breakdown,
+    pub kills_by_victim: Breakdown,
+    pub deaths_by_damage_type: Breakdown,
+    pub deaths_by_zone: Breakdown,
+    pub deaths_by_killer: Breakdown,
+    pub vehicle_soft_kills: usize,
+    pub vehicle_hard_kills: usize,
+    pub vehicles_soft_killed: usize,
+    pub vehicles_hard_killed: usize,
+}
+
+impl CombatStats {
+    /// Counts kills and deaths of `player`. Without a player only the totals of kill events
+    /// are filled in, since there is nobody to attribute them to.
+    pub fn compute<'a>(
+        events: impl IntoIterator<Item = &'a PlayerEvent>,
+        player: Option<&str>,
+    ) -> Self {
+        let player = player.map(str::trim).filter(|name| !name.is_empty());
+        let is_player = |name: &str| player.is_some_and(|p| name.eq_ignore_ascii_case(p));
+
+        let mut stats = CombatStats {
+            player: player.map(str::to_string),
+            ..CombatStats::default()
+        };
+        let mut kills_by_weapon = HashMap::new();
+        let mut kills_by_weapon_class = HashMap::new();
+        let mut kills_by_zone = HashMap::new();
+        let mut kills_by_victim = HashMap::new();
+        let mut deaths_by_damage_type = HashMap::new();
+        let mut deaths_by_zone = HashMap::new();
+        let mut deaths_by_killer = HashMap::new();
+
+        for event in events {
+            match &event.kind {
+                EventKind::Kill(kill) => {
+                    let killer_is_player = is_player(&kill.killer_name);
+                    let victim_is_player = is_player(&kill.victim_name);
+                    if killer_is_player && victim_is_player {
+                        stats.suicides += 1;
+                        stats.deaths += 1;
+                        tally(&mut deaths_by_damage_type, &kill.damage_type);
+                        tally(&mut deaths_by_zone, &kill.zone);
+                    } else if killer_is_player {
+                        stats.kills += 1;
+                        tally(&mut kills_by_weapon, weapon_base_name(&kill.weapon));
+                        tally(&mut kills_by_weapon_class, &kill.weapon_class);
+                        tally(&mut kills_by_zone, &kill.zone);
+                        tally(&mut kills_by_victim, &kill.victim_name);
+                    } else if victim_is_player {
+                        stats.deaths += 1;
+                        tally(&mut deaths_by_damage_type, &kill.damage_type);
+                        tally(&mut deaths_by_zone, &kill.zone);
+                        tally(&mut deaths_by_killer, &kill.killer_name);
+                    }
+                }
+                EventKind::VehicleDestruction(vehicle) => {
+                    let label = describe_destroy_levels(vehicle.from_level, vehicle.to_level);
+                    if is_player(&vehicle.attacker_name) && !is_player(&vehicle.driver_name) {
+                        match label {
+                            "soft kill" => stats.vehicle_soft_kills += 1,
+                            "hard kill" => stats.vehicle_hard_kills += 1,
+                            _ => {}
+                        }
+                    }
+                    if is_player(&vehicle.driver_name) {
+                        match label {
+                            "soft kill" => stats.vehicles_soft_killed += 1,
+                            "hard kill" => stats.vehicles_hard_killed += 1,
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        stats.kills_by_weapon = sorted(kills_by_weapon);
+        stats.kills_by_weapon_class = sorted(kills_by_weapon_class);
+        stats.kills_by_zone = sorted(kills_by_zone);
+        stats.kills_by_victim = sorted(kills_by_victim);
+        stats.deaths_by_damage_type = sorted(deaths_by_damage_type);
+        stats.deaths_by_zone = sorted(deaths_by_zone);
+        stats.deaths_by_killer = sorted(deaths_by_killer);
+        stats
+    }
+
+    /// Kills per death; `None` while the player has not died yet.
+    pub fn kd_ratio(&self) -> Option<f64> {
+        if self.deaths == 0 {
+            None
+        } else {
+            Some(self.kills as f64 / self.deaths as f64)
+        }
+    }
+}
+
+/// Drops the per-entity numeric suffix the game appends to item names,
+/// e.g. `KLWE_LaserRepeater_S3_1234` → `KLWE_LaserRepeater_S3`.
+pub fn weapon_base_name(weapon: &str) -> &str {
+    match weapon.rsplit_once('_') {
+        Some((base, suffix))
+            if !base.is_empty()
+                && !suffix.is_empty()
+                && suffix.bytes().all(|byte| byte.is_ascii_digit()) =>
+        {
+            base
+        }
+        _ => weapon,
+    }
+}
+
+fn tally(counts: &mut HashMap<String, usize>, key: &str) {
+    let key = key.trim();
+    let key = if key.is_empty() { "unknown" } else { key };
+    *counts.entry(key.to_string()).or_default() += 1;
+}
+
+fn sorted(counts: HashMap<String, usize>) -> Breakdown {
+    let mut entries: Breakdown = counts.into_iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_line;
+
+    fn kill(victim: &str, killer: &str, weapon: &str, zone: &str) -> PlayerEvent {
+        parse_line(&format!(
+            "<2024-09-14T18:00:00.000Z> [Notice] <Actor Death> CActor::Kill: '{}' [1] in zone '{}' killed by '{}' [2] using '{}' [Class W] with damage type 'Bullet' from direction x: 0, y: 0, z: 0 [Team_ActorTech][Actor]",
+            victim, zone, killer, weapon
+        ))
+        .expect("kill line")
+    }
+
+    fn vehicle(driver: &str, attacker: &str, from_level: u8) -> PlayerEvent {
+        parse_line(&format!(
+            "<2024-09-14T18:00:00.000Z> [Notice] <Vehicle Destruction> CVehicle::OnAdvanceDestroyLevel: Vehicle 'Ship_1' [3] in zone 'Z' [pos x: 0.000000, y: 0.000000, z: 0.000000 vel x: 0.000000, y: 0.000000, z: 0.000000] driven by '{}' [1] advanced from destroy level {} to {} caused by '{}' [2] with 'Combat' [Team_VehicleFeatures][Vehicle]",
+            driver,
+            from_level,
+            from_level + 1,
+            attacker
+        ))
+        .expect("vehicle line")
+    }
+
+    #[test]
+    fn kills_and_deaths_are_counted_for_the_player() {
+        let events = [
+            kill("Foe", "Me", "rifle_1234", "Z1"),
+            kill("Foe", "me", "rifle_99", "Z2"),
+            kill("Other", "Me", "pistol", "Z1"),
+            kill("Me", "Foe", "rifle_1", "Z1"),
+            kill("Me", "Me", "unknown", "Z2"),
+            kill("Other", "Foe", "rifle_1", "Z1"),
+        ];
+        let stats = CombatStats::compute(&events, Some(" Me "));
+
+        assert_eq!(stats.player.as_deref(), Some("Me"));
+        assert_eq!((stats.kills, stats.deaths, stats.suicides), (3, 2, 1));
+        assert_eq!(stats.kd_ratio(), Some(1.5));
+        assert_eq!(
+            stats.kills_by_weapon,
+            [("rifle".to_string(), 2), ("pistol".to_string(), 1)]
+        );
+        assert_eq!(
+            stats.kills_by_victim,
+            [("Foe".to_string(), 2), ("Other".to_string(), 1)]
+        );
+        assert_eq!(
+            stats.deaths_by_zone,
+            [("Z1".to_string(), 1), ("Z2".to_string(), 1)]
+        );
+        assert_eq!(stats.deaths_by_killer, [("Foe".to_string(), 1)]);
+    }
+
+    #[test]
+    fn vehicle_kills_are_split_into_soft_and_hard() {
+        let events = [
+            vehicle("Foe", "Me", 0),
+            vehicle("Foe", "Me", 1),
+            vehicle("Foe", "Me", 2),
+            vehicle("Me", "Me", 1),
+            vehicle("Me", "Foe", 0),
+        ];
+        let stats = CombatStats::compute(&events, Some("Me"));
+        assert_eq!((stats.vehicle_soft_kills, stats.vehicle_hard_kills), (1, 1));
+        assert_eq!(
+            (stats.vehicles_soft_killed, stats.vehicles_hard_killed),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn without_a_player_nothing_is_attributed() {
+        let stats = CombatStats::compute(&[kill("Foe", "Me", "rifle", "Z")], None);
+        assert_eq!((stats.kills, stats.deaths), (0, 0));
+        assert_eq!(stats.kd_ratio(), None);
+    }
+
+    #[test]
+    fn weapon_names_lose_their_entity_suffix() {
+        assert_eq!(
+            weapon_base_name("KLWE_LaserRepeater_S3_1234"),
+            "KLWE_LaserRepeater_S3"
+        );
+        assert_eq!(
+            weapon_base_name("KLWE_LaserRepeater_S3"),
+            "KLWE_LaserRepeater_S3"
+        );
+        assert_eq!(weapon_base_name("_1234"), "_1234");
+        assert_eq!(weapon_base_name("rifle_"), "rifle_");
+    }
+}
@@ -0,0 +1,155 @@
+use eframe::egui::{self, Color32, RichText};
+use sc_log_analyzer::stats::CombatStats;
+
+const BREAKDOWN_ROWS: usize = 10;
+
+pub fn show(ui: &mut egui::Ui, stats: &CombatStats) {
+    let Some(player) = stats.player.as_deref() else {
+        ui.label(
+            RichText::new(
+                "No player nickname found in this log, so kills and deaths cannot be attributed.",
+            )
+            .color(Color32::from_rgb(200, 200, 200)),
+        );
+        return;
+    };
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.label(
+            RichText::new(format!("Statistics for {}", player))
+                .strong()
+                .color(Color32::from_rgb(220, 220, 220)),
+        );
+        ui.add_space(6.0);
+
+        ui.horizontal_wrapped(|ui| {
+            let kd = stats
+                .kd_ratio()
+                .map(|ratio| format!("{:.2}", ratio))
+                .unwrap_or_else(|| "–".to_string());
+            stat_card(
+                ui,
+                "Kills",
+                &stats.kills.to_string(),
+                Color32::from_rgb(235, 130, 130),
+            );
+            stat_card(
+                ui,
+                "Deaths",
+                &stats.deaths.to_string(),
+                Color32::from_rgb(130, 185, 245),
+            );
+            stat_card(ui, "K/D", &kd, Color32::from_rgb(220, 220, 220));
+            stat_card(
+                ui,
+                "Suicides",
+                &stats.suicides.to_string(),
+                Color32::from_rgb(200, 200, 200),
+            );
+            stat_card(
+                ui,
+                "Vehicle soft / hard kills",
+                &format!(
+                    "{} / {}",
+                    stats.vehicle_soft_kills, stats.vehicle_hard_kills
+                ),
+                Color32::from_rgb(245, 150, 150),
+            );
+            stat_card(
+                ui,
+                "Own vehicle soft / hard killed",
+                &format!(
+                    "{} / {}",
+                    stats.vehicles_soft_killed, stats.vehicles_hard_killed
+                ),
+                Color32::from_rgb(245, 180, 140),
+            );
+        });
+        ui.add_space(10.0);
+
+        let kill_color = Color32::from_rgb(200, 90, 100);
+        let death_color = Color32::from_rgb(80, 130, 200);
+        egui::Grid::new("stats_breakdowns")
+            .num_columns(2)
+            .spacing([16.0, 16.0])
+            .show(ui, |ui| {
+                breakdown(ui, "Kills by weapon", &stats.kills_by_weapon, kill_color);
+                breakdown(
+                    ui,
+                    "Kills by weapon class",
+                    &stats.kills_by_weapon_class,
+                    kill_color,
+                );
+                ui.end_row();
+                breakdown(ui, "Kills by zone", &stats.kills_by_zone, kill_color);
+                breakdown(ui, "Kills by opponent", &stats.kills_by_victim, kill_color);
+                ui.end_row();
+                breakdown(
+                    ui,
+                    "Deaths by damage type",
+                    &stats.deaths_by_damage_type,
+                    death_color,
+                );
+                breakdown(ui, "Deaths by zone", &stats.deaths_by_zone, death_color);
+                ui.end_row();
+                breakdown(
+                    ui,
+                    "Deaths by opponent",
+                    &stats.deaths_by_killer,
+                    death_color,
+                );
+                ui.end_row();
+            });
+    });
+}
+
+fn stat_card(ui: &mut egui::Ui, label: &str, value: &str, color: Color32) {
+    egui::Frame::none()
+        .fill(Color32::from_rgb(28, 32, 40))
+        .rounding(egui::Rounding::same(6.0))
+        .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+        .show(ui, |ui| {
+            ui.vertical(|ui| {
+                ui.label(RichText::new(label).color(Color32::from_rgb(160, 160, 160)));
+                ui.label(RichText::new(value).size(20.0).strong().color(color));
+            });
+        });
+}
+
+/// Horizontal bar chart of the largest entries, scaled to the biggest count.
+fn breakdown(ui: &mut egui::Ui, title: &str, entries: &[(String, usize)], color: Color32) {
+    ui.vertical(|ui| {
+        ui.set_min_width(360.0);
+        ui.label(
+            RichText::new(title)
+                .strong()
+                .color(Color32::from_rgb(210, 210, 210)),
+        );
+        if entries.is_empty() {
+            ui.label(RichText::new("No data").color(Color32::from_rgb(140, 140, 140)));
+            return;
+        }
+        let max = entries.first().map(|(_, count)| *count).unwrap_or(1).max(1);
+        for (label, count) in entries.iter().take(BREAKDOWN_ROWS) {
+            ui.horizontal(|ui| {
+                let (rect, response) =
+                    ui.allocate_exact_size(egui::vec2(140.0, 16.0), egui::Sense::hover());
+                let width = rect.width() * (*count as f32 / max as f32);
+                let bar = egui::Rect::from_min_size(rect.min, egui::vec2(width, rect.height()));
+                ui.painter()
+                    .rect_filled(bar, egui::Rounding::same(3.0), color);
+                response.on_hover_text(format!("{}: {}", label, count));
+                ui.label(
+                    RichText::new(format!("{:>3}", count)).color(Color32::from_rgb(220, 220, 220)),
+                );
+                ui.label(RichText::new(label).color(Color32::from_rgb(190, 190, 190)));
+            });
+        }
+        if entries.len() > BREAKDOWN_ROWS {
+            ui.label(
+                RichText::new(format!("… and {} more", entries.len() - BREAKDOWN_ROWS))
+                    .color(Color32::from_rgb(140, 140, 140)),
+            );
+        }
+    });
+}
@@ -0,0 +1,195 @@
+use crate::event::{EventKind, PlayerEvent};
+use crate::filter::EventCategory;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A Discord-style message with the event summary.
+pub const DEFAULT_TEMPLATE: &str = r#"{"content": "{{summary}}"}"#;
+
+/// Where and what to post. Only events appended to the followed log are sent.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub enabled: bool,
+    pub url: String,
+    pub categories: Vec<EventCategory>,
+    /// Only post events the primary player took part in, e.g. kills where they are the killer
+    /// or the victim.
+    pub only_mine: bool,
+    /// JSON payload with `{{placeholder}}`s, see [`render_payload`].
+    pub template: String,
+    /// Write the payloads to this file, one JSON document per line, instead of posting them.
+    pub dry_run_path: Option<String>,
+    pub max_per_minute: u32,
+    pub max_retries: u32,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            categories: vec![EventCategory::Kill],
+            only_mine: true,
+            template: DEFAULT_TEMPLATE.to_string(),
+            dry_run_path: None,
+            max_per_minute: 20,
+            max_retries: 3,
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// The dry-run file, if one is set.
+    pub fn dry_run_path(&self) -> Option<&str> {
+        self.dry_run_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+    }
+
+    pub fn selects(&self, event: &PlayerEvent, nickname: Option<&str>) -> bool {
+        if !self.categories.contains(&EventCategory::of(&event.kind)) {
+            return false;
+        }
+        if !self.only_mine {
+            return true;
+        }
+        let Some(nickname) = nickname.map(str::trim).filter(|name| !name.is_empty()) else {
+            return false;
+        };
+        match &event.kind {
+            EventKind::Kill(kill) => {
+                kill.killer_name.eq_ignore_ascii_case(nickname)
+                    || kill.victim_name.eq_ignore_ascii_case(nickname)
+            }
+            _ => event
+                .involved_players()
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(nickname)),
+        }
+    }
+}
+
+/// Fills in `template` for `event` and checks that the result is JSON.
+///
+/// `{{summary}}`, `{{timestamp}}`, `{{category}}` and `{{raw}}` are available for every event,
+/// plus the fields of the event under their export names, e.g. `{{killer_name}}`,
+/// `{{victim_name}}`, `{{weapon}}` or `{{vehicle_name}}`, and the captures of custom events.
+/// Values are escaped for use inside a JSON string; fields the event does not have are left
+/// empty.
+pub fn render_payload(template: &str, event: &PlayerEvent) -> Result<Value, String> {
+    let mut fields = match serde_json::to_value(&event.kind) {
+        Ok(Value::Object(mut object)) => match object.remove("data") {
+            Some(Value::Object(data)) => data,
+            _ => Default::default(),
+        },
+        _ => Default::default(),
+    };
+    if let EventKind::Custom(custom) = &event.kind {
+        for (name, value) in &custom.fields {
+            fields
+                .entry(name.clone())
+                .or_insert_with(|| Value::String(value.clone()));
+        }
+    }
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            return Err(format!(
+                "Unclosed placeholder at '{}'",
+                &rest[start..].chars().take(20).collect::<String>()
+            ));
+        };
+        let name = after[..end].trim();
+        let value = match name {
+            "summary" => event.summary_line(),
+            "timestamp" => event.timestamp.to_rfc3339(),
+            "category" => EventCategory::of(&event.kind).key().to_string(),
+            "raw" => event.raw.clone(),
+            _ => match fields.get(name) {
+                Some(Value::String(value)) => value.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(value) => value.to_string(),
+            },
+        };
+        rendered.push_str(&escape_json_string(&value));
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+
+    serde_json::from_str(&rendered)
+        .map_err(|err| format!("The template is not valid JSON once filled in: {}", err))
+}
+
+fn escape_json_string(value: &str) -> String {
+    let quoted = Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_line;
+
+    fn kill(victim: &str, killer: &str) -> PlayerEvent {
+        parse_line(&format!(
+            "<2024-09-14T18:00:00.000Z> [Notice] <Actor Death> CActor::Kill: '{}' [1] in zone 'Z' killed by '{}' [2] using 'W_1' [Class W] with damage type 'Bullet' from direction x: 0, y: 0, z: 0 [Team_ActorTech][Actor]",
+            victim, killer
+        ))
+        .expect("kill line")
+    }
+
+    fn status_effect(nickname: &str) -> PlayerEvent {
+        parse_line(&format!(
+            "<2024-09-14T18:00:00.000Z> Logged a start of a status effect! nickname: {}, status effect: Bleeding",
+            nickname
+        ))
+        .expect("status effect line")
+    }
+
+    #[test]
+    fn payload_values_are_escaped_for_json_strings() {
+        let event = status_effect(r#"Quote"Back\slash"#);
+        let payload = render_payload(
+            r#"{"content": "{{player_name}}: {{ effect }}{{missing}}", "raw": "{{raw}}"}"#,
+            &event,
+        )
+        .expect("valid payload");
+        assert_eq!(payload["content"], r#"Quote"Back\slash: Bleeding"#);
+        assert_eq!(payload["raw"], event.raw.as_str());
+    }
+
+    #[test]
+    fn broken_templates_are_reported() {
+        let event = kill("Other", "Me");
+        assert!(render_payload(r#"{"content": "{{summary"}"#, &event).is_err());
+        assert!(render_payload(r#"{"content": {{summary}}}"#, &event).is_err());
+    }
+
+    #[test]
+    fn only_mine_selects_events_of_the_primary_nickname() {
+        let config = WebhookConfig {
+            categories: vec![EventCategory::Kill, EventCategory::Status],
+            ..WebhookConfig::default()
+        };
+        assert!(config.selects(&kill("Other", "Me"), Some("me")));
+        assert!(config.selects(&kill("Me", "Other"), Some(" Me ")));
+        assert!(!config.selects(&kill("Other", "Someone"), Some("Me")));
+        assert!(!config.selects(&kill("Other", "Me"), None));
+        assert!(!config.selects(&kill("Other", "Me"), Some(" ")));
+        assert!(config.selects(&status_effect("Me"), Some("Me")));
+        assert!(!config.selects(&status_effect("Other"), Some("Me")));
+
+        let everyone = WebhookConfig {
+            only_mine: false,
+            ..WebhookConfig::default()
+        };
+        assert!(everyone.selects(&kill("Other", "Someone"), None));
+        assert!(!everyone.selects(&status_effect("Me"), Some("Me")));
+    }
+}
@@ -0,0 +1,217 @@
+use once_cell::sync::Lazy;
+use reqwest::{StatusCode, blocking::Client, header};
+use sc_log_analyzer::WebhookConfig;
+use serde_json::Value;
+use std::{
+    collections::VecDeque,
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+static CLIENT: Lazy<Client> = Lazy::new(|| {
+    Client::builder()
+        .user_agent("SC Log Analyzer/0.1")
+        .timeout(Duration::from_secs(10))
+        .build()
+        .expect("failed to build HTTP client")
+});
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+struct WebhookJob {
+    payload: Value,
+    target: Target,
+    max_per_minute: u32,
+    max_retries: u32,
+}
+
+enum Target {
+    Url(String),
+    DryRun(PathBuf),
+}
+
+/// Posts webhook payloads in order on a worker thread, keeping to the rate limit and retrying
+/// failed posts with exponential backoff.
+pub struct WebhookSender {
+    tx: Sender<WebhookJob>,
+    status: Receiver<Result<String, String>>,
+}
+
+impl WebhookSender {
+    pub fn spawn() -> Self {
+        let (tx, rx) = mpsc::channel::<WebhookJob>();
+        let (status_tx, status) = mpsc::channel();
+        thread::spawn(move || {
+            let mut sent: VecDeque<Instant> = VecDeque::new();
+            for job in rx {
+                wait_for_rate_limit(&mut sent, job.max_per_minute);
+                let result = deliver(&job);
+                sent.push_back(Instant::now());
+                if status_tx.send(result).is_err() {
+                    return;
+                }
+            }
+        });
+        Self { tx, status }
+    }
+
+    /// Queues `payload` for the URL, or the dry-run file, of `config`.
+    pub fn send(&self, config: &WebhookConfig, payload: Value) -> Result<(), String> {
+        let target = match config.dry_run_path() {
+            Some(path) => Target::DryRun(PathBuf::from(path)),
+            None if !config.url.trim().is_empty() => Target::Url(config.url.trim().to_string()),
+            None => return Err("Set a webhook URL or a dry-run file first.".to_string()),
+        };
+        let _ = self.tx.send(WebhookJob {
+            payload,
+            target,
+            max_per_minute: config.max_per_minute,
+            max_retries: config.max_retries,
+        });
+        Ok(())
+    }
+
+    /// The outcome of the latest delivery, if any finished since the last call.
+    pub fn take_status(&self) -> Option<Result<String, String>> {
+        self.status.try_iter().last()
+    }
+}
+
+/// Sleeps until fewer than `max_per_minute` deliveries happened in the last minute.
+fn wait_for_rate_limit(sent: &mut VecDeque<Instant>, max_per_minute: u32) {
+    while let Some(delay) = rate_limit_delay(sent, max_per_minute, Instant::now()) {
+        thread::sleep(delay);
+    }
+}
+
+/// Forgets the deliveries older than a minute at `now` and returns how long to wait before the
+/// next one, if `max_per_minute` are left.
+fn rate_limit_delay(
+    sent: &mut VecDeque<Instant>,
+    max_per_minute: u32,
+    now: Instant,
+) -> Option<Duration> {
+    let limit = max_per_minute.max(1) as usize;
+    while sent
+        .front()
+        .is_some_and(|time| now.saturating_duration_since(*time) >= RATE_WINDOW)
+    {
+        sent.pop_front();
+    }
+    let oldest = sent.front().filter(|_| sent.len() >= limit)?;
+    Some(RATE_WINDOW.saturating_sub(now.saturating_duration_since(*oldest)))
+}
+
+fn deliver(job: &WebhookJob) -> Result<String, String> {
+    match &job.target {
+        Target::DryRun(path) => OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| writeln!(file, "{}", job.payload))
+            .map(|()| format!("Wrote payload to {}", path.display()))
+            .map_err(|err| format!("Failed to write {}: {}", path.display(), err)),
+        Target::Url(url) => post_with_retries(url, &job.payload, job.max_retries),
+    }
+}
+
+/// Retries network errors, `429 Too Many Requests` and server errors. Other client errors
+/// mean the payload or URL is wrong, so they are not retried.
+fn post_with_retries(url: &str, payload: &Value, max_retries: u32) -> Result<String, String> {
+    let body = payload.to_string();
+    let mut attempt = 0;
+    loop {
+        let (error, retry_after) = match CLIENT
+            .post(url)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body.clone())
+            .send()
+        {
+            Ok(response) if response.status().is_success() => {
+                return Ok(format!("Posted to webhook ({})", response.status()));
+            }
+            Ok(response) => {
+                let status = response.status();
+                if status != StatusCode::TOO_MANY_REQUESTS && !status.is_server_error() {
+                    return Err(format!("Webhook rejected the payload: {}", status));
+                }
+                let retry_after = response
+                    .headers()
+                    .get(header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(parse_retry_after);
+                (format!("Webhook returned {}", status), retry_after)
+            }
+            Err(err) => (format!("Failed to post to webhook: {}", err), None),
+        };
+        if attempt >= max_retries {
+            return Err(format!(
+                "{} (gave up after {} attempts)",
+                error,
+                attempt + 1
+            ));
+        }
+        let backoff = Duration::from_secs(1 << attempt.min(6)).min(MAX_BACKOFF);
+        thread::sleep(retry_after.unwrap_or(backoff));
+        attempt += 1;
+    }
+}
+
+/// A `Retry-After` value in whole seconds or as an HTTP date, capped at `MAX_BACKOFF`.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let secs = match value.parse::<u64>() {
+        Ok(secs) => secs,
+        Err(_) => {
+            let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+            let wait = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+            u64::try_from(wait.num_seconds()).unwrap_or(0)
+        }
+    };
+    Some(Duration::from_secs(secs.min(MAX_BACKOFF.as_secs())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deliveries_wait_for_the_oldest_to_leave_the_window() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut sent = VecDeque::from([at(0), at(40), at(50)]);
+
+        assert_eq!(
+            rate_limit_delay(&mut sent, 2, at(70)),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(sent, [at(40), at(50)]);
+        assert_eq!(rate_limit_delay(&mut sent, 3, at(70)), None);
+        assert_eq!(rate_limit_delay(&mut sent, 2, at(100)), None);
+        assert_eq!(sent, [at(50)]);
+        assert_eq!(
+            rate_limit_delay(&mut sent, 0, at(100)),
+            Some(Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn retry_after_reads_seconds_and_http_dates() {
+        assert_eq!(parse_retry_after(" 5 "), Some(Duration::from_secs(5)));
+        assert_eq!(parse_retry_after("3600"), Some(MAX_BACKOFF));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        let soon = chrono::Utc::now() + chrono::Duration::seconds(30);
+        let wait = parse_retry_after(&soon.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+            .expect("HTTP date");
+        assert!(wait > Duration::from_secs(25) && wait <= Duration::from_secs(30));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+}
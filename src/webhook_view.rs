@@ -0,0 +1,135 @@
+use eframe::egui::{self, Color32, RichText};
+use rfd::FileDialog;
+use sc_log_analyzer::{EventCategory, PlayerEvent, WebhookConfig, render_payload};
+use serde_json::Value;
+
+/// Settings for the webhook sink, with a preview of the payload for the newest matching event.
+#[derive(Default)]
+pub struct WebhookWindow {
+    pub open: bool,
+}
+
+impl WebhookWindow {
+    /// Shows the window. Returns the preview payload when the user asked to send it as a test.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        config: &mut WebhookConfig,
+        preview_event: Option<&PlayerEvent>,
+    ) -> Option<Value> {
+        if !self.open {
+            return None;
+        }
+        let mut test = None;
+        let mut open = self.open;
+        egui::Window::new("Webhook")
+            .collapsible(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.checkbox(&mut config.enabled, "Post new events while the log is followed");
+                egui::Grid::new("webhook_settings")
+                    .num_columns(2)
+                    .spacing([8.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.label("URL");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut config.url)
+                                .hint_text("https://discord.com/api/webhooks/…")
+                                .desired_width(360.0),
+                        );
+                        ui.end_row();
+
+                        ui.label("Events");
+                        ui.horizontal_wrapped(|ui| {
+                            for category in EventCategory::ALL {
+                                let mut selected = config.categories.contains(&category);
+                                if ui.checkbox(&mut selected, category.key()).changed() {
+                                    config.categories.retain(|other| *other != category);
+                                    if selected {
+                                        config.categories.push(category);
+                                        config.categories.sort();
+                                    }
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("");
+                        ui.checkbox(&mut config.only_mine, "Only events I am part of");
+                        ui.end_row();
+
+                        ui.label("Rate limit");
+                        ui.add(
+                            egui::DragValue::new(&mut config.max_per_minute)
+                                .clamp_range(1..=600)
+                                .suffix(" per minute"),
+                        );
+                        ui.end_row();
+
+                        ui.label("Retries");
+                        ui.add(egui::DragValue::new(&mut config.max_retries).clamp_range(0..=10));
+                        ui.end_row();
+
+                        ui.label("Dry run");
+                        ui.horizontal(|ui| {
+                            let path = config.dry_run_path.get_or_insert_with(String::new);
+                            ui.add(
+                                egui::TextEdit::singleline(path)
+                                    .hint_text("Write payloads to this file instead")
+                                    .desired_width(280.0),
+                            );
+                            if ui.small_button("Browse…").clicked()
+                                && let Some(picked) = FileDialog::new()
+                                    .add_filter("JSON lines", &["jsonl", "ndjson"])
+                                    .save_file()
+                            {
+                                *path = picked.display().to_string();
+                            }
+                            if path.trim().is_empty() {
+                                config.dry_run_path = None;
+                            }
+                        });
+                        ui.end_row();
+                    });
+
+                ui.add_space(4.0);
+                ui.label("Payload template")
+                    .on_hover_text(
+                        "JSON with placeholders: {{summary}}, {{timestamp}}, {{category}}, {{raw}} and the event fields, e.g. {{killer_name}}, {{victim_name}}, {{weapon}}, {{zone}}, {{vehicle_name}}",
+                    );
+                ui.add(
+                    egui::TextEdit::multiline(&mut config.template)
+                        .code_editor()
+                        .desired_rows(4)
+                        .desired_width(f32::INFINITY),
+                );
+
+                ui.add_space(4.0);
+                match preview_event {
+                    Some(event) => match render_payload(&config.template, event) {
+                        Ok(payload) => {
+                            ui.label(
+                                RichText::new(payload.to_string())
+                                    .monospace()
+                                    .color(Color32::from_rgb(160, 200, 240)),
+                            );
+                            if ui.button("Send test").clicked() {
+                                test = Some(payload);
+                            }
+                        }
+                        Err(err) => {
+                            ui.colored_label(Color32::from_rgb(240, 90, 80), err);
+                        }
+                    },
+                    None => {
+                        ui.label(
+                            RichText::new("No loaded event matches these settings to preview.")
+                                .color(Color32::from_rgb(160, 160, 160)),
+                        );
+                    }
+                }
+            });
+        self.open = open;
+        test
+    }
+}
@@ -0,0 +1,313 @@
+//! Golden-file tests for the event parsers.
+//!
+//! `tests/fixtures/<patch>/<parser>.log` holds log lines in the dialect of one game patch, and
+//! the `.json` next to it the events they have to parse into. `header.log` is the top of a log
+//! of the patch, which selects the parser profile. See `tests/fixtures/README.md`.
+
+use sc_log_analyzer::{
+    GameBuild, GameVersion, LogTail, ParserProfile, PatternSet, PlayerEvent, merge_newest_first,
+    parser,
+};
+use serde_json::Value;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+type LineParserFn = fn(&str) -> Option<PlayerEvent>;
+
+/// Fixture file names and the parser every line in them has to match.
+const PARSERS: [(&str, LineParserFn); 7] = [
+    ("actor_death", parser::parse_actor_death),
+    ("spawn_reservation", parser::parse_spawn_reservation),
+    ("corpse_status", parser::parse_corpse_status),
+    ("zone_transfer", parser::parse_zone_transfer),
+    ("status_effect", parser::parse_status_effect),
+    ("hit_event", parser::parse_hit_event),
+    ("vehicle_destruction", parser::parse_vehicle_destruction),
+];
+
+/// Set to rewrite the `.json` snapshots from the current parser output.
+const UPDATE_VAR: &str = "UPDATE_SNAPSHOTS";
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn patch_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(fixtures_dir())
+        .expect("tests/fixtures exists")
+        .map(|entry| entry.expect("readable fixtures dir").path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// The profile meant for the patch a fixture folder is named after.
+fn patch_profile(dir: &Path) -> &'static ParserProfile {
+    let name = dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let version = GameVersion::parse(name)
+        .unwrap_or_else(|| panic!("{} is not named after a patch", dir.display()));
+    ParserProfile::select(&GameBuild {
+        version: Some(version),
+        ..GameBuild::default()
+    })
+}
+
+fn read_header(dir: &Path) -> String {
+    let path = dir.join("header.log");
+    fs::read_to_string(&path).unwrap_or_else(|_| panic!("{} is missing", path.display()))
+}
+
+/// The fixture lines with their line numbers, skipping blank lines and `#` comments.
+fn fixture_lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+}
+
+/// The snapshot of one event: the fields of the export without the raw line, which is already
+/// in the fixture, plus the fixture line it came from.
+fn snapshot(line: usize, event: &PlayerEvent) -> Value {
+    let mut value = serde_json::to_value(event).expect("events serialize");
+    let object = value.as_object_mut().expect("events serialize to objects");
+    object.remove("raw");
+    object.insert("line".to_string(), line.into());
+    value
+}
+
+#[test]
+fn fixtures_match_snapshots() {
+    let update = env::var_os(UPDATE_VAR).is_some();
+    let mut failures = Vec::new();
+    for dir in patch_dirs() {
+        for (name, parse) in PARSERS {
+            let log_path = dir.join(format!("{}.log", name));
+            let Ok(text) = fs::read_to_string(&log_path) else {
+                continue;
+            };
+            let mut events = Vec::new();
+            for (line_number, line) in fixture_lines(&text) {
+                match parse(line) {
+                    Some(event) => events.push(snapshot(line_number, &event)),
+                    None => failures.push(format!(
+                        "{}:{}: not recognized by parse_{}",
+                        log_path.display(),
+                        line_number,
+                        name
+                    )),
+                }
+            }
+            let actual = serde_json::to_string_pretty(&events).expect("snapshots serialize") + "\n";
+
+            let json_path = log_path.with_extension("json");
+            if update {
+                fs::write(&json_path, &actual).expect("snapshot is writable");
+                continue;
+            }
+            match fs::read_to_string(&json_path) {
+                Ok(expected) if expected.replace("\r\n", "\n") == actual => {}
+                Ok(expected) => failures.push(format!(
+                    "{} differs from the parser output:\n{}",
+                    json_path.display(),
+                    first_difference(&expected, &actual)
+                )),
+                Err(_) => failures.push(format!(
+                    "{} is missing; run `{}=1 cargo test --test parser_fixtures` to create it",
+                    json_path.display(),
+                    UPDATE_VAR
+                )),
+            }
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn every_parser_has_fixtures() {
+    for (name, _) in PARSERS {
+        let covered = patch_dirs()
+            .iter()
+            .any(|dir| dir.join(format!("{}.log", name)).is_file());
+        assert!(covered, "no fixture for parse_{} in any patch", name);
+    }
+}
+
+#[test]
+fn headers_select_the_patch_profile() {
+    for dir in patch_dirs() {
+        let header = read_header(&dir);
+        let parsed =
+            parser::parse_reader(header.as_bytes(), &PatternSet::default()).expect("reads");
+        let build = &parsed.metadata.build;
+        assert!(
+            build.is_complete(),
+            "{}: header incomplete: {:?}",
+            dir.display(),
+            build
+        );
+        assert_eq!(
+            ParserProfile::select(build).name,
+            patch_profile(&dir).name,
+            "{}: header of {} picked another profile",
+            dir.display(),
+            build
+        );
+    }
+}
+
+/// Fixture lines go through the whole pipeline too: each one is recognized by exactly the
+/// parser its file is named after, also with the profile of the patch, and behind the header
+/// of the patch they leave nothing for the parser health report.
+#[test]
+fn fixtures_parse_as_whole_logs() {
+    for dir in patch_dirs() {
+        let profile = patch_profile(&dir);
+        let header = read_header(&dir);
+        for (name, parse) in PARSERS {
+            let log_path = dir.join(format!("{}.log", name));
+            let Ok(text) = fs::read_to_string(&log_path) else {
+                continue;
+            };
+            for (line_number, line) in fixture_lines(&text) {
+                let expected = parse(line).map(|event| snapshot(line_number, &event));
+                let actual = parser::parse_line(line).map(|event| snapshot(line_number, &event));
+                assert_eq!(
+                    actual,
+                    expected,
+                    "{}:{}: parse_line picked another parser",
+                    log_path.display(),
+                    line_number
+                );
+                let actual = profile
+                    .parse_line(line)
+                    .map(|event| snapshot(line_number, &event));
+                assert_eq!(
+                    actual,
+                    expected,
+                    "{}:{}: profile {} parses it differently",
+                    log_path.display(),
+                    line_number,
+                    profile.name
+                );
+            }
+            let lines: Vec<&str> = fixture_lines(&text).map(|(_, line)| line).collect();
+            let log = format!("{}\n{}", header, lines.join("\n"));
+            let parsed =
+                parser::parse_reader(log.as_bytes(), &PatternSet::default()).expect("reads");
+            assert_eq!(parsed.events.len(), lines.len(), "{}", log_path.display());
+            assert_eq!(
+                parsed.health.unmatched(),
+                0,
+                "{}: lines left unmatched",
+                log_path.display()
+            );
+        }
+    }
+}
+
+/// Each header picks the profile of its patch, and a kill line that leaves out the zone and
+/// the weapon still parses behind a 3.x header through the lenient fallback.
+#[test]
+fn kill_lines_parse_under_every_profile() {
+    let old_dir = fixtures_dir().join("3.24");
+    let new_dir = fixtures_dir().join("4.0");
+    let old_text = fs::read_to_string(old_dir.join("actor_death.log")).expect("3.24 fixture");
+    let new_text = fs::read_to_string(new_dir.join("actor_death.log")).expect("4.0 fixture");
+    let (_, old_line) = fixture_lines(&old_text).next().expect("a 3.24 kill line");
+    let (_, new_line) = fixture_lines(&new_text)
+        .find(|(_, line)| !line.contains(" in zone "))
+        .expect("a 4.0 kill line without a zone");
+
+    let parse_behind = |dir: &Path, line: &str| {
+        let log = format!("{}\n{}", read_header(dir), line);
+        parser::parse_reader(log.as_bytes(), &PatternSet::default()).expect("reads")
+    };
+
+    let old = parse_behind(&old_dir, old_line);
+    assert_eq!(ParserProfile::select(&old.metadata.build).name, "alpha-3");
+    assert_eq!(old.events.len(), 1, "3.24 line behind the 3.24 header");
+    assert!(parser::parse_actor_death_v3(old_line).is_some());
+
+    let new = parse_behind(&new_dir, new_line);
+    assert_eq!(ParserProfile::select(&new.metadata.build).name, "alpha-4");
+    assert_eq!(new.events.len(), 1, "4.0 line behind the 4.0 header");
+
+    assert!(parser::parse_actor_death_v3(new_line).is_none());
+    let mismatched = parse_behind(&old_dir, new_line);
+    assert_eq!(mismatched.events.len(), 1, "4.0 line behind the 3.24 header");
+    assert_eq!(mismatched.health.unmatched(), 0);
+}
+
+/// A complete log whose last line has no newline, like a backup the game stopped writing
+/// mid-line, gives `LogTail` the same events as `parse_reader` once it is finished.
+#[test]
+fn finished_tail_keeps_the_last_line_without_newline() {
+    for dir in patch_dirs() {
+        let header = read_header(&dir);
+        for (name, _) in PARSERS {
+            let log_path = dir.join(format!("{}.log", name));
+            let Ok(text) = fs::read_to_string(&log_path) else {
+                continue;
+            };
+            let lines: Vec<&str> = fixture_lines(&text).map(|(_, line)| line).collect();
+            let log = format!("{}\n{}", header, lines.join("\n"));
+            let expected =
+                parser::parse_reader(log.as_bytes(), &PatternSet::default()).expect("reads");
+
+            let path = env::temp_dir().join(format!(
+                "sc_log_analyzer-{}-{}-{}.log",
+                std::process::id(),
+                dir.file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or_default(),
+                name
+            ));
+            fs::write(&path, &log).expect("temp log is writable");
+            let mut tail = LogTail::new(&path, PatternSet::default());
+            let mut events = tail.read_appended().expect("reads").events;
+            let unfinished = events.len();
+            events.extend(tail.finish().events);
+            let _ = fs::remove_file(&path);
+
+            assert_eq!(
+                unfinished,
+                lines.len() - 1,
+                "{}: the last line is held back",
+                log_path.display()
+            );
+            let mut actual = Vec::new();
+            merge_newest_first(&mut actual, events);
+            let snapshots = |events: &[PlayerEvent]| -> Vec<Value> {
+                events.iter().map(|event| snapshot(0, event)).collect()
+            };
+            assert_eq!(
+                snapshots(&actual),
+                snapshots(&expected.events),
+                "{}",
+                log_path.display()
+            );
+        }
+    }
+}
+
+fn first_difference(expected: &str, actual: &str) -> String {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let index = expected_lines
+        .iter()
+        .zip(&actual_lines)
+        .position(|(expected, actual)| expected != actual)
+        .unwrap_or(expected_lines.len().min(actual_lines.len()));
+    format!(
+        "  line {}\n  expected: {}\n  actual:   {}",
+        index + 1,
+        expected_lines.get(index).unwrap_or(&"<end of file>"),
+        actual_lines.get(index).unwrap_or(&"<end of file>")
+    )
+}